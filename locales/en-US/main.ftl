@@ -31,6 +31,34 @@ help-title = Bot Help Menu
 
 config-general-header = ⚙️ **General Configuration**
 config-log-channel-label = **General Log Channel**
+config-log-webhooks-label = **Webhook Delivery**
+    Send logs through a bot-managed webhook in each log channel. Falls back to regular messages if the webhook cannot be used.
+config-logtpl-label = **Log Templates**
+config-logtpl-open-btn = Customize
+config-logtpl-header = Log Templates
+config-logtpl-desc = Change the texts, fields and colors of log messages posted to Discord. Templates use Fluent syntax, for example `{ "{ $userId }" }`. Exported logs keep the original texts.
+config-logtpl-colors-label = Level Colors (* = default)
+config-logtpl-edit-btn = Edit
+config-logtpl-reset-btn = Reset to Default
+config-logtpl-group-placeholder = Select a category...
+config-logtpl-type-placeholder = Select a log type...
+config-logtpl-group-protection = Protection Modules
+config-logtpl-group-moderation = Moderation & Punishments
+config-logtpl-group-logging = Logging Module
+config-logtpl-level-info = Info
+config-logtpl-level-warn = Warning
+config-logtpl-level-error = Error
+config-logtpl-level-audit = Audit
+config-logtpl-variables = **Available variables:** { $variables }
+config-logtpl-fields-label = **Shown Fields**
+config-logtpl-fields-placeholder = No fields shown
+config-logtpl-modal-edit-title = Edit Log Texts
+config-logtpl-modal-desc-label = Description
+config-logtpl-modal-colors-title = Edit Level Colors
+config-logtpl-modal-color-desc = Hex color such as #3498db. Leave empty for the default.
+config-logtpl-error-syntax = ❌ The template for `{ $key }` is not valid Fluent syntax. Nothing was saved.
+config-logtpl-error-variable = ❌ The template for `{ $key }` uses `{ $variable }`, which this log type does not provide. Nothing was saved.
+config-logtpl-error-color = ❌ `{ $value }` is not a valid hex color. Nothing was saved.
 config-jail-role-label = **Jail Role**
 config-modules-header = 📦 **Modules**
 config-select-log-channel-placeholder = Select general log channel...
@@ -82,11 +110,13 @@ config-cp-punish-create = Create
 config-cp-punish-update = Update
 config-cp-punish-delete = Delete
 config-cp-punish-when-placeholder = When to punish?
+config-cp-require-approval-label = Hold Category Deletions by Admins for Head Approval
 config-cpp-ignore-private-label = Ignore Private Channels
 config-cpp-punish-create = Create
 config-cpp-punish-update = Update
 config-cpp-punish-delete = Delete
 config-cpp-punish-when-placeholder = When to punish?
+config-rpp-require-approval-label = Hold ADMINISTRATOR Grants by Admins for Head Approval
 
 config-rp-punish-create = Create
 config-rp-punish-update = Update
@@ -96,8 +126,15 @@ config-rp-punish-when-placeholder = When to punish?
 config-rpp-punish-update = Update
 config-rpp-punish-when-placeholder = When to punish?
 
+config-mp-punish-ban = Ban
+config-mp-punish-kick = Kick
+config-mp-punish-prune = Prune
+config-mp-punish-timeout = Timeout
+config-mp-punish-when-placeholder = When to punish?
+
 config-bap-punish-add = Bot Added
 config-bap-punish-when-placeholder = When to punish?
+config-bap-punish-adder-label = Also Punish Whoever Added a Bot That Violates a Protection
 
 config-whitelists-btn = Whitelists
 config-whitelists-view-btn = View
@@ -187,6 +224,7 @@ log-status-revert-failed = \n❌ **Revert Failed**\n> Failed to roll back change
 log-status-skipped = \n🛡️ **Protection Skipped**\n> User has **{$level}** level permissions, bypassing **{$punishment}** punishment.
 log-status-no-revert = \nℹ️ **Nothing to Revert**\n> No revertible changes were found in the audit log entry.
 log-status-not-enabled = ℹ️ **Protection not enabled**\n> This protection is currently disabled in the module configuration.
+log-status-held = \n⏸️ **Held for Approval**\n> The change was undone and is waiting for a Head-level member to approve or reject it.
 
 log-field-acting-user = Acting User
 log-field-target-member = Target Member
@@ -194,6 +232,8 @@ log-field-added-perms = Added Perms
 log-field-action-status = Status
 log-field-role = Role
 log-field-channel = Channel
+log-field-channel-id = Channel ID
+log-field-role-id = Role ID
 log-field-user = User
 log-field-reason = Reason
 log-field-duration = Duration
@@ -201,6 +241,11 @@ log-field-type = Type
 log-val-permanent = Permanent
 log-val-no-reason = No reason provided
 log-val-temp-ban-expired = Temporary ban expired
+log-val-unknown = Unknown
+log-val-none = *None*
+log-field-before = Before
+log-field-after = After
+log-field-until = Until
 
 log-member-perm-title-whitelisted = Permission Sharing (Whitelisted)
 log-member-perm-title-blocked = Permission Sharing (Blocked)
@@ -253,6 +298,9 @@ log-bot-add-title-blocked = Bot Added (Blocked)
 log-bot-add-desc = Bot <@{$botId}> (`{$botId}`) was added by <@{$userId}>.
 log-bot-add-reason = Unauthorized Bot Added
 log-bot-add-revert-reason = Bot Adding Protection Revert
+log-bot-adder-title = Bot Adder Held Responsible
+log-bot-adder-desc = Bot <@{$botId}> (`{$botId}`) violated `{$module}`. <@{$userId}> added it and received a Bot Adding Protection violation as well.
+log-bot-adder-reason = A bot you added violated {$module}
 
 status-title = 📊 Bot Status
 status-modules = 📦 Enabled Modules
@@ -262,6 +310,9 @@ status-system = 🖥️ System Resources
 status-no-guild = ❌ Not in a guild.
 status-refresh-btn = Refresh
 status-metrics = Metrics
+status-module-latency = Module Latency
+status-module-latency-entry = **{$module}**: avg `{$average} ms`, max `{$max} ms` ({$handled} events)
+status-module-missing-permissions = ⚠️ Missing permissions: {$permissions}
 
 mod-error-invalid-duration = Invalid duration format! Use something like 1d, 1h, or 10m30s.
 mod-ban-success-temp = ✅ Banned <@{$userId}> for {$duration} (Reason: {$reason})
@@ -306,16 +357,41 @@ log-mod-audit-desc = User <@{$userId}> performed a `{$action}` on <@{$targetId}>
 config-log-msg-label = Message Logging
 config-log-voice-label = Voice Logging
 config-log-member-label = Member Logging
+config-log-archive-label = Attachment Archive
 config-log-channels-header = Per-type Log Channels (Optional)
 config-log-msg-channel-placeholder = Select message log channel...
 config-log-voice-channel-placeholder = Select voice log channel...
 config-log-member-channel-placeholder = Select member log channel...
+config-log-archive-channels-placeholder = Select channels to archive attachments from...
+config-log-ignore-bots-label = **Ignore Bots**
+    Skip messages, voice changes and joins/leaves of bot accounts.
+config-log-ignore-channels-label = **Ignored Channels:** Message and voice events here are not logged (threads follow their parent channel).
+config-log-ignore-channels-placeholder = Select channels to ignore...
+config-log-ignore-categories-label = **Ignored Categories:** Every channel in these categories is ignored.
+config-log-ignore-categories-placeholder = Select categories to ignore...
+config-log-ignore-users-label = **Ignored Users:**
+config-log-ignore-users-placeholder = Select users to ignore...
+config-log-ignore-roles-label = **Ignored Roles:** Members with any of these roles are ignored.
+config-log-ignore-roles-placeholder = Select roles to ignore...
 config-log-toggles-header = **Log Toggles**
 config-page-general = General
 config-page-channels = Channels
+config-page-ignore = Ignore Lists
 config-log-msg-channel-label = **Message Log Channel**
 config-log-voice-channel-label = **Voice Log Channel**
 config-log-member-channel-label = **Member Log Channel**
+config-log-archive-channels-label = **Attachment Archive Channels**
+config-log-member-update-label = Member Updates
+config-log-member-update-events-label = **Member Update Events**
+config-log-member-nick-label = Nicknames
+config-log-member-avatar-label = Server Avatars
+config-log-member-roles-label = Roles
+config-log-member-timeout-label = Timeouts
+config-log-member-update-channel-label = **Member Update Log Channel**
+config-log-member-update-channel-placeholder = Select member update log channel...
+config-log-server-label = Server Log
+config-log-server-channel-label = **Server Log Channel**
+config-log-server-channel-placeholder = Select server log channel...
 
 # Logging Events
 log-msg-delete-title = Message Deleted
@@ -324,6 +400,7 @@ log-msg-delete-desc = { $userId ->
     *[other] A message by <@{$userId}> was deleted in <#{$channelId}>
 }
 log-msg-delete-content = Content
+log-msg-delete-attachments = Archived Attachments
 log-msg-edit-title = Message Edited
 log-msg-edit-desc = <@{$userId}> edited a message in <#{$channelId}>
 log-msg-edit-before = Before
@@ -336,11 +413,63 @@ log-voice-move-title = Voice Move
 log-voice-move-desc = <@{$userId}> moved from <#{$oldChannelId}> to <#{$newChannelId}>
 log-voice-state-title = Voice State Change
 log-voice-state-desc = <@{$userId}> changed state: {$state}
+voice-not-enabled = Voice logging is not enabled on this server.
+voice-no-data = No voice activity recorded for this period.
+voice-period-day = last 24 hours
+voice-period-week = last 7 days
+voice-period-month = last 30 days
+voice-period-all = all time
+voice-stats-title = 🎙️ **Voice Statistics for {$user}** ({$period})
+voice-stats-summary = ⏱️ Total Time: **{$total}** over **{$sessions}** session(s)
+    🔇 Self Muted: **{$selfMuted}**
+    🎧 Self Deafened: **{$selfDeafened}**
+    🛑 Server Muted: **{$serverMuted}**
+    🚫 Server Deafened: **{$serverDeafened}**
+voice-stats-channels = **Time per Channel:**
+voice-top-title = 🏆 **Most Active in Voice** ({$period})
 log-member-join-title = Member Joined
 log-member-join-desc = <@{$userId}> joined the server
 log-member-leave-title = Member Left
 log-member-leave-desc = <@{$userId}> left the server
 log-member-leave-roles = Roles
+log-member-nick-title = Nickname Changed
+log-member-nick-desc = {$executor} changed the nickname of <@{$userId}>
+log-member-avatar-title = Server Avatar Changed
+log-member-avatar-desc = <@{$userId}> changed their server avatar
+log-member-roles-title = Member Roles Updated
+log-member-roles-desc = {$executor} updated the roles of <@{$userId}>
+log-member-roles-added = Added Roles
+log-member-roles-removed = Removed Roles
+log-member-timeout-title = Member Timed Out
+log-member-timeout-desc = {$executor} timed out <@{$userId}>
+log-member-timeout-removed-title = Timeout Removed
+log-member-timeout-removed-desc = {$executor} removed the timeout of <@{$userId}>
+log-server-title = {$target} {$action}
+log-server-desc = {$executor} changed {$target}
+log-server-changes = Changes
+log-server-target-guild = Server Settings
+log-server-target-channel = Channel
+log-server-target-overwrite = Channel Permissions
+log-server-target-role = Role
+log-server-target-emoji = Emoji
+log-server-target-invite = Invite
+log-server-action-create = Created
+log-server-action-update = Updated
+log-server-action-delete = Deleted
+log-channel-recovered-title = Log Channel Recreated
+log-channel-recovered-desc = The log channel `{$oldChannelId}` was deleted. This private channel was created to replace it and all log settings now point here. Logs that failed in the meantime follow below.
+log-whitelist-expired-title = Whitelist Entry Expired
+log-whitelist-expired-desc = The temporary whitelist entry of {$target} ({$level}) expired and was removed.
+log-unattributed-title = Unattributed Destructive Action
+log-unattributed-desc = **{$target}**: {$type}. No audit log entry for it appeared within {$seconds} seconds, so who did it is unknown and nothing was punished or reverted. Check the audit log and the members who can perform this action.
+log-unattributed-type-channel-delete = Channel Deleted
+log-unattributed-type-role-delete = Role Deleted
+log-unattributed-type-ban-add = Member Banned
+log-whitelist-change-title-create = 🚨 Whitelist Entry Added
+log-whitelist-change-title-update = 🚨 Whitelist Entry Changed
+log-whitelist-change-title-delete = 🚨 Whitelist Entry Removed
+log-whitelist-change-desc = {$actor} changed the whitelist entry of {$target}.
+whitelist-change-dm = {$actor} changed the whitelist entry of {$target} in **{$guild}**. If this was not expected, review your whitelist right away.
 
 # Whitelist Modals
 config-whitelist-modal-user-title-new = Add Whitelisted User
@@ -353,6 +482,74 @@ config-whitelist-modal-role-label = Role
 config-whitelist-modal-role-description = Select the role to whitelist
 config-whitelist-modal-level-label = Permission Level
 config-whitelist-modal-level-description = Select the whitelist permission level
+config-whitelist-modal-actions-label = Actions
+config-whitelist-modal-actions-description = Leave empty to cover every action
+config-whitelist-actions = Only: { $actions }
+config-whitelist-action-create = Create
+config-whitelist-action-update = Update
+config-whitelist-action-delete = Delete
+config-whitelist-action-add = Bot Add
+config-whitelist-action-ban = Ban
+config-whitelist-action-kick = Kick
+config-whitelist-action-prune = Prune
+config-whitelist-action-timeout = Timeout
+config-whitelist-action-unban = Unban
+config-whitelist-modal-duration-label = Duration
+config-whitelist-modal-duration-description = Leave empty for a permanent entry
+config-whitelist-modal-duration-placeholder = e.g. 2h, 1d12h, 30m
+config-whitelist-expires = Expires { $time }
+config-whitelist-invalid-duration = ❌ `{ $input }` is not a valid duration. Use formats like `2h`, `1d12h` or `30m`. Nothing was saved.
+config-whitelist-owner-dm-label = **Notify Owner**
+    Send the server owner a DM for every whitelist change
+config-whitelist-confirm-head-title = ## ⚠️ Confirm Head Grant
+config-whitelist-confirm-head-desc = {$target} will be able to manage the whitelist and bypass every protection. Only confirm if you trust them completely.
+config-whitelist-confirm-head-btn = Grant Head
+config-whitelist-cancel-btn = Cancel
+config-whitelist-confirm-expired = This confirmation has expired. Nothing was saved.
+
+# Approvals
+approval-request-title = ## ⏸️ Approval Required
+approval-request-desc = {$actor} made a change that needs Head approval: {$change}. It has been undone until someone decides.
+approval-change-category-delete = deleted the category **{$name}**
+approval-change-role-permissions = granted ADMINISTRATOR to {$role}
+approval-approve-btn = Approve
+approval-reject-btn = Reject
+approval-already-decided = This request has already been decided.
+approval-not-allowed = Only Head-level members other than the one who made the change can decide on this request.
+approval-apply-reason = Change approved by a Head-level member
+approval-reject-reason = Change rejected by a Head-level member
+approval-status-approved = ✅ Approved by {$user}. The change has been made again.
+approval-status-apply-failed = ⚠️ Approved by {$user}, but the change could not be made again. Check bot permissions and approve again.
+approval-status-rejected = ❌ Rejected by {$user}. The actor received **{$punishment}** punishment.
+log-approval-title-approved = ✅ Held Change Approved
+log-approval-title-rejected = ❌ Held Change Rejected
+log-approval-desc = A change by {$actor} was decided: {$change}.
+
+# Whitelist commands
+whitelist-cmd-not-allowed = Only Head-level members can change the whitelist.
+whitelist-cmd-no-targets = No users or roles were found in `{ $input }`. Use mentions or IDs.
+whitelist-cmd-module-unavailable = The `{ $module }` module is not included in this build of the bot.
+whitelist-cmd-added-title = ## ✅ Whitelist Updated
+whitelist-cmd-held = waiting for your confirmation
+whitelist-cmd-removed-title = ## 🗑️ Whitelist Entries Removed
+whitelist-cmd-not-found = no matching entry
+whitelist-cmd-list-title = ## 📋 Whitelist
+whitelist-cmd-list-empty = The whitelist is empty.
+whitelist-cmd-list-more = …and { $count } more. Use `/whitelist export` for the full list.
+whitelist-cmd-export-done = Exported { $count } whitelist entries.
+whitelist-cmd-import-invalid = The file is not a valid whitelist export.
+whitelist-cmd-import-done = Imported { $applied } entries. { $held } Head grants are waiting for your confirmation and { $skipped } entries were skipped.
+whitelist-cmd-import-skipped-role = Role { $role } was skipped, this server has no role with its ID or name.
+whitelist-cmd-import-skipped-module = { $target } was skipped, the `{ $module }` module is not available in this build.
+whitelist-cmd-import-skipped-expired = { $target } was skipped, the entry has expired.
+whitelist-check-title = ## 🔍 Whitelist Check
+    Effective whitelist level of { $user } in each module:
+whitelist-check-none = not whitelisted
+whitelist-source-owner = server owner
+whitelist-source-hierarchy = highest role is above the bot
+whitelist-source-administrator = above the bot with Administrator
+whitelist-source-user = user entry ({ $scope })
+whitelist-source-role = role entry { $role } ({ $scope })
 config-whitelist-modal-level-head = Head (Full Immunity)
 config-whitelist-modal-level-admin = Admin (Bypass Punishment)
 config-whitelist-modal-level-invulnerable = Invulnerable (Cannot be Punished)
@@ -28,6 +28,34 @@ help-title = Bot Yardım Menüsü
 
 config-general-header = ⚙️ **Genel Yapılandırma**
 config-log-channel-label = **Genel Kayıt Kanalı**
+config-log-webhooks-label = **Webhook ile Gönderim**
+    Logları her log kanalında botun yönettiği bir webhook üzerinden gönder. Webhook kullanılamazsa normal mesajlara geri döner.
+config-logtpl-label = **Log Şablonları**
+config-logtpl-open-btn = Özelleştir
+config-logtpl-header = Log Şablonları
+config-logtpl-desc = Discord'a gönderilen log mesajlarının metinlerini, alanlarını ve renklerini değiştirin. Şablonlar Fluent sözdizimini kullanır, örneğin `{ "{ $userId }" }`. Dışa aktarılan loglar orijinal metinleri korur.
+config-logtpl-colors-label = Seviye Renkleri (* = varsayılan)
+config-logtpl-edit-btn = Düzenle
+config-logtpl-reset-btn = Varsayılana Sıfırla
+config-logtpl-group-placeholder = Bir kategori seçin...
+config-logtpl-type-placeholder = Bir log türü seçin...
+config-logtpl-group-protection = Koruma Modülleri
+config-logtpl-group-moderation = Moderasyon ve Cezalar
+config-logtpl-group-logging = Loglama Modülü
+config-logtpl-level-info = Bilgi
+config-logtpl-level-warn = Uyarı
+config-logtpl-level-error = Hata
+config-logtpl-level-audit = Denetim
+config-logtpl-variables = **Kullanılabilir değişkenler:** { $variables }
+config-logtpl-fields-label = **Gösterilen Alanlar**
+config-logtpl-fields-placeholder = Hiçbir alan gösterilmiyor
+config-logtpl-modal-edit-title = Log Metinlerini Düzenle
+config-logtpl-modal-desc-label = Açıklama
+config-logtpl-modal-colors-title = Seviye Renklerini Düzenle
+config-logtpl-modal-color-desc = #3498db gibi bir hex renk. Varsayılan için boş bırakın.
+config-logtpl-error-syntax = ❌ `{ $key }` şablonu geçerli bir Fluent sözdizimi değil. Hiçbir şey kaydedilmedi.
+config-logtpl-error-variable = ❌ `{ $key }` şablonu bu log türünün sağlamadığı `{ $variable }` değişkenini kullanıyor. Hiçbir şey kaydedilmedi.
+config-logtpl-error-color = ❌ `{ $value }` geçerli bir hex renk değil. Hiçbir şey kaydedilmedi.
 config-jail-role-label = **Hapis Rolü**
 config-modules-header = 📦 **Modüller**
 config-select-log-channel-placeholder = Genel kayıt kanalını seçin...
@@ -77,11 +105,14 @@ config-cp-punish-create = Oluşturma
 config-cp-punish-update = Güncelleme
 config-cp-punish-delete = Silme
 config-cp-punish-when-placeholder = Ne zaman cezalandırılsın?
+config-cp-require-approval-label = Yöneticilerin Kategori Silmelerini Baş Onayına Tut
 config-cpp-ignore-private-label = Özel Kanalları Yoksay
 config-cpp-punish-create = Oluşturma
 config-cpp-punish-update = Güncelleme
 config-cpp-punish-delete = Silme
 config-cpp-punish-when-placeholder = Ne zaman cezalandırılsın?
+config-rpp-require-approval-label = Yöneticilerin YÖNETİCİ Yetkisi Vermelerini Baş Onayına Tut
+config-bap-punish-adder-label = Bir Korumayı İhlal Eden Botu Ekleyeni de Cezalandır
 
 config-rp-punish-create = Oluşturma
 config-rp-punish-update = Güncelleme
@@ -91,6 +122,12 @@ config-rp-punish-when-placeholder = Ne zaman cezalandırılsın?
 config-rpp-punish-update = Güncelleme
 config-rpp-punish-when-placeholder = Ne zaman cezalandırılsın?
 
+config-mp-punish-ban = Yasaklama
+config-mp-punish-kick = Atma
+config-mp-punish-prune = Üye Temizleme
+config-mp-punish-timeout = Zaman Aşımı
+config-mp-punish-when-placeholder = Ne zaman cezalandırılsın?
+
 config-whitelists-btn = Beyaz Listeler
 config-whitelists-view-btn = Görüntüle
 config-whitelist-manage-btn = Yönet
@@ -178,6 +215,7 @@ log-status-revert-failed = \n❌ **Geri Alma Başarısız**\n> Değişiklikler g
 log-status-skipped = \n🛡️ **Koruma Atlandı**\n> Kullanıcı **{$level}** seviye yetkiye sahip, **{$punishment}** cezası uygulanmadı.
 log-status-no-revert = \nℹ️ **Geri Alınacak Bir Şey Yok**\n> Denetim kaydı girişinde geri alınabilir bir değişiklik bulunamadı.
 log-status-not-enabled = ℹ️ **Koruma etkin değil**\n> Bu koruma şu anda modül yapılandırmasında devre dışı.
+log-status-held = \n⏸️ **Onay Bekliyor**\n> Değişiklik geri alındı ve Baş seviyesindeki bir üyenin onaylamasını veya reddetmesini bekliyor.
 
 log-field-acting-user = Gerçekleştiren Kullanıcı
 log-field-target-member = Hedef Üye
@@ -186,6 +224,7 @@ log-field-action-status = Eylem Durumu
 log-field-role-id = Rol ID
 log-field-role = Rol
 log-field-channel = Kanal
+log-field-channel-id = Kanal ID
 log-field-user = Kullanıcı
 log-field-reason = Sebep
 log-field-duration = Süre
@@ -193,6 +232,11 @@ log-field-type = Tür
 log-val-permanent = Kalıcı
 log-val-no-reason = Sebep belirtilmedi
 log-val-temp-ban-expired = Süreli yasaklama sona erdi
+log-val-unknown = Bilinmiyor
+log-val-none = *Yok*
+log-field-before = Önce
+log-field-after = Sonra
+log-field-until = Bitiş
 
 log-member-perm-title-whitelisted = Yetki Paylaşımı (Beyaz Listede)
 log-member-perm-title-blocked = Yetki Paylaşımı (Engellendi)
@@ -245,6 +289,9 @@ log-bot-add-title-blocked = Bot Eklendi (Engellendi)
 log-bot-add-desc = <@{$botId}> (`{$botId}`) botu <@{$userId}> tarafından eklendi.
 log-bot-add-reason = Yetkisiz Bot Ekleme
 log-bot-add-revert-reason = Bot Ekleme Koruması Geri Alma
+log-bot-adder-title = Bot Ekleyen Sorumlu Tutuldu
+log-bot-adder-desc = <@{$botId}> (`{$botId}`) botu `{$module}` korumasını ihlal etti. Botu ekleyen <@{$userId}> de Bot Ekleme Koruması ihlali aldı.
+log-bot-adder-reason = Eklediğiniz bir bot {$module} korumasını ihlal etti
 
 status-title = 📊 Bot Durumu
 status-modules = 📦 Aktif Modüller
@@ -254,6 +301,9 @@ status-system = 🖥️ Sistem Kaynakları
 status-no-guild = ❌ Sunucuda değil.
 status-refresh-btn = Yenile
 status-metrics = İstatistikler
+status-module-latency = Modül Gecikmesi
+status-module-latency-entry = **{$module}**: ort. `{$average} ms`, en fazla `{$max} ms` ({$handled} olay)
+status-module-missing-permissions = ⚠️ Eksik yetkiler: {$permissions}
 
 mod-error-invalid-duration = Geçersiz süre formatı! 1d, 1h veya 10m30s gibi bir format kullanın.
 mod-ban-success-temp = ✅ <@{$userId}> kullanıcısı {$duration} süreliğine yasaklandı (Sebep: {$reason})
@@ -298,16 +348,41 @@ log-mod-audit-desc = <@{$userId}> kullanıcısı, <@{$targetId}> üzerinde `{$ac
 config-log-msg-label = Mesaj Kayıtları
 config-log-voice-label = Ses Kayıtları
 config-log-member-label = Üye Kayıtları
+config-log-archive-label = Ek Arşivi
 config-log-channels-header = Tür Bazlı Kayıt Kanalları (İsteğe Bağlı)
 config-log-msg-channel-placeholder = Mesaj kayıt kanalı seçin...
 config-log-voice-channel-placeholder = Ses kayıt kanalı seçin...
 config-log-member-channel-placeholder = Üye kayıt kanalı seçin...
+config-log-archive-channels-placeholder = Eklerin arşivleneceği kanalları seçin...
+config-log-ignore-bots-label = **Botları Yok Say**
+    Bot hesaplarının mesajları, ses değişiklikleri ve giriş/çıkışları loglanmaz.
+config-log-ignore-channels-label = **Yok Sayılan Kanallar:** Buradaki mesaj ve ses olayları loglanmaz (alt başlıklar üst kanalı takip eder).
+config-log-ignore-channels-placeholder = Yok sayılacak kanalları seçin...
+config-log-ignore-categories-label = **Yok Sayılan Kategoriler:** Bu kategorilerdeki tüm kanallar yok sayılır.
+config-log-ignore-categories-placeholder = Yok sayılacak kategorileri seçin...
+config-log-ignore-users-label = **Yok Sayılan Kullanıcılar:**
+config-log-ignore-users-placeholder = Yok sayılacak kullanıcıları seçin...
+config-log-ignore-roles-label = **Yok Sayılan Roller:** Bu rollerden birine sahip üyeler yok sayılır.
+config-log-ignore-roles-placeholder = Yok sayılacak rolleri seçin...
 config-log-toggles-header = **Kayıt Ayarları**
 config-page-general = Genel
 config-page-channels = Kanallar
+config-page-ignore = Yok Sayılanlar
 config-log-msg-channel-label = **Mesaj Kayıt Kanalı**
 config-log-voice-channel-label = **Ses Kayıt Kanalı**
 config-log-member-channel-label = **Üye Kayıt Kanalı**
+config-log-archive-channels-label = **Ek Arşivi Kanalları**
+config-log-member-update-label = Üye Güncellemeleri
+config-log-member-update-events-label = **Üye Güncelleme Olayları**
+config-log-member-nick-label = Takma Adlar
+config-log-member-avatar-label = Sunucu Avatarları
+config-log-member-roles-label = Roller
+config-log-member-timeout-label = Zaman Aşımları
+config-log-member-update-channel-label = **Üye Güncelleme Log Kanalı**
+config-log-member-update-channel-placeholder = Üye güncelleme log kanalını seçin...
+config-log-server-label = Sunucu Logu
+config-log-server-channel-label = **Sunucu Log Kanalı**
+config-log-server-channel-placeholder = Sunucu log kanalını seçin...
 
 # Kayıt Olayları
 log-msg-delete-title = Mesaj Silindi
@@ -316,6 +391,7 @@ log-msg-delete-desc = { $userId ->
     *[other] <@{$userId}> kullanıcısının <#{$channelId}> kanalındaki mesajı silindi
 }
 log-msg-delete-content = İçerik
+log-msg-delete-attachments = Arşivlenen Ekler
 log-msg-edit-title = Mesaj Düzenlendi
 log-msg-edit-desc = <@{$userId}>, <#{$channelId}> kanalında bir mesajı düzenledi
 log-msg-edit-before = Önceki
@@ -328,11 +404,63 @@ log-voice-move-title = Ses Kanalı Değişikliği
 log-voice-move-desc = <@{$userId}>, <#{$oldChannelId}> kanalından <#{$newChannelId}> kanalına geçti
 log-voice-state-title = Ses Durumu Değişikliği
 log-voice-state-desc = <@{$userId}> durumunu değiştirdi: {$state}
+voice-not-enabled = Bu sunucuda ses logları etkin değil.
+voice-no-data = Bu dönem için kaydedilmiş ses etkinliği yok.
+voice-period-day = son 24 saat
+voice-period-week = son 7 gün
+voice-period-month = son 30 gün
+voice-period-all = tüm zamanlar
+voice-stats-title = 🎙️ **{$user} için Ses İstatistikleri** ({$period})
+voice-stats-summary = ⏱️ Toplam Süre: **{$total}**, **{$sessions}** oturum
+    🔇 Kendini Susturma: **{$selfMuted}**
+    🎧 Kendini Sağırlaştırma: **{$selfDeafened}**
+    🛑 Sunucu Susturması: **{$serverMuted}**
+    🚫 Sunucu Sağırlaştırması: **{$serverDeafened}**
+voice-stats-channels = **Kanal Başına Süre:**
+voice-top-title = 🏆 **Seste En Aktif Olanlar** ({$period})
 log-member-join-title = Üye Katıldı
 log-member-join-desc = <@{$userId}> sunucuya katıldı
 log-member-leave-title = Üye Ayrıldı
 log-member-leave-desc = <@{$userId}> sunucudan ayrıldı
 log-member-leave-roles = Roller
+log-member-nick-title = Takma Ad Değişti
+log-member-nick-desc = {$executor}, <@{$userId}> kullanıcısının takma adını değiştirdi
+log-member-avatar-title = Sunucu Avatarı Değişti
+log-member-avatar-desc = <@{$userId}> sunucu avatarını değiştirdi
+log-member-roles-title = Üye Rolleri Güncellendi
+log-member-roles-desc = {$executor}, <@{$userId}> kullanıcısının rollerini güncelledi
+log-member-roles-added = Eklenen Roller
+log-member-roles-removed = Kaldırılan Roller
+log-member-timeout-title = Üyeye Zaman Aşımı Verildi
+log-member-timeout-desc = {$executor}, <@{$userId}> kullanıcısına zaman aşımı verdi
+log-member-timeout-removed-title = Zaman Aşımı Kaldırıldı
+log-member-timeout-removed-desc = {$executor}, <@{$userId}> kullanıcısının zaman aşımını kaldırdı
+log-server-title = {$target} {$action}
+log-server-desc = {$executor}, {$target} üzerinde değişiklik yaptı
+log-server-changes = Değişiklikler
+log-server-target-guild = Sunucu Ayarları
+log-server-target-channel = Kanal
+log-server-target-overwrite = Kanal İzinleri
+log-server-target-role = Rol
+log-server-target-emoji = Emoji
+log-server-target-invite = Davet
+log-server-action-create = Oluşturuldu
+log-server-action-update = Güncellendi
+log-server-action-delete = Silindi
+log-channel-recovered-title = Log Kanalı Yeniden Oluşturuldu
+log-channel-recovered-desc = `{$oldChannelId}` log kanalı silindi. Yerine bu özel kanal oluşturuldu ve tüm log ayarları artık buraya yönlendirildi. Bu sırada gönderilemeyen loglar aşağıda yer alıyor.
+log-whitelist-expired-title = Beyaz Liste Kaydının Süresi Doldu
+log-whitelist-expired-desc = {$target} için geçici beyaz liste kaydının ({$level}) süresi doldu ve kaldırıldı.
+log-unattributed-title = Kimin Yaptığı Bilinmeyen Yıkıcı İşlem
+log-unattributed-desc = **{$target}**: {$type}. {$seconds} saniye içinde bu işleme ait bir denetim kaydı oluşmadı, bu yüzden kimin yaptığı bilinmiyor ve herhangi bir ceza ya da geri alma uygulanmadı. Denetim kaydını ve bu işlemi yapabilen üyeleri kontrol edin.
+log-unattributed-type-channel-delete = Kanal Silindi
+log-unattributed-type-role-delete = Rol Silindi
+log-unattributed-type-ban-add = Üye Yasaklandı
+log-whitelist-change-title-create = 🚨 Beyaz Liste Kaydı Eklendi
+log-whitelist-change-title-update = 🚨 Beyaz Liste Kaydı Değiştirildi
+log-whitelist-change-title-delete = 🚨 Beyaz Liste Kaydı Kaldırıldı
+log-whitelist-change-desc = {$actor}, {$target} için beyaz liste kaydını değiştirdi.
+whitelist-change-dm = {$actor}, **{$guild}** sunucusunda {$target} için beyaz liste kaydını değiştirdi. Bu beklenmiyorsa beyaz listenizi hemen gözden geçirin.
 
 # Beyaz Liste Modalleri
 config-whitelist-modal-user-title-new = Beyaz Listeye Kullanıcı Ekle
@@ -345,6 +473,74 @@ config-whitelist-modal-role-label = Rol
 config-whitelist-modal-role-description = Beyaz listeye eklenecek rolü seçin
 config-whitelist-modal-level-label = Yetki Seviyesi
 config-whitelist-modal-level-description = Beyaz liste yetki seviyesini seçin
+config-whitelist-modal-actions-label = Eylemler
+config-whitelist-modal-actions-description = Tüm eylemleri kapsaması için boş bırakın
+config-whitelist-actions = Yalnızca: { $actions }
+config-whitelist-action-create = Oluşturma
+config-whitelist-action-update = Güncelleme
+config-whitelist-action-delete = Silme
+config-whitelist-action-add = Bot Ekleme
+config-whitelist-action-ban = Yasaklama
+config-whitelist-action-kick = Atma
+config-whitelist-action-prune = Üye Temizleme
+config-whitelist-action-timeout = Zaman Aşımı
+config-whitelist-action-unban = Yasak Kaldırma
+config-whitelist-modal-duration-label = Süre
+config-whitelist-modal-duration-description = Kalıcı bir kayıt için boş bırakın
+config-whitelist-modal-duration-placeholder = örn. 2h, 1d12h, 30m
+config-whitelist-expires = Bitiş: { $time }
+config-whitelist-invalid-duration = ❌ `{ $input }` geçerli bir süre değil. `2h`, `1d12h` veya `30m` gibi biçimler kullanın. Hiçbir şey kaydedilmedi.
+config-whitelist-owner-dm-label = **Sahibi Bilgilendir**
+    Her beyaz liste değişikliğinde sunucu sahibine DM gönder
+config-whitelist-confirm-head-title = ## ⚠️ Head Yetkisini Onayla
+config-whitelist-confirm-head-desc = {$target} beyaz listeyi yönetebilecek ve tüm korumaları atlayabilecek. Yalnızca tamamen güveniyorsanız onaylayın.
+config-whitelist-confirm-head-btn = Head Yetkisi Ver
+config-whitelist-cancel-btn = İptal
+config-whitelist-confirm-expired = Bu onayın süresi doldu. Hiçbir şey kaydedilmedi.
+
+# Approvals
+approval-request-title = ## ⏸️ Onay Gerekli
+approval-request-desc = {$actor} Baş onayı gerektiren bir değişiklik yaptı: {$change}. Karar verilene kadar geri alındı.
+approval-change-category-delete = **{$name}** kategorisini sildi
+approval-change-role-permissions = {$role} rolüne YÖNETİCİ yetkisi verdi
+approval-approve-btn = Onayla
+approval-reject-btn = Reddet
+approval-already-decided = Bu istek için zaten karar verildi.
+approval-not-allowed = Bu isteğe yalnızca değişikliği yapan kişi dışındaki Baş seviyesindeki üyeler karar verebilir.
+approval-apply-reason = Değişiklik Baş seviyesindeki bir üye tarafından onaylandı
+approval-reject-reason = Değişiklik Baş seviyesindeki bir üye tarafından reddedildi
+approval-status-approved = ✅ {$user} tarafından onaylandı. Değişiklik yeniden yapıldı.
+approval-status-apply-failed = ⚠️ {$user} tarafından onaylandı, ancak değişiklik yeniden yapılamadı. Bot yetkilerini kontrol edip yeniden onaylayın.
+approval-status-rejected = ❌ {$user} tarafından reddedildi. Eylemi yapan **{$punishment}** cezası aldı.
+log-approval-title-approved = ✅ Bekletilen Değişiklik Onaylandı
+log-approval-title-rejected = ❌ Bekletilen Değişiklik Reddedildi
+log-approval-desc = {$actor} tarafından yapılan bir değişiklik karara bağlandı: {$change}.
+
+# Whitelist commands
+whitelist-cmd-not-allowed = Beyaz listeyi yalnızca Baş seviyesindeki üyeler değiştirebilir.
+whitelist-cmd-no-targets = `{ $input }` içinde kullanıcı veya rol bulunamadı. Etiket veya ID kullanın.
+whitelist-cmd-module-unavailable = `{ $module }` modülü botun bu sürümüne dahil değil.
+whitelist-cmd-added-title = ## ✅ Beyaz Liste Güncellendi
+whitelist-cmd-held = onayınızı bekliyor
+whitelist-cmd-removed-title = ## 🗑️ Beyaz Liste Kayıtları Kaldırıldı
+whitelist-cmd-not-found = eşleşen kayıt yok
+whitelist-cmd-list-title = ## 📋 Beyaz Liste
+whitelist-cmd-list-empty = Beyaz liste boş.
+whitelist-cmd-list-more = …ve { $count } kayıt daha. Tam liste için `/whitelist export` kullanın.
+whitelist-cmd-export-done = { $count } beyaz liste kaydı dışa aktarıldı.
+whitelist-cmd-import-invalid = Dosya geçerli bir beyaz liste dışa aktarımı değil.
+whitelist-cmd-import-done = { $applied } kayıt içe aktarıldı. { $held } Baş yetkisi onayınızı bekliyor ve { $skipped } kayıt atlandı.
+whitelist-cmd-import-skipped-role = { $role } rolü atlandı, bu sunucuda bu ID'ye ya da ada sahip bir rol yok.
+whitelist-cmd-import-skipped-module = { $target } atlandı, `{ $module }` modülü bu sürümde yok.
+whitelist-cmd-import-skipped-expired = { $target } atlandı, kaydın süresi dolmuş.
+whitelist-check-title = ## 🔍 Beyaz Liste Kontrolü
+    { $user } kullanıcısının her modüldeki geçerli beyaz liste seviyesi:
+whitelist-check-none = beyaz listede değil
+whitelist-source-owner = sunucu sahibi
+whitelist-source-hierarchy = en yüksek rolü botun üzerinde
+whitelist-source-administrator = botun üzerinde ve Yönetici yetkisine sahip
+whitelist-source-user = kullanıcı kaydı ({ $scope })
+whitelist-source-role = { $role } rol kaydı ({ $scope })
 config-whitelist-modal-level-head = Yetkili (Tam Bağışıklık)
 config-whitelist-modal-level-admin = Yönetici (Cezayı Baypas Eder)
 config-whitelist-modal-level-invulnerable = Dokunulmaz (Cezalandırılamaz)
@@ -8,6 +8,9 @@ pub struct Model {
     pub guild_id: i64,
     pub log_channel_id: Option<i64>,
     pub jail_role_id: Option<i64>,
+    pub log_webhooks_enabled: bool,
+    /// DM the server owner about every whitelist change
+    pub whitelist_owner_dm: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "log_templates")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub guild_id: i64,
+    /// Description key identifying the log type, see `services::log_templates::LOG_TYPES`
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub log_type: String,
+    /// Object mapping Fluent keys to the guild's template
+    pub overrides: Json,
+    /// Array of field name keys that are not shown
+    pub hidden_fields: Json,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
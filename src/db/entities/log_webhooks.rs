@@ -0,0 +1,17 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "log_webhooks")]
+pub struct Model {
+    /// Log channel the webhook posts to
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub channel_id: i64,
+    pub guild_id: i64,
+    pub webhook_id: i64,
+    pub token: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
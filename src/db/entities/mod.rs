@@ -1,14 +1,25 @@
+pub mod archived_attachments;
+pub mod audit_log_cursors;
+pub mod bot_adders;
 pub mod guild_configs;
 pub mod invite_events;
 pub mod invite_snapshots;
 pub mod invite_stats;
 pub mod jails;
+pub mod log_level_colors;
+pub mod log_templates;
+pub mod log_webhooks;
 pub mod logging_guilds;
 pub mod member_old_roles;
 pub mod module_configs;
+pub mod pending_approvals;
+pub mod pending_logs;
 pub mod prelude;
 pub mod temp_bans;
 pub mod violations;
+pub mod voice_intervals;
+pub mod voice_sessions;
+pub mod whitelist_history;
 pub mod whitelist_role;
 pub mod whitelist_user;
 pub mod whitelists;
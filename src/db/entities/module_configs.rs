@@ -1,3 +1,4 @@
+use sea_orm::Iterable;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -55,6 +56,35 @@ impl std::fmt::Display for ModuleType {
     }
 }
 
+impl ModuleType {
+    /// Whether the module's cargo feature is enabled in this build.
+    /// Every variant exists regardless, so rows of modules left out of the build still
+    /// load; they are just ignored.
+    pub const fn is_available(self) -> bool {
+        match self {
+            ModuleType::ChannelProtection => cfg!(feature = "channel-protection"),
+            ModuleType::ChannelPermissionProtection => {
+                cfg!(feature = "channel-permission-protection")
+            }
+            ModuleType::RoleProtection => cfg!(feature = "role-protection"),
+            ModuleType::RolePermissionProtection => cfg!(feature = "role-permission-protection"),
+            ModuleType::MemberPermissionProtection => {
+                cfg!(feature = "member-permission-protection")
+            }
+            ModuleType::BotAddingProtection => cfg!(feature = "bot-adding-protection"),
+            ModuleType::ModerationProtection => cfg!(feature = "moderation-protection"),
+            ModuleType::Logging => cfg!(feature = "logging"),
+            ModuleType::StickyRoles => cfg!(feature = "sticky-roles"),
+            ModuleType::InviteTracking => cfg!(feature = "invite-tracking"),
+        }
+    }
+
+    /// Iterates over the modules enabled in this build.
+    pub fn available() -> impl Iterator<Item = ModuleType> {
+        ModuleType::iter().filter(|m| m.is_available())
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -109,6 +139,9 @@ pub struct ChannelProtectionModuleConfig {
     pub ignore_private_channels: bool,
     #[serde(default)]
     pub punish_when: Vec<String>,
+    /// Categories deleted by Admin-level members are restored until a Head approves
+    #[serde(default)]
+    pub require_approval: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -129,7 +162,11 @@ pub struct RoleProtectionModuleConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
-pub struct RolePermissionProtectionModuleConfig {}
+pub struct RolePermissionProtectionModuleConfig {
+    /// ADMINISTRATOR granted by Admin-level members is removed until a Head approves
+    #[serde(default)]
+    pub require_approval: bool,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -137,7 +174,11 @@ pub struct MemberPermissionProtectionModuleConfig {}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
-pub struct BotAddingProtectionModuleConfig {}
+pub struct BotAddingProtectionModuleConfig {
+    /// When a bot violates any protection, the user who added it gets a violation too
+    #[serde(default)]
+    pub punish_adder: bool,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -146,7 +187,7 @@ pub struct ModerationProtectionModuleConfig {
     pub punish_when: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LoggingModuleConfig {
     #[serde(default)]
@@ -161,6 +202,64 @@ pub struct LoggingModuleConfig {
     pub voice_log_channel_id: Option<i64>,
     #[serde(default)]
     pub membership_log_channel_id: Option<i64>,
+    #[serde(default)]
+    pub archive_attachments: bool,
+    #[serde(default)]
+    pub archive_channel_ids: Vec<i64>,
+    #[serde(default)]
+    pub log_member_updates: bool,
+    #[serde(default)]
+    pub member_log_channel_id: Option<i64>,
+    #[serde(default = "default_true")]
+    pub log_nickname_changes: bool,
+    #[serde(default = "default_true")]
+    pub log_avatar_changes: bool,
+    #[serde(default = "default_true")]
+    pub log_role_changes: bool,
+    #[serde(default = "default_true")]
+    pub log_timeout_changes: bool,
+    #[serde(default)]
+    pub log_server: bool,
+    #[serde(default)]
+    pub server_log_channel_id: Option<i64>,
+    #[serde(default)]
+    pub ignored_channel_ids: Vec<i64>,
+    #[serde(default)]
+    pub ignored_category_ids: Vec<i64>,
+    #[serde(default)]
+    pub ignored_user_ids: Vec<i64>,
+    #[serde(default)]
+    pub ignored_role_ids: Vec<i64>,
+    #[serde(default)]
+    pub ignore_bots: bool,
+}
+
+impl Default for LoggingModuleConfig {
+    fn default() -> Self {
+        Self {
+            log_messages: false,
+            log_voice: false,
+            log_membership: false,
+            message_log_channel_id: None,
+            voice_log_channel_id: None,
+            membership_log_channel_id: None,
+            archive_attachments: false,
+            archive_channel_ids: vec![],
+            log_member_updates: false,
+            member_log_channel_id: None,
+            log_nickname_changes: true,
+            log_avatar_changes: true,
+            log_role_changes: true,
+            log_timeout_changes: true,
+            log_server: false,
+            server_log_channel_id: None,
+            ignored_channel_ids: vec![],
+            ignored_category_ids: vec![],
+            ignored_user_ids: vec![],
+            ignored_role_ids: vec![],
+            ignore_bots: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -182,6 +281,10 @@ pub struct InviteTrackingModuleConfig {
     pub leaderboard_limit: u32,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 fn default_fake_threshold() -> u32 {
     24
 }
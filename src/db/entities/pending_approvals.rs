@@ -0,0 +1,22 @@
+use super::module_configs::ModuleType;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "pending_approvals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub guild_id: i64,
+    /// Module whose protection held the change; its punishment applies on rejection
+    pub module_type: ModuleType,
+    /// Admin-level member who made the change
+    pub actor_id: i64,
+    /// `HeldChange` describing how to re-apply the change
+    pub change: Json,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
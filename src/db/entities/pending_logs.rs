@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "pending_logs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    /// `LogLevel` key, see `LogLevel::key`
+    pub level: String,
+    pub title: String,
+    pub description: String,
+    /// Array of `[name, value]` pairs
+    pub fields: Json,
+    pub attempts: i32,
+    /// Guild-specific accent color; the level's default is used if missing
+    pub accent_color: Option<i32>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
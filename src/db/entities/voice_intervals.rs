@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(32))")]
+pub enum VoiceIntervalKind {
+    #[sea_orm(string_value = "channel")]
+    Channel,
+    #[sea_orm(string_value = "self_mute")]
+    SelfMute,
+    #[sea_orm(string_value = "self_deaf")]
+    SelfDeaf,
+    #[sea_orm(string_value = "server_mute")]
+    ServerMute,
+    #[sea_orm(string_value = "server_deaf")]
+    ServerDeaf,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "voice_intervals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub session_id: i64,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub kind: VoiceIntervalKind,
+    /// Channel the member was in; for mute/deafen intervals, the channel they started in
+    pub channel_id: Option<i64>,
+    pub started_at: DateTimeWithTimeZone,
+    pub ended_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "whitelist_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub guild_id: i64,
+    /// User who made the change
+    pub actor_id: i64,
+    /// `create`, `update` or `delete`
+    pub action: String,
+    /// `user` or `role`
+    pub target_type: String,
+    pub target_id: i64,
+    /// `EntrySnapshot` of the entry before the change; missing for creations
+    pub before: Option<Json>,
+    /// `EntrySnapshot` of the entry after the change; missing for deletions
+    pub after: Option<Json>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
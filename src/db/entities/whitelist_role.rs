@@ -12,6 +12,10 @@ pub struct Model {
     pub role_id: i64,
     pub level: WhitelistLevel,
     pub module_type: Option<ModuleType>,
+    /// Temporary entries are ignored after this and purged by the expiry runner
+    pub expires_at: Option<DateTimeWithTimeZone>,
+    /// Actions (`punish_when` values) this entry covers; empty means all of them
+    pub actions: Json,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
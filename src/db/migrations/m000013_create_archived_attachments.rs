@@ -0,0 +1,113 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create archived_attachments table to track files stored on local disk
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArchivedAttachments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::ChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::MessageId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::AttachmentId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::Filename)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::FilePath)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::Size)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchivedAttachments::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Lookup by message on delete
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-archived-attachments-message-hash")
+                    .table(ArchivedAttachments::Table)
+                    .col(ArchivedAttachments::MessageId)
+                    .index_type(IndexType::Hash)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Index for faster cleanup
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-archived-attachments-created-at")
+                    .table(ArchivedAttachments::Table)
+                    .col(ArchivedAttachments::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArchivedAttachments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ArchivedAttachments {
+    Table,
+    Id,
+    GuildId,
+    ChannelId,
+    MessageId,
+    AttachmentId,
+    Filename,
+    FilePath,
+    Size,
+    CreatedAt,
+}
@@ -0,0 +1,175 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create voice_sessions table, one row per continuous stay in voice
+        manager
+            .create_table(
+                Table::create()
+                    .table(VoiceSessions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VoiceSessions::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessions::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessions::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceSessions::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VoiceSessions::EndedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-voice-sessions-guild-user")
+                    .table(VoiceSessions::Table)
+                    .col(VoiceSessions::GuildId)
+                    .col(VoiceSessions::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // A member has at most one open session, so concurrent joins cannot open two
+        let db = manager.get_connection();
+        db.execute(Statement::from_string(
+            manager.get_database_backend(),
+            r#"CREATE UNIQUE INDEX IF NOT EXISTS "idx-voice-sessions-open"
+            ON "voice_sessions" ("guild_id", "user_id")
+            WHERE "ended_at" IS NULL;"#,
+        ))
+        .await?;
+
+        // Create voice_intervals table for channel hops and mute/deafen periods
+        manager
+            .create_table(
+                Table::create()
+                    .table(VoiceIntervals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VoiceIntervals::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceIntervals::SessionId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceIntervals::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceIntervals::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceIntervals::Kind)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VoiceIntervals::ChannelId).big_integer())
+                    .col(
+                        ColumnDef::new(VoiceIntervals::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VoiceIntervals::EndedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voice-intervals-session")
+                            .from(VoiceIntervals::Table, VoiceIntervals::SessionId)
+                            .to(VoiceSessions::Table, VoiceSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-voice-intervals-session-hash")
+                    .table(VoiceIntervals::Table)
+                    .col(VoiceIntervals::SessionId)
+                    .index_type(IndexType::Hash)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Index for period statistics
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-voice-intervals-guild-started")
+                    .table(VoiceIntervals::Table)
+                    .col(VoiceIntervals::GuildId)
+                    .col(VoiceIntervals::StartedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(VoiceIntervals::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(VoiceSessions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VoiceSessions {
+    Table,
+    Id,
+    GuildId,
+    UserId,
+    StartedAt,
+    EndedAt,
+}
+
+#[derive(DeriveIden)]
+enum VoiceIntervals {
+    Table,
+    Id,
+    SessionId,
+    GuildId,
+    UserId,
+    Kind,
+    ChannelId,
+    StartedAt,
+    EndedAt,
+}
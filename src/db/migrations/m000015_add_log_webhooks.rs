@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Webhook sink toggle
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GuildConfigs::Table)
+                    .add_column(
+                        ColumnDef::new(GuildConfigs::LogWebhooksEnabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Webhook credentials per log channel, including per-category sub-channels
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogWebhooks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LogWebhooks::ChannelId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LogWebhooks::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LogWebhooks::WebhookId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LogWebhooks::Token).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_log_webhooks_guild_id")
+                    .table(LogWebhooks::Table)
+                    .col(LogWebhooks::GuildId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LogWebhooks::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GuildConfigs::Table)
+                    .drop_column(GuildConfigs::LogWebhooksEnabled)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GuildConfigs {
+    Table,
+    LogWebhooksEnabled,
+}
+
+#[derive(DeriveIden)]
+enum LogWebhooks {
+    Table,
+    ChannelId,
+    GuildId,
+    WebhookId,
+    Token,
+}
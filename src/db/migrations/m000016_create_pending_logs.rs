@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Log entries waiting for delivery, so queued logs survive a crash or restart
+        manager
+            .create_table(
+                Table::create()
+                    .table(PendingLogs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PendingLogs::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PendingLogs::GuildId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(PendingLogs::ChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PendingLogs::Level).string_len(16).not_null())
+                    .col(ColumnDef::new(PendingLogs::Title).text().not_null())
+                    .col(ColumnDef::new(PendingLogs::Description).text().not_null())
+                    .col(ColumnDef::new(PendingLogs::Fields).json().not_null())
+                    .col(
+                        ColumnDef::new(PendingLogs::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(PendingLogs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Replay on startup reads entries per channel in insertion order
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-pending-logs-channel-id")
+                    .table(PendingLogs::Table)
+                    .col(PendingLogs::ChannelId)
+                    .col(PendingLogs::Id)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PendingLogs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PendingLogs {
+    Table,
+    Id,
+    GuildId,
+    ChannelId,
+    Level,
+    Title,
+    Description,
+    Fields,
+    Attempts,
+    CreatedAt,
+}
@@ -0,0 +1,124 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-guild template overrides and hidden fields, one row per log type
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LogTemplates::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LogTemplates::LogType)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LogTemplates::Overrides)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::value("{}")),
+                    )
+                    .col(
+                        ColumnDef::new(LogTemplates::HiddenFields)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::value("[]")),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(LogTemplates::GuildId)
+                            .col(LogTemplates::LogType),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Per-guild accent color for each log level
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogLevelColors::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LogLevelColors::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LogLevelColors::Level)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LogLevelColors::Color).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(LogLevelColors::GuildId)
+                            .col(LogLevelColors::Level),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Queued logs keep the color they were rendered with
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PendingLogs::Table)
+                    .add_column(ColumnDef::new(PendingLogs::AccentColor).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PendingLogs::Table)
+                    .drop_column(PendingLogs::AccentColor)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LogLevelColors::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LogTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LogTemplates {
+    Table,
+    GuildId,
+    LogType,
+    Overrides,
+    HiddenFields,
+}
+
+#[derive(DeriveIden)]
+enum LogLevelColors {
+    Table,
+    GuildId,
+    Level,
+    Color,
+}
+
+#[derive(DeriveIden)]
+enum PendingLogs {
+    Table,
+    AccentColor,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Temporary entries; NULL means permanent
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistUsers::Table)
+                    .add_column(
+                        ColumnDef::new(WhitelistUsers::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistRoles::Table)
+                    .add_column(
+                        ColumnDef::new(WhitelistRoles::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistRoles::Table)
+                    .drop_column(WhitelistRoles::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistUsers::Table)
+                    .drop_column(WhitelistUsers::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WhitelistUsers {
+    Table,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum WhitelistRoles {
+    Table,
+    ExpiresAt,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Actions an entry is limited to; an empty list means every action
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistUsers::Table)
+                    .add_column(
+                        ColumnDef::new(WhitelistUsers::Actions)
+                            .json()
+                            .not_null()
+                            .default(Expr::value("[]")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistRoles::Table)
+                    .add_column(
+                        ColumnDef::new(WhitelistRoles::Actions)
+                            .json()
+                            .not_null()
+                            .default(Expr::value("[]")),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistRoles::Table)
+                    .drop_column(WhitelistRoles::Actions)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WhitelistUsers::Table)
+                    .drop_column(WhitelistUsers::Actions)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WhitelistUsers {
+    Table,
+    Actions,
+}
+
+#[derive(DeriveIden)]
+enum WhitelistRoles {
+    Table,
+    Actions,
+}
@@ -0,0 +1,120 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Append-only record of every whitelist change made through the config menu
+        manager
+            .create_table(
+                Table::create()
+                    .table(WhitelistHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WhitelistHistory::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WhitelistHistory::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WhitelistHistory::ActorId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WhitelistHistory::Action)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WhitelistHistory::TargetType)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WhitelistHistory::TargetId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WhitelistHistory::Before).json())
+                    .col(ColumnDef::new(WhitelistHistory::After).json())
+                    .col(
+                        ColumnDef::new(WhitelistHistory::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-whitelist-history-guild-id")
+                    .table(WhitelistHistory::Table)
+                    .col(WhitelistHistory::GuildId)
+                    .col(WhitelistHistory::Id)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Opt-in DM to the server owner for every whitelist change
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GuildConfigs::Table)
+                    .add_column(
+                        ColumnDef::new(GuildConfigs::WhitelistOwnerDm)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GuildConfigs::Table)
+                    .drop_column(GuildConfigs::WhitelistOwnerDm)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WhitelistHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WhitelistHistory {
+    Table,
+    Id,
+    GuildId,
+    ActorId,
+    Action,
+    TargetType,
+    TargetId,
+    Before,
+    After,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum GuildConfigs {
+    Table,
+    WhitelistOwnerDm,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reverted changes by Admin-level members, waiting for a Head-level decision
+        manager
+            .create_table(
+                Table::create()
+                    .table(PendingApprovals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PendingApprovals::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingApprovals::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingApprovals::ModuleType)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingApprovals::ActorId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PendingApprovals::Change).json().not_null())
+                    .col(
+                        ColumnDef::new(PendingApprovals::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PendingApprovals::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PendingApprovals {
+    Table,
+    Id,
+    GuildId,
+    ModuleType,
+    ActorId,
+    Change,
+    CreatedAt,
+}
@@ -0,0 +1,65 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_backend = manager.get_database_backend();
+        let db = manager.get_connection();
+
+        // Notify the config cache with the guild id whenever a config row changes
+        db.execute(Statement::from_string(
+            db_backend,
+            r#"CREATE OR REPLACE FUNCTION "notify_config_changed"() RETURNS TRIGGER AS $$
+            BEGIN
+                IF TG_OP = 'DELETE' THEN
+                    PERFORM pg_notify('config_changed', OLD."guild_id"::TEXT);
+                ELSE
+                    PERFORM pg_notify('config_changed', NEW."guild_id"::TEXT);
+                END IF;
+                RETURN NULL;
+            END;
+            $$ LANGUAGE plpgsql;"#
+                .to_owned(),
+        ))
+        .await?;
+
+        for table in ["guild_configs", "module_configs"] {
+            db.execute(Statement::from_string(
+                db_backend,
+                format!(
+                    r#"CREATE TRIGGER "{table}_notify_config_changed"
+                    AFTER INSERT OR UPDATE OR DELETE ON "{table}"
+                    FOR EACH ROW EXECUTE FUNCTION "notify_config_changed"();"#
+                ),
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_backend = manager.get_database_backend();
+        let db = manager.get_connection();
+
+        for table in ["guild_configs", "module_configs"] {
+            db.execute(Statement::from_string(
+                db_backend,
+                format!(r#"DROP TRIGGER IF EXISTS "{table}_notify_config_changed" ON "{table}";"#),
+            ))
+            .await?;
+        }
+
+        db.execute(Statement::from_string(
+            db_backend,
+            r#"DROP FUNCTION IF EXISTS "notify_config_changed"();"#.to_owned(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
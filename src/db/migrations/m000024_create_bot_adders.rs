@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Who added each bot, so the adder can be held responsible for what the bot does
+        manager
+            .create_table(
+                Table::create()
+                    .table(BotAdders::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BotAdders::GuildId).big_integer().not_null())
+                    .col(ColumnDef::new(BotAdders::BotId).big_integer().not_null())
+                    .col(ColumnDef::new(BotAdders::AdderId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(BotAdders::AddedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(BotAdders::GuildId)
+                            .col(BotAdders::BotId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BotAdders::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BotAdders {
+    Table,
+    GuildId,
+    BotId,
+    AdderId,
+    AddedAt,
+}
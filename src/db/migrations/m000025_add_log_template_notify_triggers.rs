@@ -0,0 +1,67 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_backend = manager.get_database_backend();
+        let db = manager.get_connection();
+
+        // Notify the log template cache with the guild id whenever a template or color changes
+        db.execute(Statement::from_string(
+            db_backend,
+            r#"CREATE OR REPLACE FUNCTION "notify_log_templates_changed"() RETURNS TRIGGER AS $$
+            BEGIN
+                IF TG_OP = 'DELETE' THEN
+                    PERFORM pg_notify('log_templates_changed', OLD."guild_id"::TEXT);
+                ELSE
+                    PERFORM pg_notify('log_templates_changed', NEW."guild_id"::TEXT);
+                END IF;
+                RETURN NULL;
+            END;
+            $$ LANGUAGE plpgsql;"#
+                .to_owned(),
+        ))
+        .await?;
+
+        for table in ["log_templates", "log_level_colors"] {
+            db.execute(Statement::from_string(
+                db_backend,
+                format!(
+                    r#"CREATE TRIGGER "{table}_notify_log_templates_changed"
+                    AFTER INSERT OR UPDATE OR DELETE ON "{table}"
+                    FOR EACH ROW EXECUTE FUNCTION "notify_log_templates_changed"();"#
+                ),
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_backend = manager.get_database_backend();
+        let db = manager.get_connection();
+
+        for table in ["log_templates", "log_level_colors"] {
+            db.execute(Statement::from_string(
+                db_backend,
+                format!(
+                    r#"DROP TRIGGER IF EXISTS "{table}_notify_log_templates_changed" ON "{table}";"#
+                ),
+            ))
+            .await?;
+        }
+
+        db.execute(Statement::from_string(
+            db_backend,
+            r#"DROP FUNCTION IF EXISTS "notify_log_templates_changed"();"#.to_owned(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
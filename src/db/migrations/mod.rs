@@ -16,6 +16,19 @@ pub mod m000009_create_temp_bans;
 pub mod m000010_create_jails;
 pub mod m000011_create_member_roles_tracking;
 pub mod m000012_create_invite_tracking;
+pub mod m000013_create_archived_attachments;
+pub mod m000014_create_voice_sessions;
+pub mod m000015_add_log_webhooks;
+pub mod m000016_create_pending_logs;
+pub mod m000017_create_log_templates;
+pub mod m000018_add_whitelist_expiry;
+pub mod m000019_add_whitelist_actions;
+pub mod m000020_create_whitelist_history;
+pub mod m000021_create_pending_approvals;
+pub mod m000022_add_config_notify_triggers;
+pub mod m000023_create_audit_log_cursors;
+pub mod m000024_create_bot_adders;
+pub mod m000025_add_log_template_notify_triggers;
 
 use sea_orm_migration::prelude::*;
 
@@ -37,6 +50,19 @@ impl MigratorTrait for Migrator {
             Box::new(m000010_create_jails::Migration),
             Box::new(m000011_create_member_roles_tracking::Migration),
             Box::new(m000012_create_invite_tracking::Migration),
+            Box::new(m000013_create_archived_attachments::Migration),
+            Box::new(m000014_create_voice_sessions::Migration),
+            Box::new(m000015_add_log_webhooks::Migration),
+            Box::new(m000016_create_pending_logs::Migration),
+            Box::new(m000017_create_log_templates::Migration),
+            Box::new(m000018_add_whitelist_expiry::Migration),
+            Box::new(m000019_add_whitelist_actions::Migration),
+            Box::new(m000020_create_whitelist_history::Migration),
+            Box::new(m000021_create_pending_approvals::Migration),
+            Box::new(m000022_add_config_notify_triggers::Migration),
+            Box::new(m000023_create_audit_log_cursors::Migration),
+            Box::new(m000024_create_bot_adders::Migration),
+            Box::new(m000025_add_log_template_notify_triggers::Migration),
         ]
     }
 }
@@ -30,16 +30,21 @@ struct Args {
 // Custom user data passed to all command functions
 pub struct Data {
     pub db: DatabaseConnection,
+    pub config_cache: Arc<services::config_cache::ConfigCacheService>,
     pub l10n: Arc<services::localization::LocalizationManager>,
     pub logger: Arc<services::logger::LoggerService>,
+    #[cfg(feature = "logging")]
+    pub attachment_archive: Arc<services::attachment_archive::AttachmentArchiveService>,
     pub punishment: Arc<services::punishment::PunishmentService>,
     pub whitelist: Arc<services::whitelist::WhitelistService>,
+    pub approval: Arc<services::approval::ApprovalService>,
     pub cache: Arc<services::cache::ObjectCacheService>,
-    pub module_definitions: Vec<modules::ModuleDefinition>,
+    pub modules: Vec<Arc<dyn modules::GuardModule>>,
     pub temp_ban: Arc<services::temp_ban::TempBanService>,
     pub jail: Arc<services::jail::JailService>,
     pub setup: Arc<services::setup::state::SetupStateService>,
     pub shard_count: AtomicU32,
+    pub event_stats: services::event_manager::EventStats,
 }
 
 pub type Error = anyhow::Error;
@@ -81,24 +86,44 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let token = serenity::Token::from_env("DISCORD_TOKEN").expect("missing DISCORD_TOKEN");
-    let intents = serenity::GatewayIntents::non_privileged()
-        | serenity::GatewayIntents::GUILD_MESSAGES
-        | serenity::GatewayIntents::AUTO_MODERATION_CONFIGURATION
-        | serenity::GatewayIntents::GUILD_MODERATION
-        | serenity::GatewayIntents::AUTO_MODERATION_EXECUTION
-        | serenity::GatewayIntents::MESSAGE_CONTENT
-        | serenity::GatewayIntents::GUILD_MEMBERS
-        | serenity::GatewayIntents::GUILD_VOICE_STATES
-        | serenity::GatewayIntents::GUILD_INVITES;
+    let guard_modules = modules::get_modules();
+    let intents = modules::intents(&guard_modules);
 
     // Initialize localization manager
     let l10n = Arc::new(services::localization::LocalizationManager::new());
 
+    // Initialize config cache service
+    let config_cache = Arc::new(services::config_cache::ConfigCacheService::new(db.clone()));
+    config_cache.start_listener();
+
     // Initialize logger service
-    let logger = Arc::new(services::logger::LoggerService::new(db.clone()));
+    let logger = Arc::new(services::logger::LoggerService::new(
+        db.clone(),
+        config_cache.clone(),
+        l10n.clone(),
+    ));
+    logger.templates().start_listener();
+
+    // Initialize attachment archive service
+    #[cfg(feature = "logging")]
+    let attachment_archive = Arc::new(
+        services::attachment_archive::AttachmentArchiveService::new(db.clone()),
+    );
 
     // Initialize whitelist service
-    let whitelist = Arc::new(services::whitelist::WhitelistService::new(db.clone()));
+    let whitelist = Arc::new(services::whitelist::WhitelistService::new(
+        db.clone(),
+        config_cache.clone(),
+        logger.clone(),
+        l10n.clone(),
+    ));
+
+    // Initialize approval service
+    let approval = Arc::new(services::approval::ApprovalService::new(
+        db.clone(),
+        logger.clone(),
+        l10n.clone(),
+    ));
 
     // Initialize object cache service
     let cache = Arc::new(services::cache::ObjectCacheService::new());
@@ -113,14 +138,20 @@ async fn main() -> anyhow::Result<()> {
     // Initialize jail service
     let jail = Arc::new(services::jail::JailService::new(
         db.clone(),
+        config_cache.clone(),
         logger.clone(),
         l10n.clone(),
     ));
 
     // Initialize punishment service
-    let mut punishment_svc =
-        services::punishment::PunishmentService::new(db.clone(), logger.clone(), l10n.clone());
+    let mut punishment_svc = services::punishment::PunishmentService::new(
+        db.clone(),
+        config_cache.clone(),
+        logger.clone(),
+        l10n.clone(),
+    );
     punishment_svc.set_jail_service(jail.clone());
+    punishment_svc.set_whitelist_service(whitelist.clone());
     let punishment = Arc::new(punishment_svc);
 
     // Initialize setup service
@@ -235,36 +266,61 @@ async fn main() -> anyhow::Result<()> {
     cache_settings.cache_channels = true;
     cache_settings.time_to_live = std::time::Duration::from_hours(6);
 
+    let data = Arc::new(Data {
+        db,
+        config_cache,
+        l10n,
+        logger: logger.clone(),
+        #[cfg(feature = "logging")]
+        attachment_archive: attachment_archive.clone(),
+        punishment,
+        whitelist: whitelist.clone(),
+        approval,
+        cache,
+        modules: guard_modules,
+        temp_ban: temp_ban.clone(),
+        jail: jail.clone(),
+        setup,
+        shard_count: AtomicU32::new(shard_count.load(Ordering::Relaxed)),
+        event_stats: services::event_manager::EventStats::new(),
+    });
+
     // Build the client with both poise framework and custom event handler
     let mut client = serenity::ClientBuilder::new(token, intents)
         .framework(Box::new(framework))
-        .event_handler(Arc::new(services::event_manager::Handler::new()))
+        .event_handler(Arc::new(services::event_manager::Handler::new(
+            data.db.clone(),
+        )))
         .cache_settings(cache_settings)
-        .data(Arc::new(Data {
-            db: db.clone(),
-            l10n,
-            logger,
-            punishment,
-            whitelist,
-            cache,
-            module_definitions: modules::definitions(),
-            temp_ban: temp_ban.clone(),
-            jail: jail.clone(),
-            setup,
-            shard_count: AtomicU32::new(shard_count.load(Ordering::Relaxed)),
-        }) as _)
+        .data(data.clone() as _)
         .await
         .context("Failed to create client")?;
 
+    // Start log delivery runner
+    logger.start_delivery_runner(client.http.clone());
+
     // Start unban runner
     temp_ban.start_unban_runner(client.http.clone());
 
     // Start unjail runner
     jail.start_unjail_runner(client.http.clone());
 
-    // Start logging cleanup runner
-    let logging_cleanup = Arc::new(services::logging_cleanup::LoggingCleanupService::new(db));
-    logging_cleanup.start_cleanup_runner();
+    // Start whitelist expiry runner
+    whitelist.start_expiry_runner();
+
+    // Start logging cleanup runner; the stored member roles are shared by several modules
+    tokio::spawn(services::logging_cleanup::LoggingCleanupService::new(data.db.clone()).run());
+
+    // Start the modules' background tasks
+    for module in &data.modules {
+        for task in module.background_tasks(client.http.clone(), data.clone()) {
+            tokio::spawn(task);
+        }
+    }
+
+    // Start attachment archive cleanup runner
+    #[cfg(feature = "logging")]
+    attachment_archive.start_cleanup_runner();
 
     info!("Bot is ready!");
     client.start_autosharded().await.context("Client error")?;
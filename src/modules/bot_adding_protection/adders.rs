@@ -0,0 +1,118 @@
+use crate::db::entities::bot_adders;
+use crate::db::entities::module_configs::ModuleType;
+use crate::{Data, Error};
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use serenity::model::guild::audit_log::{Action, MemberAction};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Entries fetched per request, the API maximum.
+const PAGE_SIZE: u8 = 100;
+/// Pages of bot additions fetched per backfill.
+const MAX_PAGES: usize = 5;
+
+/// Whether the guild has Bot Adding Protection enabled.
+pub async fn is_enabled(guild_id: serenity::GuildId, data: &Data) -> Result<bool, Error> {
+    let config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::BotAddingProtection)
+        .await?;
+    Ok(config.is_some_and(|c| c.enabled))
+}
+
+/// Whether any adder of the guild was recorded yet.
+pub async fn has_any(guild_id: serenity::GuildId, data: &Data) -> Result<bool, Error> {
+    let count = bot_adders::Entity::find()
+        .filter(bot_adders::Column::GuildId.eq(guild_id.get() as i64))
+        .count(&data.db)
+        .await?;
+    Ok(count > 0)
+}
+
+/// Remembers who added a bot, replacing an earlier addition of the same bot.
+pub async fn record(
+    data: &Data,
+    guild_id: serenity::GuildId,
+    bot_id: serenity::UserId,
+    adder_id: serenity::UserId,
+    added_at: DateTime<Utc>,
+) -> Result<(), Error> {
+    bot_adders::Entity::insert(bot_adders::ActiveModel {
+        guild_id: Set(guild_id.get() as i64),
+        bot_id: Set(bot_id.get() as i64),
+        adder_id: Set(adder_id.get() as i64),
+        added_at: Set(added_at.into()),
+    })
+    .on_conflict(
+        sea_orm::sea_query::OnConflict::columns([
+            bot_adders::Column::GuildId,
+            bot_adders::Column::BotId,
+        ])
+        .update_columns([bot_adders::Column::AdderId, bot_adders::Column::AddedAt])
+        .to_owned(),
+    )
+    .exec(&data.db)
+    .await?;
+    Ok(())
+}
+
+/// Records the adders of bots added before the bot joined or while it was offline.
+/// Discord keeps audit logs for 45 days, so older additions cannot be attributed.
+pub async fn backfill(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<(), Error> {
+    let page_size = serenity::nonmax::NonMaxU8::new(PAGE_SIZE).unwrap();
+
+    // Entries come newest first, so the first addition seen of each bot is its latest
+    let mut adders = HashMap::new();
+    let mut before = None;
+    for _ in 0..MAX_PAGES {
+        let page = guild_id
+            .audit_logs(
+                &ctx.http,
+                Some(Action::Member(MemberAction::BotAdd)),
+                None,
+                before,
+                Some(page_size),
+            )
+            .await?;
+
+        for entry in page.entries.iter() {
+            before = Some(entry.id);
+            if let (Some(bot_id), Some(adder_id)) = (entry.target_id, entry.user_id) {
+                adders
+                    .entry(serenity::UserId::new(bot_id.get()))
+                    .or_insert((adder_id, entry.id.created_at().to_utc()));
+            }
+        }
+
+        if page.entries.len() < PAGE_SIZE as usize {
+            break;
+        }
+    }
+
+    if !adders.is_empty() {
+        info!(
+            "Backfilled adders of {} bots in guild {}",
+            adders.len(),
+            guild_id
+        );
+    }
+    for (bot_id, (adder_id, added_at)) in adders {
+        record(data, guild_id, bot_id, adder_id, added_at).await?;
+    }
+    Ok(())
+}
+
+/// Forgets the adders of a guild the bot left.
+pub async fn delete_guild(guild_id: serenity::GuildId, data: &Data) -> Result<(), Error> {
+    bot_adders::Entity::delete_many()
+        .filter(bot_adders::Column::GuildId.eq(guild_id.get() as i64))
+        .exec(&data.db)
+        .await?;
+    Ok(())
+}
@@ -2,7 +2,6 @@ use crate::db::entities::module_configs::{self, BotAddingProtectionModuleConfig,
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, MemberAction};
 
 pub async fn handle_audit_log(
@@ -16,12 +15,10 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    let config_model = match module_configs::Entity::find_by_id((
-        guild_id.get() as i64,
-        ModuleType::BotAddingProtection,
-    ))
-    .one(&data.db)
-    .await?
+    let config_model = match data
+        .config_cache
+        .module_config(guild_id, ModuleType::BotAddingProtection)
+        .await?
     {
         Some(m) => {
             if !m.enabled {
@@ -40,6 +37,19 @@ pub async fn handle_audit_log(
         None => return Ok(()),
     };
 
+    // Remember the adder even when the addition is allowed, so they can be held
+    // responsible for what the bot does later
+    if let Some(bot_id) = entry.target_id {
+        crate::modules::bot_adding_protection::adders::record(
+            data,
+            guild_id,
+            serenity::UserId::new(bot_id.get()),
+            user_id,
+            entry.id.created_at().to_utc(),
+        )
+        .await?;
+    }
+
     // Ignore actions by the bot itself
     if user_id == ctx.cache.current_user().id {
         return Ok(());
@@ -48,7 +58,13 @@ pub async fn handle_audit_log(
     // Check whitelist
     let whitelist_level = data
         .whitelist
-        .get_whitelist_level(ctx, guild_id, user_id, ModuleType::BotAddingProtection)
+        .get_whitelist_level(
+            ctx,
+            guild_id,
+            user_id,
+            ModuleType::BotAddingProtection,
+            Some("add"),
+        )
         .await?;
 
     match entry.action {
@@ -104,7 +120,7 @@ async fn handle_bot_add(
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::BotAddingProtection,
@@ -158,9 +174,9 @@ async fn handle_bot_add(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-bot-add-title-whitelisted", None)
+        l10n.text("log-bot-add-title-whitelisted", None)
     } else {
-        l10n.t("log-bot-add-title-blocked", None)
+        l10n.text("log-bot-add-title-blocked", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -171,23 +187,22 @@ async fn handle_bot_add(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("botId", bot_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-bot-add-desc", Some(&desc_args));
+    let desc = l10n.text("log-bot-add-desc", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::BotAddingProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
                 (
-                    &l10n.t("log-field-acting-user", None),
+                    l10n.text("log-field-acting-user", None),
                     format!("<@{}>", user_id.get()),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -1,17 +1,87 @@
+pub mod adders;
 pub mod events;
 
-use crate::modules::{Module, ModuleDefinition};
+use crate::db::entities::module_configs;
+use crate::modules::{EventKind, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
 
-pub const DEFINITION: ModuleDefinition = ModuleDefinition {
-    id: "bot_adding_protection",
-    name_key: "module-bot-adding-protection-name",
-    desc_key: "module-bot-adding-protection-desc",
-};
+pub struct BotAddingProtection;
 
-pub fn module() -> Module {
-    Module {
-        definition: DEFINITION,
-        commands: vec![],
-        event_handlers: vec![events::handler],
+#[serenity::async_trait]
+impl GuardModule for BotAddingProtection {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
+            id: "bot_adding_protection",
+            name_key: "module-bot-adding-protection-name",
+            desc_key: "module-bot-adding-protection-desc",
+        }
+    }
+
+    fn events(&self) -> &'static [EventKind] {
+        &[EventKind::GuildAuditLogEntryCreate]
+    }
+
+    async fn on_guild_join(
+        &self,
+        ctx: &serenity::Context,
+        guild: &serenity::Guild,
+        is_new: bool,
+        data: &Data,
+    ) -> Result<(), Error> {
+        // Bots may have been added before we joined. Guilds we already know get their
+        // additions live, so their audit log is only read once.
+        if !adders::is_enabled(guild.id, data).await? {
+            return Ok(());
+        }
+        if is_new || !adders::has_any(guild.id, data).await? {
+            adders::backfill(ctx, guild.id, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_guild_leave(
+        &self,
+        _ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        data: &Data,
+    ) -> Result<(), Error> {
+        adders::delete_guild(guild_id, data).await
+    }
+
+    async fn on_event(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        data: &Data,
+    ) -> Result<(), Error> {
+        events::handle_event(ctx, event, data).await
+    }
+
+    async fn on_config_changed(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        old: Option<&module_configs::Model>,
+        new: &module_configs::Model,
+        data: &Data,
+    ) -> Result<(), Error> {
+        if new.enabled && !old.is_some_and(|c| c.enabled) {
+            adders::backfill(ctx, guild_id, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(
+            ctx,
+            guild_id,
+            serenity::Permissions::VIEW_AUDIT_LOG | serenity::Permissions::KICK_MEMBERS,
+        )
     }
 }
@@ -4,7 +4,6 @@ use crate::db::entities::module_configs::{
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, ChannelOverwriteAction};
 
 pub async fn handle_audit_log(
@@ -24,12 +23,10 @@ pub async fn handle_audit_log(
     }
 
     // Fetch module config
-    let config_model = module_configs::Entity::find_by_id((
-        guild_id.get() as i64,
-        ModuleType::ChannelPermissionProtection,
-    ))
-    .one(&data.db)
-    .await?;
+    let config_model = data
+        .config_cache
+        .module_config(guild_id, ModuleType::ChannelPermissionProtection)
+        .await?;
 
     let config_model = match config_model {
         Some(m) => {
@@ -54,7 +51,12 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    // Check whitelist
+    // Check whitelist for this specific action
+    let action = match entry.action {
+        Action::ChannelOverwrite(ChannelOverwriteAction::Create) => "create",
+        Action::ChannelOverwrite(ChannelOverwriteAction::Delete) => "delete",
+        _ => "update",
+    };
     let whitelist_level = data
         .whitelist
         .get_whitelist_level(
@@ -62,6 +64,7 @@ pub async fn handle_audit_log(
             guild_id,
             user_id,
             ModuleType::ChannelPermissionProtection,
+            Some(action),
         )
         .await?;
 
@@ -177,7 +180,7 @@ async fn handle_overwrite_create(
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ChannelPermissionProtection,
@@ -232,11 +235,11 @@ async fn handle_overwrite_create(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-chan-perm-title-whitelisted", None)
+        l10n.text("log-chan-perm-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-chan-perm-title-blocked", None)
+        l10n.text("log-chan-perm-title-blocked", None)
     } else {
-        l10n.t("log-chan-perm-title-logged", None)
+        l10n.text("log-chan-perm-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -249,24 +252,23 @@ async fn handle_overwrite_create(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("channelId", channel_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-chan-perm-desc-create", Some(&desc_args));
+    let desc = l10n.text("log-chan-perm-desc-create", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ChannelPermissionProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
+                (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
                 (
-                    &l10n.t("log-field-channel", None),
+                    l10n.text("log-field-channel", None),
                     format!("<#{}>", channel_id),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -316,7 +318,7 @@ async fn handle_overwrite_delete(
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ChannelPermissionProtection,
@@ -407,11 +409,11 @@ async fn handle_overwrite_delete(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-chan-perm-title-whitelisted", None)
+        l10n.text("log-chan-perm-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-chan-perm-title-blocked", None)
+        l10n.text("log-chan-perm-title-blocked", None)
     } else {
-        l10n.t("log-chan-perm-title-logged", None)
+        l10n.text("log-chan-perm-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -424,24 +426,23 @@ async fn handle_overwrite_delete(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("channelId", channel_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-chan-perm-desc-delete", Some(&desc_args));
+    let desc = l10n.text("log-chan-perm-desc-delete", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ChannelPermissionProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
+                (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
                 (
-                    &l10n.t("log-field-channel", None),
+                    l10n.text("log-field-channel", None),
                     format!("<#{}>", channel_id),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -491,7 +492,7 @@ async fn handle_overwrite_update(
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ChannelPermissionProtection,
@@ -606,11 +607,11 @@ async fn handle_overwrite_update(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-chan-perm-title-whitelisted", None)
+        l10n.text("log-chan-perm-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-chan-perm-title-blocked", None)
+        l10n.text("log-chan-perm-title-blocked", None)
     } else {
-        l10n.t("log-chan-perm-title-logged", None)
+        l10n.text("log-chan-perm-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -623,24 +624,23 @@ async fn handle_overwrite_update(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("channelId", channel_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-chan-perm-desc-update", Some(&desc_args));
+    let desc = l10n.text("log-chan-perm-desc-update", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ChannelPermissionProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
+                (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
                 (
-                    &l10n.t("log-field-channel", None),
+                    l10n.text("log-field-channel", None),
                     format!("<#{}>", channel_id),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
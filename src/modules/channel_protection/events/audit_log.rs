@@ -1,9 +1,11 @@
 use crate::db::entities::module_configs::{self, ChannelProtectionModuleConfig, ModuleType};
+use crate::db::entities::whitelists::WhitelistLevel;
+use crate::services::approval::HeldChange;
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, ChannelAction};
+use tracing::warn;
 
 pub async fn handle_audit_log(
     ctx: &serenity::Context,
@@ -22,10 +24,10 @@ pub async fn handle_audit_log(
     }
 
     // Fetch module config
-    let config_model =
-        module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::ChannelProtection))
-            .one(&data.db)
-            .await?;
+    let config_model = data
+        .config_cache
+        .module_config(guild_id, ModuleType::ChannelProtection)
+        .await?;
 
     let (config_model, config) = match config_model {
         Some(m) => {
@@ -49,10 +51,21 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    // Check whitelist
+    // Check whitelist for this specific action
+    let action = match entry.action {
+        Action::Channel(ChannelAction::Create) => "create",
+        Action::Channel(ChannelAction::Delete) => "delete",
+        _ => "update",
+    };
     let whitelist_level = data
         .whitelist
-        .get_whitelist_level(ctx, guild_id, user_id, ModuleType::ChannelProtection)
+        .get_whitelist_level(
+            ctx,
+            guild_id,
+            user_id,
+            ModuleType::ChannelProtection,
+            Some(action),
+        )
         .await?;
 
     // Check if we should ignore private channels (ownership check)
@@ -160,11 +173,11 @@ async fn handle_channel_create(
 
     if whitelist_level.is_none() && should_punish {
         // Punishment
-        let reason = l10n.t("log-channel-reason-create", None);
+        let reason = l10n.t("log-chan-reason-create", None);
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ChannelProtection,
@@ -194,7 +207,7 @@ async fn handle_channel_create(
 
         // Revert
         if config_model.revert && channel_id != 0 {
-            let revert_reason = l10n.t("log-channel-revert-reason", None);
+            let revert_reason = l10n.t("log-chan-revert-reason", None);
             if ctx
                 .http
                 .delete_channel(
@@ -218,11 +231,11 @@ async fn handle_channel_create(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-channel-title-whitelisted", None)
+        l10n.text("log-chan-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-channel-title-blocked", None)
+        l10n.text("log-chan-title-blocked", None)
     } else {
-        l10n.t("log-channel-title-logged", None)
+        l10n.text("log-chan-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -235,24 +248,23 @@ async fn handle_channel_create(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("channelId", channel_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-channel-desc-create", Some(&desc_args));
+    let desc = l10n.text("log-chan-desc-create", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ChannelProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
+                (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
                 (
-                    &l10n.t("log-field-channel", None),
+                    l10n.text("log-field-channel", None),
                     format!("<#{}>", channel_id),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -290,13 +302,42 @@ async fn handle_channel_delete(
         l10n.t("log-status-unauthorized", None)
     };
 
+    // Categories deleted by Admin-level members are restored and held for Head approval
+    let mut held = false;
+    if whitelist_level == Some(WhitelistLevel::Admin) && config.require_approval {
+        if let Some((channel, children)) = wait_for_cached_channel(data, guild_id, channel_id).await
+        {
+            if channel.base.kind == serenity::ChannelType::Category {
+                match restore_channel(ctx, guild_id, &channel, &children).await {
+                    Ok(restored) => {
+                        data.approval
+                            .hold(
+                                &ctx.http,
+                                guild_id,
+                                ModuleType::ChannelProtection,
+                                user_id,
+                                HeldChange::CategoryDelete {
+                                    channel_id: restored.id.get(),
+                                    name: channel.base.name.to_string(),
+                                },
+                            )
+                            .await?;
+                        status += &l10n.t("log-status-held", None);
+                        held = true;
+                    }
+                    Err(_) => status += &l10n.t("log-status-revert-failed", None),
+                }
+            }
+        }
+    }
+
     if whitelist_level.is_none() && should_punish {
         // Punishment
-        let reason = l10n.t("log-channel-reason-delete", None);
+        let reason = l10n.t("log-chan-reason-delete", None);
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ChannelProtection,
@@ -326,62 +367,19 @@ async fn handle_channel_delete(
 
         // Revert
         if config_model.revert {
-            // Wait for the channel to be stored in cache
-            let mut cached_channel = None;
-            for _ in 0..10 {
-                if let Some(c) = data
-                    .cache
-                    .take_channel(guild_id, serenity::ChannelId::new(channel_id))
-                {
-                    cached_channel = Some(c);
-                    break;
-                }
-                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-            }
-
-            if let Some(channel) = cached_channel {
-                let mut create_channel = serenity::CreateChannel::new(channel.base.name.clone())
-                    .kind(channel.base.kind)
-                    .permissions(channel.permission_overwrites.clone());
-
-                if let Some(id) = channel.parent_id {
-                    create_channel = create_channel.category(id);
-                }
-
-                if let Some(ref topic) = channel.topic {
-                    create_channel = create_channel.topic(topic);
-                }
-
-                create_channel = create_channel.nsfw(channel.nsfw);
-
-                if let Some(bitrate) = channel.bitrate {
-                    create_channel = create_channel.bitrate(bitrate.get());
-                }
-
-                if let Some(user_limit) = channel.user_limit {
-                    create_channel = create_channel.user_limit(user_limit);
-                }
-
-                if let Some(ref rate_limit) = channel.base.rate_limit_per_user {
-                    create_channel = create_channel.rate_limit_per_user(*rate_limit);
-                }
-
-                create_channel = create_channel.position(channel.position as u16);
-
-                if guild_id
-                    .create_channel(&ctx.http, create_channel)
+            let restored = match wait_for_cached_channel(data, guild_id, channel_id).await {
+                Some((channel, children)) => restore_channel(ctx, guild_id, &channel, &children)
                     .await
-                    .is_ok()
-                {
-                    status += &l10n.t("log-status-reverted", None);
-                } else {
-                    status += &l10n.t("log-status-revert-failed", None);
-                }
+                    .is_ok(),
+                None => false,
+            };
+            if restored {
+                status += &l10n.t("log-status-reverted", None);
             } else {
                 status += &l10n.t("log-status-revert-failed", None);
             }
         }
-    } else if let Some(level) = whitelist_level {
+    } else if let (false, Some(level)) = (held, whitelist_level) {
         let mut args = fluent::FluentArgs::new();
         args.set("level", format!("{:?}", level));
         args.set("punishment", format!("{:?}", config_model.punishment));
@@ -390,11 +388,11 @@ async fn handle_channel_delete(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-channel-title-whitelisted", None)
+        l10n.text("log-chan-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-channel-title-blocked", None)
+        l10n.text("log-chan-title-blocked", None)
     } else {
-        l10n.t("log-channel-title-logged", None)
+        l10n.text("log-chan-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -407,24 +405,23 @@ async fn handle_channel_delete(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("channelId", channel_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-channel-desc-delete", Some(&desc_args));
+    let desc = l10n.text("log-chan-desc-delete", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ChannelProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
+                (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
                 (
-                    &l10n.t("log-field-channel-id", None),
+                    l10n.text("log-field-channel-id", None),
                     channel_id.to_string(),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -432,6 +429,78 @@ async fn handle_channel_delete(
     Ok(())
 }
 
+/// Waits for a deleted channel to be stored in the object cache and takes it.
+async fn wait_for_cached_channel(
+    data: &Data,
+    guild_id: serenity::GuildId,
+    channel_id: u64,
+) -> Option<(serenity::GuildChannel, Vec<serenity::ChannelId>)> {
+    for _ in 0..10 {
+        if let Some(c) = data
+            .cache
+            .take_channel(guild_id, serenity::ChannelId::new(channel_id))
+        {
+            return Some(c);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    }
+    None
+}
+
+/// Recreates a deleted channel from its cached copy. A category gets back the
+/// channels it held.
+async fn restore_channel(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    channel: &serenity::GuildChannel,
+    children: &[serenity::ChannelId],
+) -> Result<serenity::GuildChannel, Error> {
+    let mut create_channel = serenity::CreateChannel::new(channel.base.name.clone())
+        .kind(channel.base.kind)
+        .permissions(channel.permission_overwrites.clone());
+
+    if let Some(id) = channel.parent_id {
+        create_channel = create_channel.category(id);
+    }
+
+    if let Some(ref topic) = channel.topic {
+        create_channel = create_channel.topic(topic);
+    }
+
+    create_channel = create_channel.nsfw(channel.nsfw);
+
+    if let Some(bitrate) = channel.bitrate {
+        create_channel = create_channel.bitrate(bitrate.get());
+    }
+
+    if let Some(user_limit) = channel.user_limit {
+        create_channel = create_channel.user_limit(user_limit);
+    }
+
+    if let Some(ref rate_limit) = channel.base.rate_limit_per_user {
+        create_channel = create_channel.rate_limit_per_user(*rate_limit);
+    }
+
+    create_channel = create_channel.position(channel.position as u16);
+
+    let restored = guild_id.create_channel(&ctx.http, create_channel).await?;
+    for child in children {
+        if let Err(e) = child
+            .edit(
+                &ctx.http,
+                serenity::EditChannel::new().category(Some(restored.id)),
+            )
+            .await
+        {
+            warn!(
+                "Failed to move channel {} back into category {}: {:?}",
+                child, restored.id, e
+            );
+        }
+    }
+    Ok(restored)
+}
+
 async fn handle_channel_update(
     ctx: &serenity::Context,
     entry: &serenity::AuditLogEntry,
@@ -464,11 +533,11 @@ async fn handle_channel_update(
 
     if whitelist_level.is_none() && should_punish {
         // Punishment
-        let reason = l10n.t("log-channel-reason-update", None);
+        let reason = l10n.t("log-chan-reason-update", None);
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ChannelProtection,
@@ -526,7 +595,7 @@ async fn handle_channel_update(
             }
 
             if !map.is_empty() {
-                let revert_reason = l10n.t("log-channel-revert-reason", None);
+                let revert_reason = l10n.t("log-chan-revert-reason", None);
                 if ctx
                     .http
                     .edit_channel(
@@ -554,11 +623,11 @@ async fn handle_channel_update(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-channel-title-whitelisted", None)
+        l10n.text("log-chan-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-channel-title-blocked", None)
+        l10n.text("log-chan-title-blocked", None)
     } else {
-        l10n.t("log-channel-title-logged", None)
+        l10n.text("log-chan-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -571,24 +640,23 @@ async fn handle_channel_update(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("channelId", channel_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-channel-desc-update", Some(&desc_args));
+    let desc = l10n.text("log-chan-desc-update", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ChannelProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
+                (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
                 (
-                    &l10n.t("log-field-channel", None),
+                    l10n.text("log-field-channel", None),
                     format!("<#{}>", channel_id),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -3,20 +3,18 @@ pub mod audit_log;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
 
-pub fn handler<'a>(
-    ctx: &'a serenity::Context,
-    event: &'a serenity::FullEvent,
-    data: &'a Data,
-) -> poise::BoxFuture<'a, Result<(), Error>> {
-    Box::pin(async move {
-        match event {
-            serenity::FullEvent::GuildAuditLogEntryCreate {
-                entry, guild_id, ..
-            } => {
-                audit_log::handle_audit_log(ctx, entry, *guild_id, data).await?;
-            }
-            _ => {}
+pub async fn handle_event(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    data: &Data,
+) -> Result<(), Error> {
+    match event {
+        serenity::FullEvent::GuildAuditLogEntryCreate {
+            entry, guild_id, ..
+        } => {
+            audit_log::handle_audit_log(ctx, entry, *guild_id, data).await?;
         }
-        Ok(())
-    })
+        _ => {}
+    }
+    Ok(())
 }
@@ -1,15 +1,44 @@
 pub mod events;
 
-use crate::modules::{Module, ModuleDefinition};
+use crate::modules::{EventKind, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
 
-pub fn module() -> Module {
-    Module {
-        definition: ModuleDefinition {
+pub struct ChannelProtection;
+
+#[serenity::async_trait]
+impl GuardModule for ChannelProtection {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
             id: "channel_protection",
             name_key: "module-channel-protection-name",
             desc_key: "module-channel-protection-desc",
-        },
-        commands: vec![],
-        event_handlers: vec![events::handler],
+        }
+    }
+
+    fn events(&self) -> &'static [EventKind] {
+        &[EventKind::GuildAuditLogEntryCreate]
+    }
+
+    async fn on_event(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        data: &Data,
+    ) -> Result<(), Error> {
+        events::handle_event(ctx, event, data).await
+    }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(
+            ctx,
+            guild_id,
+            serenity::Permissions::VIEW_AUDIT_LOG | serenity::Permissions::MANAGE_CHANNELS,
+        )
     }
 }
@@ -1,5 +1,6 @@
 use crate::db::entities::{invite_events, module_configs::ModuleType};
 use crate::modules::invite_tracking::{stats, tracking};
+use crate::services::localization::LocalizedText;
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use chrono::Utc;
@@ -7,17 +8,7 @@ use fluent_bundle::FluentArgs;
 use poise::serenity_prelude as serenity;
 use sea_orm::{ActiveModelTrait, Set};
 
-pub fn handler<'a>(
-    ctx: &'a serenity::Context,
-    event: &'a serenity::FullEvent,
-    data: &'a Data,
-) -> poise::BoxFuture<'a, Result<(), Error>> {
-    Box::pin(async move {
-        handle_event(ctx, event, data).await
-    })
-}
-
-async fn handle_event(
+pub async fn handle_event(
     ctx: &serenity::Context,
     event: &serenity::FullEvent,
     data: &Data,
@@ -33,7 +24,7 @@ async fn handle_event(
             handle_member_join(ctx, new_member, data).await?;
         }
         serenity::FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
-            handle_member_leave(ctx, *guild_id, user, data).await?;
+            handle_member_leave(*guild_id, user, data).await?;
         }
         _ => {}
     }
@@ -227,18 +218,27 @@ async fn handle_member_join(
 
     // Log to audit log
     let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
-    let mut fields = vec![("Join Type", tracking::format_join_type(&join_type))];
+    let mut fields = vec![(
+        LocalizedText::plain("Join Type"),
+        tracking::format_join_type(&join_type),
+    )];
 
     if let Some(inviter_id) = inviter_id {
-        fields.push(("Inviter", format!("<@{}>", inviter_id)));
+        fields.push((
+            LocalizedText::plain("Inviter"),
+            format!("<@{}>", inviter_id),
+        ));
     }
 
     if let Some(code) = invite_code {
-        fields.push(("Invite Code", format!("`{}`", code)));
+        fields.push((LocalizedText::plain("Invite Code"), format!("`{}`", code)));
     }
 
     if is_suspicious {
-        fields.push(("Suspicious", suspicious_reason.unwrap_or_else(|| "Unknown reason".to_string())));
+        fields.push((
+            LocalizedText::plain("Suspicious"),
+            suspicious_reason.unwrap_or_else(|| "Unknown reason".to_string()),
+        ));
     }
 
     let mut args = FluentArgs::new();
@@ -247,13 +247,12 @@ async fn handle_member_join(
     let _ = data
         .logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::InviteTracking),
             None,
             LogLevel::Info,
-            &l10n.t("log-member-join-title", None),
-            &l10n.t("log-member-join-desc", Some(&args)),
+            l10n.text("log-member-join-title", None),
+            l10n.text("log-member-join-desc", Some(&args)),
             fields,
         )
         .await;
@@ -262,7 +261,6 @@ async fn handle_member_join(
 }
 
 async fn handle_member_leave(
-    ctx: &serenity::Context,
     guild_id: serenity::GuildId,
     user: &serenity::User,
     data: &Data,
@@ -312,11 +310,17 @@ async fn handle_member_leave(
     let mut fields = vec![];
 
     if let Some(inviter_id) = inviter_id {
-        fields.push(("Inviter", format!("<@{}>", inviter_id)));
+        fields.push((
+            LocalizedText::plain("Inviter"),
+            format!("<@{}>", inviter_id),
+        ));
     }
 
     if is_fake {
-        fields.push(("Suspicious", "Yes (Left quickly)".to_string()));
+        fields.push((
+            LocalizedText::plain("Suspicious"),
+            "Yes (Left quickly)".to_string(),
+        ));
     }
 
     let mut args = FluentArgs::new();
@@ -325,13 +329,12 @@ async fn handle_member_leave(
     let _ = data
         .logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::InviteTracking),
             None,
             LogLevel::Info,
-            &l10n.t("log-member-leave-title", None),
-            &l10n.t("log-member-leave-desc", Some(&args)),
+            l10n.text("log-member-leave-title", None),
+            l10n.text("log-member-leave-desc", Some(&args)),
             fields,
         )
         .await;
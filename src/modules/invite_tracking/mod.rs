@@ -3,18 +3,83 @@ pub mod tracking;
 pub mod stats;
 pub mod commands;
 
-use crate::modules::{Module, ModuleDefinition};
-
-pub const DEFINITION: ModuleDefinition = ModuleDefinition {
-    id: "invite_tracking",
-    name_key: "module-invite-tracking-name",
-    desc_key: "module-invite-tracking-desc",
-};
-
-pub fn module() -> Module {
-    Module {
-        definition: DEFINITION,
-        commands: commands::commands(),
-        event_handlers: vec![events::handler],
+use crate::db::entities::module_configs;
+use crate::modules::{EventKind, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
+
+pub struct InviteTracking;
+
+#[serenity::async_trait]
+impl GuardModule for InviteTracking {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
+            id: "invite_tracking",
+            name_key: "module-invite-tracking-name",
+            desc_key: "module-invite-tracking-desc",
+        }
+    }
+
+    fn commands(&self) -> Vec<poise::Command<Data, Error>> {
+        commands::commands()
+    }
+
+    fn events(&self) -> &'static [EventKind] {
+        &[
+            EventKind::InviteCreate,
+            EventKind::InviteDelete,
+            EventKind::GuildMemberAddition,
+            EventKind::GuildMemberRemoval,
+        ]
+    }
+
+    fn intents(&self) -> serenity::GatewayIntents {
+        serenity::GatewayIntents::GUILD_INVITES
+    }
+
+    async fn on_guild_join(
+        &self,
+        ctx: &serenity::Context,
+        guild: &serenity::Guild,
+        _is_new: bool,
+        data: &Data,
+    ) -> Result<(), Error> {
+        // Invites created or used while offline are not in the snapshots
+        if tracking::get_config(guild.id, data).await?.is_some() {
+            tracking::sync_all_guild_invites(ctx, guild.id, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_event(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        data: &Data,
+    ) -> Result<(), Error> {
+        events::handle_event(ctx, event, data).await
+    }
+
+    async fn on_config_changed(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        old: Option<&module_configs::Model>,
+        new: &module_configs::Model,
+        data: &Data,
+    ) -> Result<(), Error> {
+        if new.enabled && !old.is_some_and(|c| c.enabled) {
+            tracking::sync_all_guild_invites(ctx, guild_id, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(ctx, guild_id, serenity::Permissions::MANAGE_GUILD)
     }
 }
@@ -1,4 +1,4 @@
-use crate::db::entities::{invite_snapshots, module_configs::{self, InviteTrackingModuleConfig, ModuleType}};
+use crate::db::entities::{invite_snapshots, module_configs::{InviteTrackingModuleConfig, ModuleType}};
 use crate::{Data, Error};
 use chrono::Utc;
 use poise::serenity_prelude as serenity;
@@ -10,10 +10,10 @@ pub async fn get_config(
     guild_id: serenity::GuildId,
     data: &Data,
 ) -> Result<Option<InviteTrackingModuleConfig>, Error> {
-    let m_config =
-        module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::InviteTracking))
-            .one(&data.db)
-            .await?;
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::InviteTracking)
+        .await?;
 
     match m_config {
         Some(m) => {
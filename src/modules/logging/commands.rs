@@ -0,0 +1,140 @@
+use crate::modules::logging::events::voice::get_voice_logging_config;
+use crate::modules::logging::voice_sessions::{self, StatsPeriod};
+use crate::services::duration::format_duration;
+use crate::services::localization::ContextL10nExt;
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Voice activity statistics
+#[poise::command(slash_command, guild_only, subcommands("stats", "top"))]
+pub async fn voice(_ctx: Context<'_>) -> Result<(), Error> {
+    // This is the parent command, subcommands will handle actual functionality
+    Ok(())
+}
+
+/// View time spent in voice by a user
+#[poise::command(slash_command, guild_only)]
+pub async fn stats(
+    ctx: Context<'_>,
+    #[description = "User to check (defaults to you)"] user: Option<serenity::User>,
+    #[description = "Time period (default: last 7 days)"] period: Option<StatsPeriod>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let target = user.as_ref().unwrap_or_else(|| ctx.author());
+    let period = period.unwrap_or(StatsPeriod::Week);
+    let l10n = ctx.l10n_guild();
+
+    if get_voice_logging_config(guild_id, &ctx.data()).await?.is_none() {
+        send_text(ctx, l10n.t("voice-not-enabled", None), true).await?;
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+
+    let stats = voice_sessions::get_user_stats(guild_id, target.id, period, &ctx.data()).await?;
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("user", target.name.to_string());
+    args.set("period", l10n.t(period.l10n_key(), None));
+    let mut response = format!("{}\n\n", l10n.t("voice-stats-title", Some(&args)));
+
+    if stats.total == 0 {
+        response.push_str(&l10n.t("voice-no-data", None));
+    } else {
+        let mut args = fluent::FluentArgs::new();
+        args.set("total", format_duration(stats.total));
+        args.set("sessions", stats.sessions);
+        args.set("selfMuted", format_duration(stats.self_muted));
+        args.set("selfDeafened", format_duration(stats.self_deafened));
+        args.set("serverMuted", format_duration(stats.server_muted));
+        args.set("serverDeafened", format_duration(stats.server_deafened));
+        response.push_str(&l10n.t("voice-stats-summary", Some(&args)));
+
+        response.push_str(&format!("\n\n{}\n", l10n.t("voice-stats-channels", None)));
+        for (channel_id, seconds) in stats.per_channel.iter().take(10) {
+            response.push_str(&format!("<#{}> - {}\n", channel_id, format_duration(*seconds)));
+        }
+    }
+
+    send_text(ctx, response, false).await?;
+    Ok(())
+}
+
+/// View the users with the most time in voice
+#[poise::command(slash_command, guild_only)]
+pub async fn top(
+    ctx: Context<'_>,
+    #[description = "Time period (default: last 7 days)"] period: Option<StatsPeriod>,
+    #[description = "Number of users to show (default: 10)"]
+    #[min = 1]
+    #[max = 25]
+    limit: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let period = period.unwrap_or(StatsPeriod::Week);
+    let limit = limit.unwrap_or(10) as usize;
+    let l10n = ctx.l10n_guild();
+
+    if get_voice_logging_config(guild_id, &ctx.data()).await?.is_none() {
+        send_text(ctx, l10n.t("voice-not-enabled", None), true).await?;
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+
+    let top_users = voice_sessions::get_top_users(guild_id, period, limit, &ctx.data()).await?;
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("period", l10n.t(period.l10n_key(), None));
+    let mut response = format!("{}\n\n", l10n.t("voice-top-title", Some(&args)));
+
+    if top_users.is_empty() {
+        response.push_str(&l10n.t("voice-no-data", None));
+    } else {
+        for (idx, (user_id, seconds)) in top_users.iter().enumerate() {
+            let medal = match idx {
+                0 => "🥇",
+                1 => "🥈",
+                2 => "🥉",
+                _ => "  ",
+            };
+            response.push_str(&format!(
+                "{} **#{}** <@{}> - {}\n",
+                medal,
+                idx + 1,
+                user_id,
+                format_duration(*seconds)
+            ));
+        }
+    }
+
+    send_text(ctx, response, false).await?;
+    Ok(())
+}
+
+async fn send_text(ctx: Context<'_>, text: String, ephemeral: bool) -> Result<(), Error> {
+    let mut flags = serenity::MessageFlags::IS_COMPONENTS_V2;
+    if ephemeral {
+        flags |= serenity::MessageFlags::EPHEMERAL;
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .components(vec![serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(vec![
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(text),
+                    ),
+                ]),
+            )])
+            .flags(flags)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub fn commands() -> Vec<poise::Command<crate::Data, Error>> {
+    vec![voice()]
+}
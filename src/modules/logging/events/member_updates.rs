@@ -0,0 +1,245 @@
+use crate::db::entities::module_configs::{LoggingModuleConfig, ModuleType};
+use crate::services::logger::LogLevel;
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
+use serenity::model::guild::audit_log::{Action, Change, MemberAction};
+
+/// Check if the logging module is enabled for member update logging.
+/// Returns Some(config) if enabled, None if disabled.
+pub async fn get_member_update_logging_config(
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<Option<LoggingModuleConfig>, Error> {
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
+        .await?;
+
+    match m_config {
+        Some(m) => {
+            if !m.enabled {
+                return Ok(None);
+            }
+            let config: LoggingModuleConfig = serde_json::from_value(m.config).unwrap_or_default();
+            if !config.log_member_updates {
+                return Ok(None);
+            }
+            Ok(Some(config))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Logs nickname, role and timeout changes.
+/// These come from audit log entries, which carry both the executor and the old/new values.
+pub async fn handle_audit_log(
+    entry: &serenity::AuditLogEntry,
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<(), Error> {
+    // Check action type first to avoid unnecessary database calls
+    if !matches!(
+        entry.action,
+        Action::Member(MemberAction::RoleUpdate) | Action::Member(MemberAction::Update)
+    ) {
+        return Ok(());
+    }
+
+    let Some(target_id) = entry.target_id.map(|id| id.get()) else {
+        return Ok(());
+    };
+
+    let config = match get_member_update_logging_config(guild_id, data).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+
+    let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
+    let executor = entry
+        .user_id
+        .map(|id| format!("<@{}>", id.get()))
+        .unwrap_or_else(|| l10n.t("log-val-unknown", None));
+    let none_label = l10n.t("log-val-none", None);
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("userId", target_id.to_string());
+    args.set("executor", executor.clone());
+
+    let executor_label = l10n.text("log-field-acting-user", None);
+    let before_label = l10n.text("log-field-before", None);
+    let after_label = l10n.text("log-field-after", None);
+
+    for change in &entry.changes {
+        match change {
+            Change::Nick { old, new } if config.log_nickname_changes => {
+                let old = old
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| none_label.clone());
+                let new = new
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| none_label.clone());
+
+                data.logger
+                    .log_action(
+                        guild_id,
+                        Some(ModuleType::Logging),
+                        config.member_log_channel_id,
+                        LogLevel::Info,
+                        l10n.text("log-member-nick-title", None),
+                        l10n.text("log-member-nick-desc", Some(&args)),
+                        vec![
+                            (executor_label.clone(), executor.clone()),
+                            (before_label.clone(), old),
+                            (after_label.clone(), new),
+                        ],
+                    )
+                    .await?;
+            }
+            Change::CommunicationDisabledUntil { new, .. } if config.log_timeout_changes => {
+                let (title, desc, mut fields) = match new {
+                    Some(until) => (
+                        l10n.text("log-member-timeout-title", None),
+                        l10n.text("log-member-timeout-desc", Some(&args)),
+                        vec![(
+                            l10n.text("log-field-until", None),
+                            format!("<t:{}:F>", until.unix_timestamp()),
+                        )],
+                    ),
+                    None => (
+                        l10n.text("log-member-timeout-removed-title", None),
+                        l10n.text("log-member-timeout-removed-desc", Some(&args)),
+                        vec![],
+                    ),
+                };
+                fields.insert(0, (executor_label.clone(), executor.clone()));
+                if let Some(reason) = &entry.reason {
+                    fields.push((l10n.text("log-field-reason", None), reason.to_string()));
+                }
+
+                data.logger
+                    .log_action(
+                        guild_id,
+                        Some(ModuleType::Logging),
+                        config.member_log_channel_id,
+                        LogLevel::Info,
+                        title,
+                        desc,
+                        fields,
+                    )
+                    .await?;
+            }
+            _ => {}
+        }
+    }
+
+    if config.log_role_changes {
+        let mut added = vec![];
+        let mut removed = vec![];
+
+        for change in &entry.changes {
+            match change {
+                Change::RolesAdded { old, new } => {
+                    if let Some(roles) = new.as_ref().or(old.as_ref()) {
+                        added.extend(roles.iter().map(|r| format!("<@&{}>", r.id.get())));
+                    }
+                }
+                Change::RolesRemove { old, new } => {
+                    if let Some(roles) = new.as_ref().or(old.as_ref()) {
+                        removed.extend(roles.iter().map(|r| format!("<@&{}>", r.id.get())));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !added.is_empty() || !removed.is_empty() {
+            let mut fields = vec![(executor_label, executor.clone())];
+            if !added.is_empty() {
+                fields.push((l10n.text("log-member-roles-added", None), added.join(", ")));
+            }
+            if !removed.is_empty() {
+                fields.push((
+                    l10n.text("log-member-roles-removed", None),
+                    removed.join(", "),
+                ));
+            }
+
+            data.logger
+                .log_action(
+                    guild_id,
+                    Some(ModuleType::Logging),
+                    config.member_log_channel_id,
+                    LogLevel::Info,
+                    l10n.text("log-member-roles-title", None),
+                    l10n.text("log-member-roles-desc", Some(&args)),
+                    fields,
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Logs server avatar changes.
+/// Members change their own server avatar, so there is no audit log entry for it.
+pub async fn handle_guild_member_update(
+    old_if_available: Option<serenity::Member>,
+    event: serenity::GuildMemberUpdateEvent,
+    data: &Data,
+) -> Result<(), Error> {
+    // Without the cached member we cannot tell what changed
+    let Some(old) = old_if_available else {
+        return Ok(());
+    };
+
+    if old.avatar == event.avatar {
+        return Ok(());
+    }
+
+    let guild_id = event.guild_id;
+    let config = match get_member_update_logging_config(guild_id, data).await? {
+        Some(c) if c.log_avatar_changes => c,
+        _ => return Ok(()),
+    };
+
+    let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
+    let none_label = l10n.t("log-val-none", None);
+    let avatar_url = |hash: &Option<serenity::ImageHash>| {
+        hash.as_ref()
+            .map(|h| {
+                format!(
+                    "https://cdn.discordapp.com/guilds/{}/users/{}/avatars/{}.png",
+                    guild_id.get(),
+                    event.user.id.get(),
+                    h
+                )
+            })
+            .unwrap_or_else(|| none_label.clone())
+    };
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("userId", event.user.id.get().to_string());
+
+    data.logger
+        .log_action(
+            guild_id,
+            Some(ModuleType::Logging),
+            config.member_log_channel_id,
+            LogLevel::Info,
+            l10n.text("log-member-avatar-title", None),
+            l10n.text("log-member-avatar-desc", Some(&args)),
+            vec![
+                (l10n.text("log-field-before", None), avatar_url(&old.avatar)),
+                (
+                    l10n.text("log-field-after", None),
+                    avatar_url(&event.avatar),
+                ),
+            ],
+        )
+        .await?;
+
+    Ok(())
+}
@@ -1,7 +1,65 @@
-use crate::db::entities::module_configs::{self, LoggingModuleConfig, ModuleType};
+use crate::db::entities::module_configs::{LoggingModuleConfig, ModuleType};
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
+
+pub async fn handle_message_create(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+    data: &Data,
+) -> Result<(), Error> {
+    // Nothing to archive, skip before touching the database
+    if message.attachments.is_empty() || message.author.id == ctx.cache.current_user().id {
+        return Ok(());
+    }
+
+    // 1. Get module config and check if enabled
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
+        .await?;
+
+    let Some(m_config) = m_config else {
+        return Ok(());
+    };
+
+    // Check if the logging module is enabled globally
+    if !m_config.enabled {
+        return Ok(());
+    }
+
+    let config: LoggingModuleConfig =
+        serde_json::from_value(m_config.config).unwrap_or_default();
+
+    // Archiving is opt-in per channel
+    if !config.log_messages
+        || !config.archive_attachments
+        || !config
+            .archive_channel_ids
+            .contains(&(message.channel_id.get() as i64))
+    {
+        return Ok(());
+    }
+
+    if super::is_channel_ignored(ctx, guild_id, message.channel_id.get(), &config)
+        || super::is_user_ignored(
+            ctx,
+            guild_id,
+            message.author.id,
+            message.author.bot(),
+            None,
+            &config,
+        )
+    {
+        return Ok(());
+    }
+
+    data.attachment_archive
+        .archive_message(guild_id, message)
+        .await?;
+
+    Ok(())
+}
 
 pub async fn handle_message_delete(
     ctx: &serenity::Context,
@@ -11,8 +69,9 @@ pub async fn handle_message_delete(
     data: &Data,
 ) -> Result<(), Error> {
     // 1. Get module config and check if enabled
-    let m_config = module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::Logging))
-        .one(&data.db)
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
         .await?;
 
     let Some(m_config) = m_config else {
@@ -27,41 +86,81 @@ pub async fn handle_message_delete(
     let config: LoggingModuleConfig =
         serde_json::from_value(m_config.config).unwrap_or_default();
 
-    // Check if message logging is enabled specifically
-    if !config.log_messages {
+    // Check if message logging is enabled specifically; archived files are only kept
+    // for the delete log, so they go right away when there is none
+    if !config.log_messages || super::is_channel_ignored(ctx, guild_id, channel_id.get(), &config) {
+        data.attachment_archive
+            .discard_message_files(deleted_message_id)
+            .await?;
         return Ok(());
     }
 
-    let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
-
     // Try to get message from cache and extract data immediately to avoid Send issues
     let cached_data = ctx
         .cache
         .message(channel_id.into(), deleted_message_id)
-        .map(|msg| (msg.author.id, msg.content.to_string()));
+        .map(|msg| (msg.author.id, msg.author.bot(), msg.content.to_string()));
+
+    // The author is only known for cached messages
+    if let Some((author_id, is_bot, _)) = &cached_data {
+        if super::is_user_ignored(ctx, guild_id, *author_id, *is_bot, None, &config) {
+            data.attachment_archive
+                .discard_message_files(deleted_message_id)
+                .await?;
+            return Ok(());
+        }
+    }
+
+    let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
 
     let mut args = fluent::FluentArgs::new();
     args.set("channelId", channel_id.get().to_string());
     args.set("userId", 0);
 
-    let mut fields: Vec<(&str, String)> = vec![];
-    let content_label = l10n.t("log-msg-delete-content", None);
+    let mut fields = vec![];
 
-    if let Some((author_id, content)) = cached_data {
+    if let Some((author_id, _, content)) = cached_data {
         args.set("userId", author_id.get().to_string());
-        fields.push((content_label.as_str(), content));
+        fields.push((l10n.text("log-msg-delete-content", None), content));
     }
 
+    // Re-upload archived attachments, their CDN URLs are no longer valid
+    let archived_files = if config.archive_attachments {
+        data.attachment_archive
+            .message_files(deleted_message_id)
+            .await?
+    } else {
+        // Archiving was turned off after these were stored
+        data.attachment_archive
+            .discard_message_files(deleted_message_id)
+            .await?;
+        vec![]
+    };
+
+    if !archived_files.is_empty() {
+        let names = archived_files
+            .iter()
+            .map(|f| format!("`{}`", f.filename))
+            .collect::<Vec<_>>()
+            .join(", ");
+        fields.push((l10n.text("log-msg-delete-attachments", None), names));
+    }
+
+    let files = archived_files
+        .into_iter()
+        .map(|f| serenity::CreateAttachment::bytes(f.bytes, f.filename))
+        .collect();
+
     data.logger
-        .log_action(
-            &ctx.http,
+        .log_action_with_files(
             guild_id,
             Some(ModuleType::Logging),
             config.message_log_channel_id,
             crate::services::logger::LogLevel::Info,
-            &l10n.t("log-msg-delete-title", None),
-            &l10n.t("log-msg-delete-desc", Some(&args)),
+            l10n.text("log-msg-delete-title", None),
+            l10n.text("log-msg-delete-desc", Some(&args)),
             fields,
+            files,
         )
         .await?;
 
@@ -76,8 +175,9 @@ pub async fn handle_message_edit(
     data: &Data,
 ) -> Result<(), Error> {
     // 1. Get module config and check if enabled
-    let m_config = module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::Logging))
-        .one(&data.db)
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
         .await?;
 
     let Some(m_config) = m_config else {
@@ -97,6 +197,19 @@ pub async fn handle_message_edit(
         return Ok(());
     }
 
+    if super::is_channel_ignored(ctx, guild_id, new.message.channel_id.get(), &config)
+        || super::is_user_ignored(
+            ctx,
+            guild_id,
+            new.message.author.id,
+            new.message.author.bot(),
+            None,
+            &config,
+        )
+    {
+        return Ok(());
+    }
+
     // 2. Check if content changed
     let old_content = old_if_available
         .as_ref()
@@ -120,19 +233,21 @@ pub async fn handle_message_edit(
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::Logging),
             config.message_log_channel_id,
             crate::services::logger::LogLevel::Info,
-            &l10n.t("log-msg-edit-title", None),
-            &l10n.t("log-msg-edit-desc", Some(&args)),
+            l10n.text("log-msg-edit-title", None),
+            l10n.text("log-msg-edit-desc", Some(&args)),
             vec![
                 (
-                    &l10n.t("log-msg-edit-before", None),
+                    l10n.text("log-msg-edit-before", None),
                     old_content.to_string(),
                 ),
-                (&l10n.t("log-msg-edit-after", None), new_content.to_string()),
+                (
+                    l10n.text("log-msg-edit-after", None),
+                    new_content.to_string(),
+                ),
             ],
         )
         .await?;
@@ -1,58 +1,163 @@
+pub mod member_updates;
 pub mod membership;
 pub mod messages;
+pub mod server;
 pub mod voice;
 
+use crate::db::entities::module_configs::LoggingModuleConfig;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
 
-pub fn handler<'a>(
-    ctx: &'a serenity::Context,
-    event: &'a serenity::FullEvent,
-    data: &'a Data,
-) -> poise::BoxFuture<'a, Result<(), Error>> {
-    Box::pin(async move {
-        match event {
-            serenity::FullEvent::MessageUpdate {
-                old_if_available,
-                event,
-                ..
-            } => {
-                if let Some(guild_id) = event.message.guild_id {
-                    messages::handle_message_edit(
-                        ctx,
-                        guild_id,
-                        old_if_available.clone(),
-                        event.clone(),
-                        data,
-                    )
-                    .await?;
+/// Whether events in a channel are excluded from logging.
+/// A channel is ignored if it, its parent channel (for threads) or its category is listed.
+pub fn is_channel_ignored(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    channel_id: u64,
+    config: &LoggingModuleConfig,
+) -> bool {
+    if config.ignored_channel_ids.is_empty() && config.ignored_category_ids.is_empty() {
+        return false;
+    }
+
+    // Walk up from the channel: thread -> channel -> category
+    let mut chain = vec![channel_id];
+    if let Some(guild) = ctx.cache.guild(guild_id) {
+        let mut current = channel_id;
+        for _ in 0..2 {
+            let parent = guild
+                .channels
+                .get(&serenity::ChannelId::new(current))
+                .and_then(|c| c.parent_id.map(|p| p.get()))
+                .or_else(|| {
+                    guild
+                        .threads
+                        .iter()
+                        .find(|t| t.id.get() == current)
+                        .map(|t| t.parent_id.get())
+                });
+            match parent {
+                Some(parent) => {
+                    chain.push(parent);
+                    current = parent;
                 }
+                None => break,
             }
-            serenity::FullEvent::MessageDelete {
-                channel_id,
-                deleted_message_id,
-                guild_id,
-                ..
-            } => {
-                if let Some(guild_id) = guild_id {
-                    messages::handle_message_delete(
-                        ctx,
-                        *guild_id,
-                        serenity::ChannelId::new(channel_id.get()),
-                        *deleted_message_id,
-                        data,
-                    )
-                    .await?;
-                }
+        }
+    }
+
+    chain.iter().any(|id| {
+        config.ignored_channel_ids.contains(&(*id as i64))
+            || config.ignored_category_ids.contains(&(*id as i64))
+    })
+}
+
+/// Whether events caused by a user are excluded from logging: listed users, bots when
+/// bots are ignored, and members holding an ignored role.
+/// Roles are taken from the given list, or from the cache if none are given.
+pub fn is_user_ignored(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    is_bot: bool,
+    roles: Option<&[serenity::RoleId]>,
+    config: &LoggingModuleConfig,
+) -> bool {
+    if config.ignore_bots && is_bot {
+        return true;
+    }
+    if config.ignored_user_ids.contains(&(user_id.get() as i64)) {
+        return true;
+    }
+    if config.ignored_role_ids.is_empty() {
+        return false;
+    }
+
+    let has_ignored_role = |roles: &[serenity::RoleId]| {
+        roles
+            .iter()
+            .any(|r| config.ignored_role_ids.contains(&(r.get() as i64)))
+    };
+
+    match roles {
+        Some(roles) => has_ignored_role(roles),
+        None => ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|g| g.members.get(&user_id).map(|m| has_ignored_role(&m.roles)))
+            .unwrap_or(false),
+    }
+}
+
+pub async fn handle_event(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    data: &Data,
+) -> Result<(), Error> {
+    match event {
+        serenity::FullEvent::Message { new_message, .. } => {
+            if let Some(guild_id) = new_message.guild_id {
+                messages::handle_message_create(ctx, guild_id, new_message, data).await?;
             }
-            serenity::FullEvent::VoiceStateUpdate { old, new, .. } => {
-                if let Some(guild_id) = new.guild_id {
-                    voice::handle_voice_state_update(ctx, guild_id, old.clone(), new.clone(), data)
-                        .await?;
-                }
+        }
+        serenity::FullEvent::MessageUpdate {
+            old_if_available,
+            event,
+            ..
+        } => {
+            if let Some(guild_id) = event.message.guild_id {
+                messages::handle_message_edit(
+                    ctx,
+                    guild_id,
+                    old_if_available.clone(),
+                    event.clone(),
+                    data,
+                )
+                .await?;
             }
-            _ => {}
         }
-        Ok(())
-    })
+        serenity::FullEvent::MessageDelete {
+            channel_id,
+            deleted_message_id,
+            guild_id,
+            ..
+        } => {
+            if let Some(guild_id) = guild_id {
+                messages::handle_message_delete(
+                    ctx,
+                    *guild_id,
+                    serenity::ChannelId::new(channel_id.get()),
+                    *deleted_message_id,
+                    data,
+                )
+                .await?;
+            }
+        }
+        serenity::FullEvent::VoiceStateUpdate { old, new, .. } => {
+            if let Some(guild_id) = new.guild_id {
+                voice::handle_voice_state_update(ctx, guild_id, old.clone(), new.clone(), data)
+                    .await?;
+            }
+        }
+        serenity::FullEvent::GuildAuditLogEntryCreate {
+            entry, guild_id, ..
+        } => {
+            member_updates::handle_audit_log(entry, *guild_id, data).await?;
+            server::handle_audit_log(entry, *guild_id, data).await?;
+        }
+        serenity::FullEvent::GuildMemberUpdate {
+            old_if_available,
+            event,
+            ..
+        } => {
+            member_updates::handle_guild_member_update(
+                old_if_available.clone(),
+                event.clone(),
+                data,
+            )
+            .await?;
+        }
+        _ => {}
+    }
+    Ok(())
 }
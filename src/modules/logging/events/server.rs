@@ -0,0 +1,213 @@
+use crate::db::entities::module_configs::{LoggingModuleConfig, ModuleType};
+use crate::services::logger::LogLevel;
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
+use serenity::model::guild::audit_log::{
+    Action, Change, ChannelAction, ChannelOverwriteAction, EmojiAction, InviteAction, RoleAction,
+};
+
+/// Longest rendering of a single old/new value before it gets cut off.
+const MAX_VALUE_LEN: usize = 200;
+
+/// Change keys that hold permission bitsets and are rendered as permission names.
+const PERMISSION_KEYS: [&str; 3] = ["permissions", "allow", "deny"];
+
+/// Check if the logging module is enabled for server logging.
+/// Returns Some(config) if enabled, None if disabled.
+pub async fn get_server_logging_config(
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<Option<LoggingModuleConfig>, Error> {
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
+        .await?;
+
+    match m_config {
+        Some(m) => {
+            if !m.enabled {
+                return Ok(None);
+            }
+            let config: LoggingModuleConfig = serde_json::from_value(m.config).unwrap_or_default();
+            if !config.log_server {
+                return Ok(None);
+            }
+            Ok(Some(config))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Logs channel, overwrite, role, emoji, invite and guild setting changes as neutral
+/// server log entries, independent of whether any protection module is enabled.
+pub async fn handle_audit_log(
+    entry: &serenity::AuditLogEntry,
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<(), Error> {
+    // Check action type first to avoid unnecessary database calls
+    let Some((target, kind)) = classify(&entry.action) else {
+        return Ok(());
+    };
+
+    let config = match get_server_logging_config(guild_id, data).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+
+    let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
+    let none_label = l10n.t("log-val-none", None);
+
+    let executor = entry
+        .user_id
+        .map(|id| format!("<@{}>", id.get()))
+        .unwrap_or_else(|| l10n.t("log-val-unknown", None));
+
+    let target_label = l10n.t(&format!("log-server-target-{}", target), None);
+
+    let mut title_args = fluent::FluentArgs::new();
+    title_args.set("target", target_label.clone());
+    title_args.set("action", l10n.t(&format!("log-server-action-{}", kind), None));
+
+    let mut desc_args = fluent::FluentArgs::new();
+    desc_args.set("executor", executor.clone());
+    desc_args.set(
+        "target",
+        render_target(target, entry).unwrap_or_else(|| format!("**{}**", target_label)),
+    );
+
+    let diff = entry
+        .changes
+        .iter()
+        .filter_map(|change| render_change(change, &none_label))
+        .collect::<Vec<_>>();
+
+    let mut fields = vec![(l10n.text("log-field-acting-user", None), executor.clone())];
+    if !diff.is_empty() {
+        fields.push((l10n.text("log-server-changes", None), diff.join("\n")));
+    }
+    if let Some(reason) = &entry.reason {
+        fields.push((l10n.text("log-field-reason", None), reason.to_string()));
+    }
+
+    let level = if kind == "delete" {
+        LogLevel::Warn
+    } else {
+        LogLevel::Info
+    };
+
+    data.logger
+        .log_action(
+            guild_id,
+            Some(ModuleType::Logging),
+            config.server_log_channel_id,
+            level,
+            l10n.text("log-server-title", Some(&title_args)),
+            l10n.text("log-server-desc", Some(&desc_args)),
+            fields,
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Maps an audit log action to the target type and action kind used in the locale keys.
+fn classify(action: &Action) -> Option<(&'static str, &'static str)> {
+    let result = match action {
+        Action::GuildUpdate => ("guild", "update"),
+        Action::Channel(ChannelAction::Create) => ("channel", "create"),
+        Action::Channel(ChannelAction::Update) => ("channel", "update"),
+        Action::Channel(ChannelAction::Delete) => ("channel", "delete"),
+        Action::ChannelOverwrite(ChannelOverwriteAction::Create) => ("overwrite", "create"),
+        Action::ChannelOverwrite(ChannelOverwriteAction::Update) => ("overwrite", "update"),
+        Action::ChannelOverwrite(ChannelOverwriteAction::Delete) => ("overwrite", "delete"),
+        Action::Role(RoleAction::Create) => ("role", "create"),
+        Action::Role(RoleAction::Update) => ("role", "update"),
+        Action::Role(RoleAction::Delete) => ("role", "delete"),
+        Action::Emoji(EmojiAction::Create) => ("emoji", "create"),
+        Action::Emoji(EmojiAction::Update) => ("emoji", "update"),
+        Action::Emoji(EmojiAction::Delete) => ("emoji", "delete"),
+        Action::Invite(InviteAction::Create) => ("invite", "create"),
+        Action::Invite(InviteAction::Update) => ("invite", "update"),
+        Action::Invite(InviteAction::Delete) => ("invite", "delete"),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Renders the affected object as a mention where Discord supports one, with its ID.
+/// Returns None for invites and the guild itself, which have no target ID.
+fn render_target(target: &str, entry: &serenity::AuditLogEntry) -> Option<String> {
+    let id = entry.target_id?.get();
+    let rendered = match target {
+        "channel" | "overwrite" => format!("<#{}> (`{}`)", id, id),
+        "role" => format!("<@&{}> (`{}`)", id, id),
+        _ => format!("`{}`", id),
+    };
+    Some(rendered)
+}
+
+/// Renders a single change as a `key: old → new` line.
+/// Returns None if neither side carries a value.
+fn render_change(change: &Change, none_label: &str) -> Option<String> {
+    // Changes serialize as `{"key": ..., "old_value": ..., "new_value": ...}`, which lets us
+    // render every variant the same way instead of matching on each one.
+    let value = serde_json::to_value(change).ok()?;
+    let key = value.get("key")?.as_str()?.to_string();
+    let old = value.get("old_value").filter(|v| !v.is_null());
+    let new = value.get("new_value").filter(|v| !v.is_null());
+
+    if old.is_none() && new.is_none() {
+        return None;
+    }
+
+    let render = |v: Option<&serde_json::Value>| match v {
+        Some(v) => render_value(&key, v),
+        None => none_label.to_string(),
+    };
+
+    Some(format!("**{}**: {} → {}", key, render(old), render(new)))
+}
+
+fn render_value(key: &str, value: &serde_json::Value) -> String {
+    if PERMISSION_KEYS.contains(&key) {
+        let bits = match value {
+            serde_json::Value::String(s) => s.parse::<u64>().ok(),
+            serde_json::Value::Number(n) => n.as_u64(),
+            _ => None,
+        };
+        if let Some(bits) = bits {
+            let names = serenity::Permissions::from_bits_truncate(bits).get_permission_names();
+            return if names.is_empty() {
+                "`-`".to_string()
+            } else {
+                format!("`{}`", truncate(&names.join(", ")))
+            };
+        }
+    }
+
+    let text = match value {
+        serde_json::Value::String(s) => s.clone(),
+        // Role lists ($add / $remove) carry objects with a name
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| match item.get("name").and_then(|n| n.as_str()) {
+                Some(name) => name.to_string(),
+                None => item.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    };
+
+    format!("`{}`", truncate(&text.replace('`', "'")))
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() > MAX_VALUE_LEN {
+        let cut: String = text.chars().take(MAX_VALUE_LEN).collect();
+        format!("{}…", cut)
+    } else {
+        text.to_string()
+    }
+}
@@ -1,27 +1,26 @@
-use crate::db::entities::module_configs::{self, LoggingModuleConfig, ModuleType};
+use crate::db::entities::module_configs::{LoggingModuleConfig, ModuleType};
+use crate::modules::logging::voice_sessions;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 
-pub async fn handle_voice_state_update(
-    ctx: &serenity::Context,
+/// Check if the logging module is enabled for voice logging.
+/// Returns Some(config) if enabled, None if disabled.
+pub async fn get_voice_logging_config(
     guild_id: serenity::GuildId,
-    old: Option<serenity::VoiceState>,
-    new: serenity::VoiceState,
     data: &Data,
-) -> Result<(), Error> {
-    // 1. Get module config and check if enabled
-    let m_config = module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::Logging))
-        .one(&data.db)
+) -> Result<Option<LoggingModuleConfig>, Error> {
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
         .await?;
 
     let Some(m_config) = m_config else {
-        return Ok(());
+        return Ok(None);
     };
 
     // Check if the logging module is enabled globally
     if !m_config.enabled {
-        return Ok(());
+        return Ok(None);
     }
 
     let config: LoggingModuleConfig =
@@ -29,6 +28,50 @@ pub async fn handle_voice_state_update(
 
     // Check if voice logging is enabled specifically
     if !config.log_voice {
+        return Ok(None);
+    }
+
+    Ok(Some(config))
+}
+
+pub async fn handle_voice_state_update(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    old: Option<serenity::VoiceState>,
+    new: serenity::VoiceState,
+    data: &Data,
+) -> Result<(), Error> {
+    let Some(config) = get_voice_logging_config(guild_id, data).await? else {
+        return Ok(());
+    };
+
+    // Persist the session before logging so the leave log can show its length
+    let session_length = voice_sessions::track_voice_state(guild_id, &new, data).await?;
+
+    // Ignore lists only affect the log, sessions are still tracked
+    let (is_bot, roles) = match &new.member {
+        Some(member) => (
+            member.user.bot(),
+            Some(member.roles.iter().cloned().collect::<Vec<_>>()),
+        ),
+        None => (false, None),
+    };
+    if super::is_user_ignored(ctx, guild_id, new.user_id, is_bot, roles.as_deref(), &config) {
+        return Ok(());
+    }
+
+    // A change is only skipped if every channel involved is ignored,
+    // so moving out of an ignored channel is still logged
+    let channels: Vec<u64> = [old.as_ref().and_then(|s| s.channel_id), new.channel_id]
+        .into_iter()
+        .flatten()
+        .map(|c| c.get())
+        .collect();
+    if !channels.is_empty()
+        && channels
+            .iter()
+            .all(|c| super::is_channel_ignored(ctx, guild_id, *c, &config))
+    {
         return Ok(());
     }
 
@@ -47,13 +90,12 @@ pub async fn handle_voice_state_update(
 
                 data.logger
                     .log_action(
-                        &ctx.http,
                         guild_id,
                         Some(ModuleType::Logging),
                         config.voice_log_channel_id,
                         crate::services::logger::LogLevel::Info,
-                        &l10n.t("log-voice-join-title", None),
-                        &l10n.t("log-voice-join-desc", Some(&args)),
+                        l10n.text("log-voice-join-title", None),
+                        l10n.text("log-voice-join-desc", Some(&args)),
                         vec![],
                     )
                     .await?;
@@ -64,16 +106,23 @@ pub async fn handle_voice_state_update(
                 args.set("userId", user_id.get().to_string());
                 args.set("channelId", channel_id.get().to_string());
 
+                let mut fields = vec![];
+                if let Some(length) = session_length {
+                    fields.push((
+                        l10n.text("log-field-duration", None),
+                        crate::services::duration::format_duration(length.num_seconds()),
+                    ));
+                }
+
                 data.logger
                     .log_action(
-                        &ctx.http,
                         guild_id,
                         Some(ModuleType::Logging),
                         config.voice_log_channel_id,
                         crate::services::logger::LogLevel::Info,
-                        &l10n.t("log-voice-leave-title", None),
-                        &l10n.t("log-voice-leave-desc", Some(&args)),
-                        vec![],
+                        l10n.text("log-voice-leave-title", None),
+                        l10n.text("log-voice-leave-desc", Some(&args)),
+                        fields,
                     )
                     .await?;
             }
@@ -86,13 +135,12 @@ pub async fn handle_voice_state_update(
 
                 data.logger
                     .log_action(
-                        &ctx.http,
                         guild_id,
                         Some(ModuleType::Logging),
                         config.voice_log_channel_id,
                         crate::services::logger::LogLevel::Info,
-                        &l10n.t("log-voice-move-title", None),
-                        &l10n.t("log-voice-move-desc", Some(&args)),
+                        l10n.text("log-voice-move-title", None),
+                        l10n.text("log-voice-move-desc", Some(&args)),
                         vec![],
                     )
                     .await?;
@@ -124,13 +172,12 @@ pub async fn handle_voice_state_update(
 
             data.logger
                 .log_action(
-                    &ctx.http,
                     guild_id,
                     Some(ModuleType::Logging),
                     config.voice_log_channel_id,
                     crate::services::logger::LogLevel::Info,
-                    &l10n.t("log-voice-state-title", None),
-                    &l10n.t("log-voice-state-desc", Some(&args)),
+                    l10n.text("log-voice-state-title", None),
+                    l10n.text("log-voice-state-desc", Some(&args)),
                     vec![],
                 )
                 .await?;
@@ -1,15 +1,76 @@
+pub mod commands;
 pub mod events;
+pub mod voice_sessions;
 
-use crate::modules::{Module, ModuleDefinition};
+use crate::modules::{EventKind, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
 
-pub fn module() -> Module {
-    Module {
-        definition: ModuleDefinition {
+pub struct Logging;
+
+#[serenity::async_trait]
+impl GuardModule for Logging {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
             id: "logging",
             name_key: "module-logging-name",
             desc_key: "module-logging-desc",
-        },
-        commands: vec![],
-        event_handlers: vec![events::handler],
+        }
+    }
+
+    fn commands(&self) -> Vec<poise::Command<Data, Error>> {
+        commands::commands()
+    }
+
+    fn events(&self) -> &'static [EventKind] {
+        &[
+            EventKind::GuildAuditLogEntryCreate,
+            EventKind::GuildMemberUpdate,
+            EventKind::Message,
+            EventKind::MessageUpdate,
+            EventKind::MessageDelete,
+            EventKind::VoiceStateUpdate,
+        ]
+    }
+
+    fn intents(&self) -> serenity::GatewayIntents {
+        serenity::GatewayIntents::GUILD_MESSAGES
+            | serenity::GatewayIntents::MESSAGE_CONTENT
+            | serenity::GatewayIntents::GUILD_VOICE_STATES
+    }
+
+    async fn on_guild_join(
+        &self,
+        _ctx: &serenity::Context,
+        guild: &serenity::Guild,
+        _is_new: bool,
+        data: &Data,
+    ) -> Result<(), Error> {
+        // Close sessions left open while offline and pick up members already in voice
+        if events::voice::get_voice_logging_config(guild.id, data)
+            .await?
+            .is_some()
+        {
+            voice_sessions::reconcile_guild(guild, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_event(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        data: &Data,
+    ) -> Result<(), Error> {
+        events::handle_event(ctx, event, data).await
+    }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(ctx, guild_id, serenity::Permissions::VIEW_AUDIT_LOG)
     }
 }
@@ -0,0 +1,467 @@
+use crate::db::entities::voice_intervals::{self, VoiceIntervalKind};
+use crate::db::entities::voice_sessions;
+use crate::{Data, Error};
+use chrono::{DateTime, Duration, Utc};
+use poise::serenity_prelude as serenity;
+use sea_orm::sea_query::{Expr, OnConflict, SimpleExpr};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Select, Set,
+};
+
+/// Time range for voice statistics.
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum StatsPeriod {
+    #[name = "day"]
+    Day,
+    #[name = "week"]
+    Week,
+    #[name = "month"]
+    Month,
+    #[name = "all"]
+    All,
+}
+
+impl StatsPeriod {
+    /// Start of the period, or None for all time.
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        let now = Utc::now();
+        match self {
+            StatsPeriod::Day => Some(now - Duration::days(1)),
+            StatsPeriod::Week => Some(now - Duration::days(7)),
+            StatsPeriod::Month => Some(now - Duration::days(30)),
+            StatsPeriod::All => None,
+        }
+    }
+
+    pub fn l10n_key(&self) -> &'static str {
+        match self {
+            StatsPeriod::Day => "voice-period-day",
+            StatsPeriod::Week => "voice-period-week",
+            StatsPeriod::Month => "voice-period-month",
+            StatsPeriod::All => "voice-period-all",
+        }
+    }
+}
+
+/// Voice time of a single user over a period, in seconds.
+#[derive(Debug, Default)]
+pub struct UserVoiceStats {
+    pub total: i64,
+    pub per_channel: Vec<(i64, i64)>,
+    pub self_muted: i64,
+    pub self_deafened: i64,
+    pub server_muted: i64,
+    pub server_deafened: i64,
+    pub sessions: usize,
+}
+
+/// A change to one kind of open interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntervalChange {
+    Close(VoiceIntervalKind),
+    Open(VoiceIntervalKind),
+}
+
+/// Works out which intervals to close and open to go from the open ones to the new
+/// voice state. `open` holds the kind and channel of each open interval, `channel_id`
+/// is the channel the member is now in, or None if they left.
+fn interval_changes(
+    open: &[(VoiceIntervalKind, Option<i64>)],
+    channel_id: Option<i64>,
+    desired: &[(VoiceIntervalKind, bool)],
+) -> Vec<IntervalChange> {
+    let mut changes = vec![];
+
+    for &(kind, active) in desired {
+        let active = active && channel_id.is_some();
+        let current = open.iter().find(|(k, _)| *k == kind);
+
+        // A channel hop closes the old channel interval and opens a new one
+        let stale = match current {
+            Some((_, current_channel)) => {
+                !active || (kind == VoiceIntervalKind::Channel && *current_channel != channel_id)
+            }
+            None => false,
+        };
+
+        if stale {
+            changes.push(IntervalChange::Close(kind));
+        }
+        if active && (current.is_none() || stale) {
+            changes.push(IntervalChange::Open(kind));
+        }
+    }
+
+    changes
+}
+
+/// Brings the stored session of a member in line with their current voice state.
+/// Opens and closes the session, channel intervals and mute/deafen intervals as needed.
+/// Returns the length of the session if this update ended it.
+///
+/// Expects the updates of a member one at a time and in gateway order, which the
+/// logging module's ordered dispatch provides.
+pub async fn track_voice_state(
+    guild_id: serenity::GuildId,
+    state: &serenity::VoiceState,
+    data: &Data,
+) -> Result<Option<Duration>, Error> {
+    let now = Utc::now();
+    let user_id = state.user_id.get() as i64;
+    let channel_id = state.channel_id.map(|c| c.get() as i64);
+
+    let session = find_open_session(guild_id, user_id, data).await?;
+
+    let session = match (session, channel_id) {
+        (Some(session), _) => session,
+        (None, Some(_)) => open_session(guild_id, user_id, now, data).await?,
+        (None, None) => return Ok(None),
+    };
+
+    let open = voice_intervals::Entity::find()
+        .filter(voice_intervals::Column::SessionId.eq(session.id))
+        .filter(voice_intervals::Column::EndedAt.is_null())
+        .all(&data.db)
+        .await?
+        .into_iter()
+        .map(|i| (i.kind, i.channel_id))
+        .collect::<Vec<_>>();
+
+    let desired = [
+        (VoiceIntervalKind::Channel, true),
+        (VoiceIntervalKind::SelfMute, state.self_mute()),
+        (VoiceIntervalKind::SelfDeaf, state.self_deaf()),
+        (VoiceIntervalKind::ServerMute, state.mute()),
+        (VoiceIntervalKind::ServerDeaf, state.deaf()),
+    ];
+
+    for change in interval_changes(&open, channel_id, &desired) {
+        match change {
+            IntervalChange::Close(kind) => {
+                close_open_intervals(session.id, Some(kind), now, data).await?;
+            }
+            IntervalChange::Open(kind) => {
+                let model = voice_intervals::ActiveModel {
+                    session_id: Set(session.id),
+                    guild_id: Set(guild_id.get() as i64),
+                    user_id: Set(user_id),
+                    kind: Set(kind),
+                    channel_id: Set(channel_id),
+                    started_at: Set(now.into()),
+                    ended_at: Set(None),
+                    ..Default::default()
+                };
+                model.insert(&data.db).await?;
+            }
+        }
+    }
+
+    if channel_id.is_some() {
+        return Ok(None);
+    }
+
+    // Left voice: end the session as well
+    let started_at = session.started_at.with_timezone(&Utc);
+    let mut active: voice_sessions::ActiveModel = session.into();
+    active.ended_at = Set(Some(now.into()));
+    active.update(&data.db).await?;
+
+    Ok(Some(now - started_at))
+}
+
+async fn find_open_session(
+    guild_id: serenity::GuildId,
+    user_id: i64,
+    data: &Data,
+) -> Result<Option<voice_sessions::Model>, Error> {
+    Ok(voice_sessions::Entity::find()
+        .filter(voice_sessions::Column::GuildId.eq(guild_id.get() as i64))
+        .filter(voice_sessions::Column::UserId.eq(user_id))
+        .filter(voice_sessions::Column::EndedAt.is_null())
+        .one(&data.db)
+        .await?)
+}
+
+/// Starts a session for the member, or returns the one already open.
+/// The partial unique index on open sessions keeps a member from having two.
+async fn open_session(
+    guild_id: serenity::GuildId,
+    user_id: i64,
+    now: DateTime<Utc>,
+    data: &Data,
+) -> Result<voice_sessions::Model, Error> {
+    let model = voice_sessions::ActiveModel {
+        guild_id: Set(guild_id.get() as i64),
+        user_id: Set(user_id),
+        started_at: Set(now.into()),
+        ended_at: Set(None),
+        ..Default::default()
+    };
+
+    voice_sessions::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                voice_sessions::Column::GuildId,
+                voice_sessions::Column::UserId,
+            ])
+            .target_and_where(Expr::col(voice_sessions::Column::EndedAt).is_null())
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&data.db)
+        .await?;
+
+    find_open_session(guild_id, user_id, data)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Open voice session of user {} disappeared", user_id))
+}
+
+/// Closes sessions left open while the bot was offline and opens sessions for members
+/// already in voice, using the voice states delivered with the guild.
+///
+/// Sessions of members who left while the bot was offline are ended at reconciliation
+/// time, as the real leave time is unknown.
+pub async fn reconcile_guild(guild: &serenity::Guild, data: &Data) -> Result<(), Error> {
+    let open_sessions = voice_sessions::Entity::find()
+        .filter(voice_sessions::Column::GuildId.eq(guild.id.get() as i64))
+        .filter(voice_sessions::Column::EndedAt.is_null())
+        .all(&data.db)
+        .await?;
+
+    for session in open_sessions {
+        let user_id = serenity::UserId::new(session.user_id as u64);
+        let in_voice = guild
+            .voice_states
+            .iter()
+            .any(|s| s.user_id == user_id && s.channel_id.is_some());
+
+        if !in_voice {
+            let now = Utc::now();
+            close_open_intervals(session.id, None, now, data).await?;
+            let mut active: voice_sessions::ActiveModel = session.into();
+            active.ended_at = Set(Some(now.into()));
+            active.update(&data.db).await?;
+        }
+    }
+
+    for state in guild.voice_states.iter() {
+        if state.channel_id.is_some() {
+            track_voice_state(guild.id, state, data).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn close_open_intervals(
+    session_id: i64,
+    kind: Option<VoiceIntervalKind>,
+    now: DateTime<Utc>,
+    data: &Data,
+) -> Result<(), Error> {
+    let mut query = voice_intervals::Entity::update_many()
+        .col_expr(
+            voice_intervals::Column::EndedAt,
+            sea_orm::sea_query::Expr::value(sea_orm::prelude::DateTimeWithTimeZone::from(now)),
+        )
+        .filter(voice_intervals::Column::SessionId.eq(session_id))
+        .filter(voice_intervals::Column::EndedAt.is_null());
+
+    if let Some(kind) = kind {
+        query = query.filter(voice_intervals::Column::Kind.eq(kind));
+    }
+
+    query.exec(&data.db).await?;
+    Ok(())
+}
+
+/// Intervals of the guild that overlap the period, optionally for a single user.
+fn intervals_in_period(
+    guild_id: serenity::GuildId,
+    user_id: Option<serenity::UserId>,
+    since: Option<DateTime<Utc>>,
+) -> Select<voice_intervals::Entity> {
+    let mut query = voice_intervals::Entity::find()
+        .filter(voice_intervals::Column::GuildId.eq(guild_id.get() as i64));
+
+    if let Some(user_id) = user_id {
+        query = query.filter(voice_intervals::Column::UserId.eq(user_id.get() as i64));
+    }
+
+    if let Some(since) = since {
+        query = query.filter(
+            sea_orm::Condition::any()
+                .add(voice_intervals::Column::EndedAt.is_null())
+                .add(voice_intervals::Column::EndedAt.gt(since)),
+        );
+    }
+
+    query
+}
+
+/// Sum of the seconds of the selected intervals that fall inside the period.
+/// Open intervals count up to now.
+fn clipped_seconds_sum(since: Option<DateTime<Utc>>) -> SimpleExpr {
+    let start = match since {
+        Some(_) => r#"GREATEST("started_at", ?)"#,
+        None => r#""started_at""#,
+    };
+    let sql = format!(
+        r#"COALESCE(SUM(GREATEST(EXTRACT(EPOCH FROM COALESCE("ended_at", NOW()) - {start}), 0)), 0)::BIGINT"#
+    );
+
+    match since {
+        Some(since) => Expr::cust_with_values(sql, [since]),
+        None => Expr::cust(sql),
+    }
+}
+
+pub async fn get_user_stats(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    period: StatsPeriod,
+    data: &Data,
+) -> Result<UserVoiceStats, Error> {
+    let since = period.since();
+
+    let totals: Vec<(VoiceIntervalKind, Option<i64>, i64)> =
+        intervals_in_period(guild_id, Some(user_id), since)
+            .select_only()
+            .column(voice_intervals::Column::Kind)
+            .column(voice_intervals::Column::ChannelId)
+            .column_as(clipped_seconds_sum(since), "seconds")
+            .group_by(voice_intervals::Column::Kind)
+            .group_by(voice_intervals::Column::ChannelId)
+            .into_tuple()
+            .all(&data.db)
+            .await?;
+
+    let sessions: i64 = intervals_in_period(guild_id, Some(user_id), since)
+        .filter(voice_intervals::Column::Kind.eq(VoiceIntervalKind::Channel))
+        .select_only()
+        .column_as(Expr::cust(r#"COUNT(DISTINCT "session_id")"#), "sessions")
+        .into_tuple()
+        .one(&data.db)
+        .await?
+        .unwrap_or(0);
+
+    let mut stats = UserVoiceStats::default();
+    for (kind, channel_id, seconds) in totals {
+        match kind {
+            VoiceIntervalKind::Channel => {
+                stats.total += seconds;
+                if let Some(channel_id) = channel_id {
+                    stats.per_channel.push((channel_id, seconds));
+                }
+            }
+            VoiceIntervalKind::SelfMute => stats.self_muted += seconds,
+            VoiceIntervalKind::SelfDeaf => stats.self_deafened += seconds,
+            VoiceIntervalKind::ServerMute => stats.server_muted += seconds,
+            VoiceIntervalKind::ServerDeaf => stats.server_deafened += seconds,
+        }
+    }
+
+    stats.per_channel.sort_by(|a, b| b.1.cmp(&a.1));
+    stats.sessions = sessions as usize;
+    Ok(stats)
+}
+
+/// Users with the most time in voice over the period, as (user_id, seconds).
+pub async fn get_top_users(
+    guild_id: serenity::GuildId,
+    period: StatsPeriod,
+    limit: usize,
+    data: &Data,
+) -> Result<Vec<(i64, i64)>, Error> {
+    let since = period.since();
+    let seconds = clipped_seconds_sum(since);
+
+    Ok(intervals_in_period(guild_id, None, since)
+        .filter(voice_intervals::Column::Kind.eq(VoiceIntervalKind::Channel))
+        .select_only()
+        .column(voice_intervals::Column::UserId)
+        .column_as(seconds.clone(), "seconds")
+        .group_by(voice_intervals::Column::UserId)
+        .having(Expr::expr(seconds.clone()).gt(0))
+        .order_by_desc(seconds)
+        .limit(limit as u64)
+        .into_tuple()
+        .all(&data.db)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desired(self_mute: bool) -> [(VoiceIntervalKind, bool); 5] {
+        [
+            (VoiceIntervalKind::Channel, true),
+            (VoiceIntervalKind::SelfMute, self_mute),
+            (VoiceIntervalKind::SelfDeaf, false),
+            (VoiceIntervalKind::ServerMute, false),
+            (VoiceIntervalKind::ServerDeaf, false),
+        ]
+    }
+
+    #[test]
+    fn test_interval_changes_join() {
+        assert_eq!(
+            interval_changes(&[], Some(1), &desired(true)),
+            vec![
+                IntervalChange::Open(VoiceIntervalKind::Channel),
+                IntervalChange::Open(VoiceIntervalKind::SelfMute),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interval_changes_move() {
+        let open = [
+            (VoiceIntervalKind::Channel, Some(1)),
+            (VoiceIntervalKind::SelfMute, Some(1)),
+        ];
+        assert_eq!(
+            interval_changes(&open, Some(2), &desired(true)),
+            vec![
+                IntervalChange::Close(VoiceIntervalKind::Channel),
+                IntervalChange::Open(VoiceIntervalKind::Channel),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interval_changes_state() {
+        let open = [(VoiceIntervalKind::Channel, Some(1))];
+        assert_eq!(interval_changes(&open, Some(1), &desired(false)), vec![]);
+        assert_eq!(
+            interval_changes(&open, Some(1), &desired(true)),
+            vec![IntervalChange::Open(VoiceIntervalKind::SelfMute)]
+        );
+
+        let open = [
+            (VoiceIntervalKind::Channel, Some(1)),
+            (VoiceIntervalKind::SelfMute, Some(1)),
+        ];
+        assert_eq!(
+            interval_changes(&open, Some(1), &desired(false)),
+            vec![IntervalChange::Close(VoiceIntervalKind::SelfMute)]
+        );
+    }
+
+    #[test]
+    fn test_interval_changes_leave() {
+        let open = [
+            (VoiceIntervalKind::Channel, Some(1)),
+            (VoiceIntervalKind::SelfMute, Some(1)),
+        ];
+        assert_eq!(
+            interval_changes(&open, None, &desired(true)),
+            vec![
+                IntervalChange::Close(VoiceIntervalKind::Channel),
+                IntervalChange::Close(VoiceIntervalKind::SelfMute),
+            ]
+        );
+        assert_eq!(interval_changes(&[], None, &desired(true)), vec![]);
+    }
+}
@@ -2,7 +2,6 @@ use crate::db::entities::module_configs::{self, ModuleType};
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, Change, MemberAction};
 
 pub async fn handle_audit_log(
@@ -16,12 +15,10 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    let config_model = match module_configs::Entity::find_by_id((
-        guild_id.get() as i64,
-        ModuleType::MemberPermissionProtection,
-    ))
-    .one(&data.db)
-    .await?
+    let config_model = match data
+        .config_cache
+        .module_config(guild_id, ModuleType::MemberPermissionProtection)
+        .await?
     {
         Some(m) => {
             if !m.enabled {
@@ -50,6 +47,7 @@ pub async fn handle_audit_log(
             guild_id,
             user_id,
             ModuleType::MemberPermissionProtection,
+            Some("update"),
         )
         .await?;
 
@@ -193,7 +191,7 @@ async fn handle_member_role_update(
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::MemberPermissionProtection,
@@ -251,9 +249,9 @@ async fn handle_member_role_update(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-member-perm-title-whitelisted", None)
+        l10n.text("log-member-perm-title-whitelisted", None)
     } else {
-        l10n.t("log-member-perm-title-blocked", None)
+        l10n.text("log-member-perm-title-blocked", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -264,31 +262,30 @@ async fn handle_member_role_update(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("userId", user_id.get().to_string());
     desc_args.set("targetId", target_user_id.to_string());
-    let desc = l10n.t("log-member-perm-desc", Some(&desc_args));
+    let desc = l10n.text("log-member-perm-desc", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::MemberPermissionProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
                 (
-                    &l10n.t("log-field-acting-user", None),
+                    l10n.text("log-field-acting-user", None),
                     format!("<@{}>", user_id.get()),
                 ),
                 (
-                    &l10n.t("log-field-target-member", None),
+                    l10n.text("log-field-target-member", None),
                     format!("<@{}>", target_user_id),
                 ),
                 (
-                    &l10n.t("log-field-added-perms", None),
+                    l10n.text("log-field-added-perms", None),
                     format!("`{:?}`", added_perms & dangerous_permissions),
                 ),
-                (&l10n.t("log-field-action-status", None), status),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
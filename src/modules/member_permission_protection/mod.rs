@@ -1,17 +1,44 @@
 pub mod events;
 
-use crate::modules::{Module, ModuleDefinition};
+use crate::modules::{EventKind, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
 
-pub const DEFINITION: ModuleDefinition = ModuleDefinition {
-    id: "member_permission_protection",
-    name_key: "module-member-permission-protection-name",
-    desc_key: "module-member-permission-protection-desc",
-};
+pub struct MemberPermissionProtection;
 
-pub fn module() -> Module {
-    Module {
-        definition: DEFINITION,
-        commands: vec![],
-        event_handlers: vec![events::handler],
+#[serenity::async_trait]
+impl GuardModule for MemberPermissionProtection {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
+            id: "member_permission_protection",
+            name_key: "module-member-permission-protection-name",
+            desc_key: "module-member-permission-protection-desc",
+        }
+    }
+
+    fn events(&self) -> &'static [EventKind] {
+        &[EventKind::GuildAuditLogEntryCreate]
+    }
+
+    async fn on_event(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        data: &Data,
+    ) -> Result<(), Error> {
+        events::handle_event(ctx, event, data).await
+    }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(
+            ctx,
+            guild_id,
+            serenity::Permissions::VIEW_AUDIT_LOG | serenity::Permissions::MANAGE_ROLES,
+        )
     }
 }
@@ -1,21 +1,76 @@
+#[cfg(feature = "bot-adding-protection")]
 pub mod bot_adding_protection;
+#[cfg(feature = "channel-permission-protection")]
 pub mod channel_permission_protection;
+#[cfg(feature = "channel-protection")]
 pub mod channel_protection;
+#[cfg(feature = "invite-tracking")]
 pub mod invite_tracking;
+#[cfg(feature = "logging")]
 pub mod logging;
+#[cfg(feature = "member-permission-protection")]
 pub mod member_permission_protection;
+#[cfg(feature = "moderation-protection")]
 pub mod moderation_protection;
+#[cfg(feature = "role-permission-protection")]
 pub mod role_permission_protection;
+#[cfg(feature = "role-protection")]
 pub mod role_protection;
+#[cfg(feature = "sticky-roles")]
 pub mod sticky_roles;
 
+use crate::db::entities::module_configs::{self, ModuleType};
+use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
+use std::sync::Arc;
 
-pub type EventHandler = for<'a> fn(
-    &'a serenity::Context,
-    &'a serenity::FullEvent,
-    &'a crate::Data,
-) -> poise::BoxFuture<'a, Result<(), crate::Error>>;
+/// Gateway events a module can subscribe to. The dispatcher only calls
+/// [`GuardModule::on_event`] for the kinds listed in [`GuardModule::events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    GuildCreate,
+    GuildAuditLogEntryCreate,
+    GuildMemberAddition,
+    GuildMemberUpdate,
+    GuildMemberRemoval,
+    InviteCreate,
+    InviteDelete,
+    Message,
+    MessageUpdate,
+    MessageDelete,
+    VoiceStateUpdate,
+}
+
+impl EventKind {
+    /// Returns the kind of the event, or None if no module can subscribe to it.
+    pub fn of(event: &serenity::FullEvent) -> Option<Self> {
+        Some(match event {
+            serenity::FullEvent::GuildCreate { .. } => Self::GuildCreate,
+            serenity::FullEvent::GuildAuditLogEntryCreate { .. } => Self::GuildAuditLogEntryCreate,
+            serenity::FullEvent::GuildMemberAddition { .. } => Self::GuildMemberAddition,
+            serenity::FullEvent::GuildMemberUpdate { .. } => Self::GuildMemberUpdate,
+            serenity::FullEvent::GuildMemberRemoval { .. } => Self::GuildMemberRemoval,
+            serenity::FullEvent::InviteCreate { .. } => Self::InviteCreate,
+            serenity::FullEvent::InviteDelete { .. } => Self::InviteDelete,
+            serenity::FullEvent::Message { .. } => Self::Message,
+            serenity::FullEvent::MessageUpdate { .. } => Self::MessageUpdate,
+            serenity::FullEvent::MessageDelete { .. } => Self::MessageDelete,
+            serenity::FullEvent::VoiceStateUpdate { .. } => Self::VoiceStateUpdate,
+            _ => return None,
+        })
+    }
+}
+
+/// How the dispatcher runs a module's handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Events of the same guild are handled one at a time, in gateway order.
+    /// Needed when a handler depends on what an earlier event did, such as reverts
+    /// and violation counting.
+    Ordered,
+    /// Every event is handled in its own task as soon as it arrives.
+    Concurrent,
+}
 
 #[derive(Debug, Clone)]
 pub struct ModuleDefinition {
@@ -24,45 +79,266 @@ pub struct ModuleDefinition {
     pub desc_key: &'static str,
 }
 
-pub struct Module {
-    pub definition: ModuleDefinition,
-    pub commands: Vec<poise::Command<crate::Data, crate::Error>>,
-    pub event_handlers: Vec<EventHandler>,
+/// Whether a module can do its job in a guild, shown in `/status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleHealth {
+    Healthy,
+    /// The bot lacks permissions the module needs.
+    MissingPermissions(serenity::Permissions),
 }
 
-pub fn get_modules() -> Vec<Module> {
-    vec![
-        channel_protection::module(),
-        channel_permission_protection::module(),
-        role_protection::module(),
-        role_permission_protection::module(),
-        member_permission_protection::module(),
-        bot_adding_protection::module(),
-        moderation_protection::module(),
-        logging::module(),
-        sticky_roles::module(),
-        invite_tracking::module(),
-    ]
+impl ModuleHealth {
+    /// Checks the bot's guild-wide permissions against `required`, using the cache.
+    /// Guilds that are not cached are reported as healthy.
+    pub fn require(
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        required: serenity::Permissions,
+    ) -> Self {
+        let bot_id = ctx.cache.current_user().id;
+        let Some(guild) = ctx.cache.guild(guild_id) else {
+            return Self::Healthy;
+        };
+        if guild.owner_id == bot_id {
+            return Self::Healthy;
+        }
+        let Some(member) = guild.members.get(&bot_id) else {
+            return Self::Healthy;
+        };
+
+        let permissions = member
+            .roles
+            .iter()
+            .chain(std::iter::once(&guild_id.everyone_role()))
+            .filter_map(|r| guild.roles.get(r))
+            .fold(serenity::Permissions::empty(), |acc, role| {
+                acc | role.permissions
+            });
+        if permissions.contains(serenity::Permissions::ADMINISTRATOR) {
+            return Self::Healthy;
+        }
+
+        let missing = required - permissions;
+        if missing.is_empty() {
+            Self::Healthy
+        } else {
+            Self::MissingPermissions(missing)
+        }
+    }
 }
 
-pub fn commands() -> Vec<poise::Command<crate::Data, crate::Error>> {
+/// A protection or utility module.
+///
+/// The dispatcher only talks to modules through these hooks, so a new module plugs in
+/// by implementing this trait and being listed in [`get_modules`]. Hooks of
+/// [`DispatchMode::Ordered`] modules run in the guild's queue, in gateway order.
+#[serenity::async_trait]
+pub trait GuardModule: Send + Sync {
+    fn definition(&self) -> ModuleDefinition;
+
+    fn commands(&self) -> Vec<poise::Command<Data, Error>> {
+        vec![]
+    }
+
+    /// Event kinds passed to [`GuardModule::on_event`]; other events are never
+    /// dispatched to the module.
+    fn events(&self) -> &'static [EventKind] {
+        &[]
+    }
+
+    fn dispatch_mode(&self) -> DispatchMode {
+        DispatchMode::Ordered
+    }
+
+    /// Gateway intents the module needs on top of the ones [`intents`] always requests.
+    fn intents(&self) -> serenity::GatewayIntents {
+        serenity::GatewayIntents::empty()
+    }
+
+    /// Called each time a shard has connected to the gateway.
+    async fn on_ready(&self, _ctx: &serenity::Context, _data: &Data) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a guild becomes available: on startup, after an outage, or when the
+    /// bot was added to it (`is_new`).
+    async fn on_guild_join(
+        &self,
+        _ctx: &serenity::Context,
+        _guild: &serenity::Guild,
+        _is_new: bool,
+        _data: &Data,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the bot was removed from a guild. Outages do not count.
+    async fn on_guild_leave(
+        &self,
+        _ctx: &serenity::Context,
+        _guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn on_event(
+        &self,
+        _ctx: &serenity::Context,
+        _event: &serenity::FullEvent,
+        _data: &Data,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called after the module's config in a guild was changed, from `/config`, `/setup`
+    /// or outside the bot. `old` is None if the guild had no config row for the module
+    /// before. Changes are delivered one at a time, in the order they were made.
+    async fn on_config_changed(
+        &self,
+        _ctx: &serenity::Context,
+        _guild_id: serenity::GuildId,
+        _old: Option<&module_configs::Model>,
+        _new: &module_configs::Model,
+        _data: &Data,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Long-running tasks of the module, spawned once the client is built.
+    fn background_tasks(
+        &self,
+        _http: Arc<serenity::Http>,
+        _data: Arc<Data>,
+    ) -> Vec<poise::BoxFuture<'static, ()>> {
+        vec![]
+    }
+
+    async fn health(
+        &self,
+        _ctx: &serenity::Context,
+        _guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::Healthy
+    }
+}
+
+/// Returns the modules enabled in this build, see the features in `Cargo.toml`.
+pub fn get_modules() -> Vec<Arc<dyn GuardModule>> {
+    let mut modules: Vec<Arc<dyn GuardModule>> = vec![];
+    #[cfg(feature = "channel-protection")]
+    modules.push(Arc::new(channel_protection::ChannelProtection));
+    #[cfg(feature = "channel-permission-protection")]
+    modules.push(Arc::new(
+        channel_permission_protection::ChannelPermissionProtection,
+    ));
+    #[cfg(feature = "role-protection")]
+    modules.push(Arc::new(role_protection::RoleProtection));
+    #[cfg(feature = "role-permission-protection")]
+    modules.push(Arc::new(
+        role_permission_protection::RolePermissionProtection,
+    ));
+    #[cfg(feature = "member-permission-protection")]
+    modules.push(Arc::new(
+        member_permission_protection::MemberPermissionProtection,
+    ));
+    #[cfg(feature = "bot-adding-protection")]
+    modules.push(Arc::new(bot_adding_protection::BotAddingProtection));
+    #[cfg(feature = "moderation-protection")]
+    modules.push(Arc::new(moderation_protection::ModerationProtection));
+    #[cfg(feature = "logging")]
+    modules.push(Arc::new(logging::Logging));
+    #[cfg(feature = "sticky-roles")]
+    modules.push(Arc::new(sticky_roles::StickyRoles));
+    #[cfg(feature = "invite-tracking")]
+    modules.push(Arc::new(invite_tracking::InviteTracking));
+    modules
+}
+
+/// Gateway intents to connect with: what the core needs plus what the given modules ask for.
+pub fn intents(modules: &[Arc<dyn GuardModule>]) -> serenity::GatewayIntents {
+    // Messages, voice states and invites are only requested by the modules using them
+    let core = serenity::GatewayIntents::non_privileged()
+        - serenity::GatewayIntents::GUILD_MESSAGES
+        - serenity::GatewayIntents::GUILD_VOICE_STATES
+        - serenity::GatewayIntents::GUILD_INVITES
+        | serenity::GatewayIntents::GUILD_MODERATION
+        | serenity::GatewayIntents::AUTO_MODERATION_CONFIGURATION
+        | serenity::GatewayIntents::AUTO_MODERATION_EXECUTION
+        | serenity::GatewayIntents::GUILD_MEMBERS;
+
+    modules
+        .iter()
+        .fold(core, |intents, module| intents | module.intents())
+}
+
+/// Returns the module behind a module type.
+pub fn find_module(data: &Data, module_type: ModuleType) -> Option<&Arc<dyn GuardModule>> {
+    let id = module_type.to_string();
+    data.modules.iter().find(|m| m.definition().id == id)
+}
+
+/// Tells a module that its config in a guild was changed; `old` is the row as it was
+/// before. Does nothing if the row did not actually change.
+async fn config_changed(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    module_type: ModuleType,
+    old: Option<module_configs::Model>,
+) -> Result<(), Error> {
+    let Some(module) = find_module(data, module_type) else {
+        return Ok(());
+    };
+    let Some(new) = data
+        .config_cache
+        .module_config(guild_id, module_type)
+        .await?
+    else {
+        return Ok(());
+    };
+    if old.as_ref() == Some(&new) {
+        return Ok(());
+    }
+    module
+        .on_config_changed(ctx, guild_id, old.as_ref(), &new, data)
+        .await
+}
+
+/// Runs the modules' config hooks for the changes queued by the config cache.
+/// Started once, when the first shard is ready.
+pub async fn run_config_hooks(ctx: serenity::Context) {
+    let data = ctx.data::<Data>();
+    let Some(mut changes) = data.config_cache.take_changes() else {
+        return;
+    };
+
+    while let Some(change) = changes.recv().await {
+        if let Err(e) =
+            config_changed(&ctx, &data, change.guild_id, change.module, change.old).await
+        {
+            tracing::error!("Failed to apply {} config change: {:?}", change.module, e);
+        }
+    }
+}
+
+pub fn commands() -> Vec<poise::Command<Data, Error>> {
     let mut all_commands = vec![];
 
-    for mut module in get_modules() {
-        let category = module.definition.id;
-        for command in &mut module.commands {
+    for module in get_modules() {
+        let category = module.definition().id;
+        let mut commands = module.commands();
+        for command in &mut commands {
             command.category = Some(category.into());
         }
-        all_commands.extend(module.commands);
+        all_commands.extend(commands);
     }
 
     all_commands.push(crate::services::config::config());
+    all_commands.push(crate::services::config::whitelist_command::whitelist());
     all_commands.push(crate::services::help::help());
     all_commands.push(crate::services::status::status());
     all_commands.push(crate::services::setup::setup());
     all_commands
 }
-
-pub fn definitions() -> Vec<ModuleDefinition> {
-    get_modules().into_iter().map(|m| m.definition).collect()
-}
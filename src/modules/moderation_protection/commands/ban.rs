@@ -1,5 +1,5 @@
 use crate::db::entities::module_configs::ModuleType;
-use crate::modules::moderation_protection::duration_parser::parse_duration;
+use crate::services::duration::parse_duration;
 use crate::services::localization::ContextL10nExt;
 use crate::services::logger::LogLevel;
 use crate::{Context, Error};
@@ -110,15 +110,15 @@ pub async fn ban(
             &ctx,
             Some(ModuleType::ModerationProtection),
             LogLevel::Audit,
-            &l10n_guild.t("log-mod-ban-cmd-title", None),
-            &l10n_guild.t("log-mod-ban-cmd-desc", Some(&log_args)),
+            l10n_guild.text("log-mod-ban-cmd-title", None),
+            l10n_guild.text("log-mod-ban-cmd-desc", Some(&log_args)),
             vec![
                 (
-                    &l10n_guild.t("log-field-user", None),
+                    l10n_guild.text("log-field-user", None),
                     format!("<@{}>", user.id.get()),
                 ),
-                (&l10n_guild.t("log-field-duration", None), expires_at_str),
-                (&l10n_guild.t("log-field-reason", None), ban_reason),
+                (l10n_guild.text("log-field-duration", None), expires_at_str),
+                (l10n_guild.text("log-field-reason", None), ban_reason),
             ],
         )
         .await?;
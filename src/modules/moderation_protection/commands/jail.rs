@@ -1,5 +1,5 @@
 use crate::db::entities::module_configs::ModuleType;
-use crate::modules::moderation_protection::duration_parser::parse_duration;
+use crate::services::duration::parse_duration;
 use crate::services::localization::ContextL10nExt;
 use crate::services::logger::LogLevel;
 use crate::{Context, Error};
@@ -67,21 +67,24 @@ pub async fn jail(
             &ctx,
             Some(ModuleType::ModerationProtection),
             LogLevel::Audit,
-            &l10n_guild.t("log-mod-jail-cmd-title", None),
-            &l10n_guild.t("log-mod-jail-cmd-desc", Some(&log_args)),
+            l10n_guild.text("log-mod-jail-cmd-title", None),
+            l10n_guild.text("log-mod-jail-cmd-desc", Some(&log_args)),
             vec![
                 (
-                    &l10n_guild.t("log-field-user", None),
+                    l10n_guild.text("log-field-user", None),
                     format!("<@{}>", user.id.get()),
                 ),
                 (
-                    &l10n_guild.t("log-field-duration", None),
+                    l10n_guild.text("log-field-duration", None),
                     duration
                         .as_deref()
                         .unwrap_or_else(|| l10n_guild.t("log-val-permanent", None).leak())
                         .to_string(),
                 ),
-                (&l10n_guild.t("log-field-reason", None), jail_reason.clone()),
+                (
+                    l10n_guild.text("log-field-reason", None),
+                    jail_reason.clone(),
+                ),
             ],
         )
         .await?;
@@ -40,14 +40,17 @@ pub async fn kick(
             &ctx,
             Some(ModuleType::ModerationProtection),
             LogLevel::Audit,
-            &l10n_guild.t("log-mod-kick-cmd-title", None),
-            &l10n_guild.t("log-mod-kick-cmd-desc", Some(&log_args)),
+            l10n_guild.text("log-mod-kick-cmd-title", None),
+            l10n_guild.text("log-mod-kick-cmd-desc", Some(&log_args)),
             vec![
                 (
-                    &l10n_guild.t("log-field-user", None),
+                    l10n_guild.text("log-field-user", None),
                     format!("<@{}>", user.id.get()),
                 ),
-                (&l10n_guild.t("log-field-reason", None), kick_reason.clone()),
+                (
+                    l10n_guild.text("log-field-reason", None),
+                    kick_reason.clone(),
+                ),
             ],
         )
         .await?;
@@ -1,5 +1,5 @@
 use crate::db::entities::module_configs::ModuleType;
-use crate::modules::moderation_protection::duration_parser::parse_duration;
+use crate::services::duration::parse_duration;
 use crate::services::localization::ContextL10nExt;
 use crate::services::logger::LogLevel;
 use crate::{Context, Error};
@@ -61,16 +61,19 @@ pub async fn timeout(
             &ctx,
             Some(ModuleType::ModerationProtection),
             LogLevel::Audit,
-            &l10n_guild.t("log-mod-timeout-cmd-title", None),
-            &l10n_guild.t("log-mod-timeout-cmd-desc", Some(&log_args)),
+            l10n_guild.text("log-mod-timeout-cmd-title", None),
+            l10n_guild.text("log-mod-timeout-cmd-desc", Some(&log_args)),
             vec![
                 (
-                    &l10n_guild.t("log-field-user", None),
+                    l10n_guild.text("log-field-user", None),
                     format!("<@{}>", user.id.get()),
                 ),
-                (&l10n_guild.t("log-field-duration", None), duration.clone()),
                 (
-                    &l10n_guild.t("log-field-reason", None),
+                    l10n_guild.text("log-field-duration", None),
+                    duration.clone(),
+                ),
+                (
+                    l10n_guild.text("log-field-reason", None),
                     timeout_reason.clone(),
                 ),
             ],
@@ -36,10 +36,10 @@ pub async fn unjail(
             &ctx,
             Some(ModuleType::ModerationProtection),
             LogLevel::Audit,
-            &l10n_guild.t("log-mod-unjail-cmd-title", None),
-            &l10n_guild.t("log-mod-unjail-cmd-desc", Some(&log_args)),
+            l10n_guild.text("log-mod-unjail-cmd-title", None),
+            l10n_guild.text("log-mod-unjail-cmd-desc", Some(&log_args)),
             vec![(
-                &l10n_guild.t("log-field-user", None),
+                l10n_guild.text("log-field-user", None),
                 format!("<@{}>", user.id.get()),
             )],
         )
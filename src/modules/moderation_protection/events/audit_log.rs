@@ -2,7 +2,6 @@ use crate::db::entities::module_configs::{self, ModerationProtectionModuleConfig
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, MemberAction};
 
 pub async fn handle_audit_log(
@@ -23,12 +22,10 @@ pub async fn handle_audit_log(
     }
 
     // Fetch module config
-    let config_model = module_configs::Entity::find_by_id((
-        guild_id.get() as i64,
-        ModuleType::ModerationProtection,
-    ))
-    .one(&data.db)
-    .await?;
+    let config_model = data
+        .config_cache
+        .module_config(guild_id, ModuleType::ModerationProtection)
+        .await?;
 
     let (config_model, config) = match config_model {
         Some(m) => {
@@ -52,82 +49,47 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    // Check whitelist
+    // Check whitelist for this specific action
+    let action = match entry.action {
+        Action::Member(MemberAction::BanAdd) => "ban",
+        Action::Member(MemberAction::Kick) => "kick",
+        Action::Member(MemberAction::Prune) => "prune",
+        Action::Member(MemberAction::BanRemove) => "unban",
+        Action::Member(MemberAction::Update) if is_timeout_change(entry) => "timeout",
+        _ => return Ok(()),
+    };
     let whitelist_level = data
         .whitelist
-        .get_whitelist_level(ctx, guild_id, user_id, ModuleType::ModerationProtection)
+        .get_whitelist_level(
+            ctx,
+            guild_id,
+            user_id,
+            ModuleType::ModerationProtection,
+            Some(action),
+        )
         .await?;
 
-    match entry.action {
-        Action::Member(MemberAction::BanAdd) => {
-            handle_moderation_action(
-                ctx,
-                entry,
-                guild_id,
-                data,
-                &config_model,
-                user_id,
-                whitelist_level,
-                &config,
-                "ban",
-            )
-            .await?;
-        }
-        Action::Member(MemberAction::Kick) => {
-            handle_moderation_action(
-                ctx,
-                entry,
-                guild_id,
-                data,
-                &config_model,
-                user_id,
-                whitelist_level,
-                &config,
-                "kick",
-            )
-            .await?;
-        }
-        Action::Member(MemberAction::Update) => {
-            // Check for timeout changes
-            let has_timeout_change = entry.changes.iter().any(|change| {
-                matches!(
-                    change,
-                    serenity::model::guild::audit_log::Change::CommunicationDisabledUntil { .. }
-                )
-            });
-            if has_timeout_change {
-                handle_moderation_action(
-                    ctx,
-                    entry,
-                    guild_id,
-                    data,
-                    &config_model,
-                    user_id,
-                    whitelist_level,
-                    &config,
-                    "timeout",
-                )
-                .await?;
-            }
-        }
-        Action::Member(MemberAction::BanRemove) => {
-            handle_moderation_action(
-                ctx,
-                entry,
-                guild_id,
-                data,
-                &config_model,
-                user_id,
-                whitelist_level,
-                &config,
-                "unban",
-            )
-            .await?;
-        }
-        _ => {}
-    }
+    handle_moderation_action(
+        ctx,
+        entry,
+        guild_id,
+        data,
+        &config_model,
+        user_id,
+        whitelist_level,
+        &config,
+        action,
+    )
+    .await
+}
 
-    Ok(())
+fn is_timeout_change(entry: &serenity::AuditLogEntry) -> bool {
+    entry.changes.iter().any(|change| {
+        matches!(
+            change,
+            serenity::model::guild::audit_log::Change::CommunicationDisabledUntil { .. }
+        )
+    })
 }
 
 async fn handle_moderation_action(
@@ -166,7 +128,7 @@ async fn handle_moderation_action(
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::ModerationProtection,
@@ -296,11 +258,11 @@ async fn handle_moderation_action(
     title_args.set("action", action_type);
 
     let title = if is_whitelisted {
-        l10n.t("log-mod-audit-title-whitelisted", Some(&title_args))
+        l10n.text("log-mod-audit-title-whitelisted", Some(&title_args))
     } else if should_punish {
-        l10n.t("log-mod-audit-title-limited", Some(&title_args))
+        l10n.text("log-mod-audit-title-limited", Some(&title_args))
     } else {
-        l10n.t("log-mod-audit-title-logged", Some(&title_args))
+        l10n.text("log-mod-audit-title-logged", Some(&title_args))
     };
 
     let log_level = if is_whitelisted {
@@ -316,25 +278,30 @@ async fn handle_moderation_action(
     desc_args.set("action", action_type);
     desc_args.set("targetId", target_id.to_string());
 
-    let desc = l10n.t("log-mod-audit-desc", Some(&desc_args));
+    let desc = l10n.text("log-mod-audit-desc", Some(&desc_args));
+
+    let mut fields = vec![(
+        l10n.text("log-field-user", None),
+        format!("<@{}>", user_id.get()),
+    )];
+    // Prunes have no single target
+    if target_id != 0 {
+        fields.push((
+            l10n.text("log-field-target-member", None),
+            format!("<@{}>", target_id),
+        ));
+    }
+    fields.push((l10n.text("log-field-action-status", None), status));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::ModerationProtection),
             None,
             log_level,
-            &title,
-            &desc,
-            vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                (
-                    &l10n.t("log-field-target-member", None),
-                    format!("<@{}>", target_id),
-                ),
-                (&l10n.t("log-field-action-status", None), status),
-            ],
+            title,
+            desc,
+            fields,
         )
         .await?;
 
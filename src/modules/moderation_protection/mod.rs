@@ -1,23 +1,57 @@
 pub mod commands;
-pub mod duration_parser;
 pub mod events;
 
-use crate::modules::{Module, ModuleDefinition};
+use crate::modules::{EventKind, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
 
-pub fn module() -> Module {
-    Module {
-        definition: ModuleDefinition {
+pub struct ModerationProtection;
+
+#[serenity::async_trait]
+impl GuardModule for ModerationProtection {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
             id: "moderation_protection",
             name_key: "module-moderation-protection-name",
             desc_key: "module-moderation-protection-desc",
-        },
-        commands: vec![
+        }
+    }
+
+    fn commands(&self) -> Vec<poise::Command<Data, Error>> {
+        vec![
             commands::ban(),
             commands::jail(),
             commands::kick(),
             commands::timeout(),
             commands::unjail::unjail(),
-        ],
-        event_handlers: vec![events::handler],
+        ]
+    }
+
+    fn events(&self) -> &'static [EventKind] {
+        &[EventKind::GuildAuditLogEntryCreate]
+    }
+
+    async fn on_event(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        data: &Data,
+    ) -> Result<(), Error> {
+        events::handle_event(ctx, event, data).await
+    }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(
+            ctx,
+            guild_id,
+            serenity::Permissions::VIEW_AUDIT_LOG
+                | serenity::Permissions::BAN_MEMBERS
+                | serenity::Permissions::MODERATE_MEMBERS,
+        )
     }
 }
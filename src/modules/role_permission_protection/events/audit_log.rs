@@ -1,10 +1,14 @@
 use crate::db::entities::module_configs::{self, ModuleType, RolePermissionProtectionModuleConfig};
+use crate::db::entities::whitelists::WhitelistLevel;
+use crate::services::approval::HeldChange;
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, Change, RoleAction};
 
+/// Permissions that Admin-level members can only grant with Head approval.
+const HELD_PERMISSIONS: serenity::Permissions = serenity::Permissions::ADMINISTRATOR;
+
 pub async fn handle_audit_log(
     ctx: &serenity::Context,
     entry: &serenity::AuditLogEntry,
@@ -16,12 +20,10 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    let config_model = match module_configs::Entity::find_by_id((
-        guild_id.get() as i64,
-        ModuleType::RolePermissionProtection,
-    ))
-    .one(&data.db)
-    .await?
+    let config_model = match data
+        .config_cache
+        .module_config(guild_id, ModuleType::RolePermissionProtection)
+        .await?
     {
         Some(m) => {
             if !m.enabled {
@@ -32,7 +34,7 @@ pub async fn handle_audit_log(
         None => return Ok(()),
     };
 
-    let _config: RolePermissionProtectionModuleConfig =
+    let config: RolePermissionProtectionModuleConfig =
         serde_json::from_value(config_model.config.clone()).unwrap_or_default();
 
     let user_id = match entry.user_id {
@@ -48,7 +50,13 @@ pub async fn handle_audit_log(
     // Check whitelist
     let whitelist_level = data
         .whitelist
-        .get_whitelist_level(ctx, guild_id, user_id, ModuleType::RolePermissionProtection)
+        .get_whitelist_level(
+            ctx,
+            guild_id,
+            user_id,
+            ModuleType::RolePermissionProtection,
+            Some("update"),
+        )
         .await?;
 
     match entry.action {
@@ -67,6 +75,7 @@ pub async fn handle_audit_log(
                     &config_model,
                     user_id,
                     whitelist_level,
+                    &config,
                 )
                 .await?;
             }
@@ -85,6 +94,7 @@ async fn handle_role_permission_update(
     config: &module_configs::Model,
     user_id: serenity::UserId,
     whitelist_level: Option<crate::db::entities::whitelists::WhitelistLevel>,
+    module_config: &RolePermissionProtectionModuleConfig,
 ) -> Result<(), Error> {
     let role_id = entry.target_id.map(|id| id.get()).unwrap_or(0);
 
@@ -102,13 +112,66 @@ async fn handle_role_permission_update(
         l10n.t("log-status-unauthorized", None)
     };
 
+    // Held permissions granted by Admin-level members are removed and held for Head approval
+    let mut held = false;
+    if whitelist_level == Some(WhitelistLevel::Admin)
+        && module_config.require_approval
+        && role_id != 0
+    {
+        let permissions = entry.changes.iter().find_map(|change| match change {
+            Change::Permissions { old, new } => Some((*old, *new)),
+            _ => None,
+        });
+
+        if let Some((old, Some(new))) = permissions {
+            let granted = new & !old.unwrap_or_default() & HELD_PERMISSIONS;
+            if !granted.is_empty() {
+                // Only the held bits are taken away, other changes to the role stay
+                let current = guild
+                    .roles
+                    .get(&serenity::RoleId::new(role_id))
+                    .map(|role| role.permissions)
+                    .unwrap_or(new);
+                let revert_reason = l10n.t("log-role-perm-revert-reason", None);
+                if guild_id
+                    .edit_role(
+                        &ctx.http,
+                        serenity::RoleId::new(role_id),
+                        serenity::EditRole::default()
+                            .permissions(current & !granted)
+                            .audit_log_reason(&revert_reason),
+                    )
+                    .await
+                    .is_ok()
+                {
+                    data.approval
+                        .hold(
+                            &ctx.http,
+                            guild_id,
+                            ModuleType::RolePermissionProtection,
+                            user_id,
+                            HeldChange::RolePermissions {
+                                role_id,
+                                granted: granted.bits(),
+                            },
+                        )
+                        .await?;
+                    status += &l10n.t("log-status-held", None);
+                    held = true;
+                } else {
+                    status += &l10n.t("log-status-revert-failed", None);
+                }
+            }
+        }
+    }
+
     if whitelist_level.is_none() {
         // Punishment
         let reason = l10n.t("log-role-perm-reason-update", None);
         let result = data
             .punishment
             .handle_violation(
-                &ctx.http,
+                ctx,
                 guild_id,
                 user_id,
                 ModuleType::RolePermissionProtection,
@@ -165,7 +228,7 @@ async fn handle_role_permission_update(
                 }
             }
         }
-    } else if let Some(level) = whitelist_level {
+    } else if let (false, Some(level)) = (held, whitelist_level) {
         let mut args = fluent::FluentArgs::new();
         args.set("level", format!("{:?}", level));
         args.set("punishment", format!("{:?}", config.punishment));
@@ -174,9 +237,9 @@ async fn handle_role_permission_update(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-role-perm-title-whitelisted", None)
+        l10n.text("log-role-perm-title-whitelisted", None)
     } else {
-        l10n.t("log-role-perm-title-blocked", None)
+        l10n.text("log-role-perm-title-blocked", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -187,21 +250,26 @@ async fn handle_role_permission_update(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("roleId", role_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-role-perm-desc-update", Some(&desc_args));
+    let desc = l10n.text("log-role-perm-desc-update", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::RolePermissionProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                (&l10n.t("log-field-role", None), format!("<@&{}>", role_id)),
-                (&l10n.t("log-field-action-status", None), status),
+                (
+                    l10n.text("log-field-user", None),
+                    format!("<@{}>", user_id.get()),
+                ),
+                (
+                    l10n.text("log-field-role", None),
+                    format!("<@&{}>", role_id),
+                ),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
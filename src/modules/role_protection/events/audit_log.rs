@@ -2,7 +2,6 @@ use crate::db::entities::module_configs::{self, ModuleType, RoleProtectionModule
 use crate::services::logger::LogLevel;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use serenity::model::guild::audit_log::{Action, RoleAction};
 
 pub async fn handle_audit_log(
@@ -21,12 +20,10 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    let config_model = match module_configs::Entity::find_by_id((
-        guild_id.get() as i64,
-        ModuleType::RoleProtection,
-    ))
-    .one(&data.db)
-    .await?
+    let config_model = match data
+        .config_cache
+        .module_config(guild_id, ModuleType::RoleProtection)
+        .await?
     {
         Some(m) => {
             if !m.enabled {
@@ -50,10 +47,21 @@ pub async fn handle_audit_log(
         return Ok(());
     }
 
-    // Check whitelist
+    // Check whitelist for this specific action
+    let action = match entry.action {
+        Action::Role(RoleAction::Create) => "create",
+        Action::Role(RoleAction::Delete) => "delete",
+        _ => "update",
+    };
     let whitelist_level = data
         .whitelist
-        .get_whitelist_level(ctx, guild_id, user_id, ModuleType::RoleProtection)
+        .get_whitelist_level(
+            ctx,
+            guild_id,
+            user_id,
+            ModuleType::RoleProtection,
+            Some(action),
+        )
         .await?;
 
     // Match on the audit log action to triggers variants error
@@ -147,13 +155,7 @@ async fn handle_role_create(
         let reason = l10n.t("log-role-reason-create", None);
         let result = data
             .punishment
-            .handle_violation(
-                &ctx.http,
-                guild_id,
-                user_id,
-                ModuleType::RoleProtection,
-                &reason,
-            )
+            .handle_violation(ctx, guild_id, user_id, ModuleType::RoleProtection, &reason)
             .await?;
 
         status = match result {
@@ -203,11 +205,11 @@ async fn handle_role_create(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-role-title-whitelisted", None)
+        l10n.text("log-role-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-role-title-blocked", None)
+        l10n.text("log-role-title-blocked", None)
     } else {
-        l10n.t("log-role-title-logged", None)
+        l10n.text("log-role-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -220,21 +222,23 @@ async fn handle_role_create(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("roleId", role_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-role-desc-create", Some(&desc_args));
+    let desc = l10n.text("log-role-desc-create", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::RoleProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                (&l10n.t("log-field-role-id", None), role_id.to_string()),
-                (&l10n.t("log-field-action-status", None), status),
+                (
+                    l10n.text("log-field-user", None),
+                    format!("<@{}>", user_id.get()),
+                ),
+                (l10n.text("log-field-role-id", None), role_id.to_string()),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -277,13 +281,7 @@ async fn handle_role_delete(
         let reason = l10n.t("log-role-reason-delete", None);
         let result = data
             .punishment
-            .handle_violation(
-                &ctx.http,
-                guild_id,
-                user_id,
-                ModuleType::RoleProtection,
-                &reason,
-            )
+            .handle_violation(ctx, guild_id, user_id, ModuleType::RoleProtection, &reason)
             .await?;
 
         status = match result {
@@ -347,11 +345,11 @@ async fn handle_role_delete(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-role-title-whitelisted", None)
+        l10n.text("log-role-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-role-title-blocked", None)
+        l10n.text("log-role-title-blocked", None)
     } else {
-        l10n.t("log-role-title-logged", None)
+        l10n.text("log-role-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -364,21 +362,23 @@ async fn handle_role_delete(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("roleId", role_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-role-desc-delete", Some(&desc_args));
+    let desc = l10n.text("log-role-desc-delete", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::RoleProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                (&l10n.t("log-field-role-id", None), role_id.to_string()),
-                (&l10n.t("log-field-action-status", None), status),
+                (
+                    l10n.text("log-field-user", None),
+                    format!("<@{}>", user_id.get()),
+                ),
+                (l10n.text("log-field-role-id", None), role_id.to_string()),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
@@ -421,13 +421,7 @@ async fn handle_role_update(
         let reason = l10n.t("log-role-reason-update", None);
         let result = data
             .punishment
-            .handle_violation(
-                &ctx.http,
-                guild_id,
-                user_id,
-                ModuleType::RoleProtection,
-                &reason,
-            )
+            .handle_violation(ctx, guild_id, user_id, ModuleType::RoleProtection, &reason)
             .await?;
 
         status = match result {
@@ -512,11 +506,11 @@ async fn handle_role_update(
 
     let is_whitelisted = whitelist_level.is_some();
     let title = if is_whitelisted {
-        l10n.t("log-role-title-whitelisted", None)
+        l10n.text("log-role-title-whitelisted", None)
     } else if should_punish {
-        l10n.t("log-role-title-blocked", None)
+        l10n.text("log-role-title-blocked", None)
     } else {
-        l10n.t("log-role-title-logged", None)
+        l10n.text("log-role-title-logged", None)
     };
     let log_level = if is_whitelisted {
         LogLevel::Audit
@@ -529,21 +523,26 @@ async fn handle_role_update(
     let mut desc_args = fluent::FluentArgs::new();
     desc_args.set("roleId", role_id.to_string());
     desc_args.set("userId", user_id.get().to_string());
-    let desc = l10n.t("log-role-desc-update", Some(&desc_args));
+    let desc = l10n.text("log-role-desc-update", Some(&desc_args));
 
     data.logger
         .log_action(
-            &ctx.http,
             guild_id,
             Some(ModuleType::RoleProtection),
             None,
             log_level,
-            &title,
-            &desc,
+            title,
+            desc,
             vec![
-                (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                (&l10n.t("log-field-role", None), format!("<@&{}>", role_id)),
-                (&l10n.t("log-field-action-status", None), status),
+                (
+                    l10n.text("log-field-user", None),
+                    format!("<@{}>", user_id.get()),
+                ),
+                (
+                    l10n.text("log-field-role", None),
+                    format!("<@&{}>", role_id),
+                ),
+                (l10n.text("log-field-action-status", None), status),
             ],
         )
         .await?;
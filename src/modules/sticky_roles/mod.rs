@@ -1,15 +1,80 @@
-use crate::modules::{Module, ModuleDefinition};
+use crate::db::entities::module_configs::{self, LoggingModuleConfig, ModuleType};
+use crate::modules::{DispatchMode, GuardModule, ModuleDefinition, ModuleHealth};
+use crate::services::event_manager::shared_events;
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
 
 pub mod tracking;
 
-pub fn module() -> Module {
-    Module {
-        definition: ModuleDefinition {
+pub struct StickyRoles;
+
+#[serenity::async_trait]
+impl GuardModule for StickyRoles {
+    fn definition(&self) -> ModuleDefinition {
+        ModuleDefinition {
             id: "sticky_roles",
             name_key: "module-sticky-roles-name",
             desc_key: "module-sticky-roles-desc",
-        },
-        commands: vec![],
-        event_handlers: vec![],
+        }
+    }
+
+    fn dispatch_mode(&self) -> DispatchMode {
+        DispatchMode::Concurrent
+    }
+
+    async fn on_config_changed(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        old: Option<&module_configs::Model>,
+        new: &module_configs::Model,
+        data: &Data,
+    ) -> Result<(), Error> {
+        let was_enabled = old.is_some_and(|c| c.enabled);
+        if new.enabled && !was_enabled {
+            // Enabling: fetch all members and store their roles in background
+            let http = ctx.http.clone();
+            let db = data.db.clone();
+            tokio::spawn(async move {
+                if let Err(e) = shared_events::fetch_and_store_all_members(http, guild_id, db).await
+                {
+                    tracing::error!(
+                        "Failed to fetch and store members for guild {} (StickyRoles): {:?}",
+                        guild_id,
+                        e
+                    );
+                }
+            });
+        } else if !new.enabled && was_enabled {
+            // Disabling: keep the roles if membership logging still needs them
+            if !is_logging_membership_enabled(guild_id, data).await? {
+                shared_events::delete_all_guild_member_roles(guild_id, data).await?;
+            }
+        }
+        Ok(())
     }
+
+    async fn health(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        _data: &Data,
+    ) -> ModuleHealth {
+        ModuleHealth::require(ctx, guild_id, serenity::Permissions::MANAGE_ROLES)
+    }
+}
+
+async fn is_logging_membership_enabled(
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<bool, Error> {
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
+        .await?;
+
+    Ok(m_config.is_some_and(|m| {
+        let config: LoggingModuleConfig = serde_json::from_value(m.config).unwrap_or_default();
+        m.enabled && config.log_membership
+    }))
 }
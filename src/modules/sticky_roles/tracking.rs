@@ -1,8 +1,7 @@
-use crate::db::entities::module_configs::{self, ModuleType, StickyRolesModuleConfig};
+use crate::db::entities::module_configs::{ModuleType, StickyRolesModuleConfig};
 use crate::services::event_manager::shared_events;
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
-use sea_orm::EntityTrait;
 use tracing::{error, info};
 
 /// Check if the sticky roles module is enabled.
@@ -11,10 +10,10 @@ pub async fn get_sticky_roles_config(
     guild_id: serenity::GuildId,
     data: &Data,
 ) -> Result<Option<StickyRolesModuleConfig>, Error> {
-    let m_config =
-        module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::StickyRoles))
-            .one(&data.db)
-            .await?;
+    let m_config = data
+        .config_cache
+        .module_config(guild_id, ModuleType::StickyRoles)
+        .await?;
 
     match m_config {
         Some(m) => {
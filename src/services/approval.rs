@@ -0,0 +1,427 @@
+use crate::db::entities::{
+    module_configs::{ModuleType, PunishmentType},
+    pending_approvals, whitelist_role, whitelist_user,
+    whitelists::WhitelistLevel,
+};
+use crate::services::localization::{L10nProxy, LocalizationManager};
+use crate::services::logger::{LogLevel, LoggerService};
+use crate::services::whitelist::{covers_action, not_expired_role, not_expired_user};
+use crate::{Data, Error};
+use fluent::FluentArgs;
+use poise::serenity_prelude as serenity;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+/// A change that was reverted by a protection and held until a Head-level member
+/// approves or rejects it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HeldChange {
+    /// A deleted category, restored by the revert as `channel_id`; approval deletes it again
+    CategoryDelete { channel_id: u64, name: String },
+    /// Dangerous permissions granted to a role, removed by the revert; approval grants
+    /// them again on top of whatever the role has by then
+    RolePermissions { role_id: u64, granted: u64 },
+}
+
+impl HeldChange {
+    /// Whitelist action the change counts as, so approvers are checked like the actor.
+    fn action(&self) -> &'static str {
+        match self {
+            HeldChange::CategoryDelete { .. } => "delete",
+            HeldChange::RolePermissions { .. } => "update",
+        }
+    }
+
+    fn describe(&self, l10n: &L10nProxy) -> String {
+        let mut args = FluentArgs::new();
+        match self {
+            HeldChange::CategoryDelete { name, .. } => {
+                args.set("name", name.clone());
+                l10n.t("approval-change-category-delete", Some(&args))
+            }
+            HeldChange::RolePermissions { role_id, .. } => {
+                args.set("role", format!("<@&{}>", role_id));
+                l10n.t("approval-change-role-permissions", Some(&args))
+            }
+        }
+    }
+
+    /// Makes the held change again.
+    async fn apply(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        reason: &str,
+    ) -> Result<(), Error> {
+        match self {
+            HeldChange::CategoryDelete { channel_id, .. } => {
+                http.delete_channel(serenity::GenericChannelId::new(*channel_id), Some(reason))
+                    .await?;
+            }
+            HeldChange::RolePermissions { role_id, granted } => {
+                let role_id = serenity::RoleId::new(*role_id);
+                let guild = guild_id.to_partial_guild(http).await?;
+                let current = guild
+                    .roles
+                    .get(&role_id)
+                    .map(|role| role.permissions)
+                    .ok_or_else(|| anyhow::anyhow!("Role {} not found", role_id))?;
+                guild_id
+                    .edit_role(
+                        http,
+                        role_id,
+                        serenity::EditRole::default()
+                            .permissions(
+                                current | serenity::Permissions::from_bits_truncate(*granted),
+                            )
+                            .audit_log_reason(reason),
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct ApprovalService {
+    db: DatabaseConnection,
+    logger: Arc<LoggerService>,
+    l10n: Arc<LocalizationManager>,
+}
+
+impl ApprovalService {
+    pub fn new(
+        db: DatabaseConnection,
+        logger: Arc<LoggerService>,
+        l10n: Arc<LocalizationManager>,
+    ) -> Self {
+        Self { db, logger, l10n }
+    }
+
+    /// Stores a reverted change and posts an approval request to the module's log
+    /// channel, mentioning the guild owner and the guild's Head-level members.
+    pub async fn hold(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        module: ModuleType,
+        actor_id: serenity::UserId,
+        change: HeldChange,
+    ) -> Result<(), Error> {
+        let approval = pending_approvals::ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            module_type: Set(module),
+            actor_id: Set(actor_id.get() as i64),
+            change: Set(serde_json::to_value(&change)?),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        let Some(channel_id) = self.logger.resolve_channel(guild_id, Some(module)).await? else {
+            warn!(
+                "No log channel for approval request {} in guild {}",
+                approval.id,
+                guild_id.get()
+            );
+            return Ok(());
+        };
+
+        let l10n = self.l10n.get_l10n_for_guild(guild_id, &self.db).await;
+        let mut mentions = self
+            .head_mentions(guild_id, module, change.action())
+            .await?;
+        // The owner is Head without a whitelist entry
+        match guild_id.to_partial_guild(http).await {
+            Ok(guild) if guild.owner_id != actor_id => {
+                let owner = format!("<@{}>", guild.owner_id.get());
+                mentions.retain(|mention| *mention != owner);
+                mentions.insert(0, owner);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to fetch owner of guild {}: {:?}", guild_id.get(), e),
+        }
+
+        let message = serenity::CreateMessage::new()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(build_request(
+                &approval, &change, &mentions, None, false, &l10n,
+            ))
+            .allowed_mentions(
+                serenity::CreateAllowedMentions::new()
+                    .all_users(true)
+                    .all_roles(true),
+            );
+        http.send_message(channel_id.into(), Vec::new(), &message)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mentions of the Head-level entries that can decide on a module's approvals.
+    async fn head_mentions(
+        &self,
+        guild_id: serenity::GuildId,
+        module: ModuleType,
+        action: &str,
+    ) -> Result<Vec<String>, Error> {
+        let users = whitelist_user::Entity::find()
+            .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(whitelist_user::Column::Level.eq(WhitelistLevel::Head))
+            .filter(
+                Condition::any()
+                    .add(whitelist_user::Column::ModuleType.is_null())
+                    .add(whitelist_user::Column::ModuleType.eq(Some(module))),
+            )
+            .filter(not_expired_user())
+            .all(&self.db)
+            .await?;
+
+        let roles = whitelist_role::Entity::find()
+            .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(whitelist_role::Column::Level.eq(WhitelistLevel::Head))
+            .filter(
+                Condition::any()
+                    .add(whitelist_role::Column::ModuleType.is_null())
+                    .add(whitelist_role::Column::ModuleType.eq(Some(module))),
+            )
+            .filter(not_expired_role())
+            .all(&self.db)
+            .await?;
+
+        Ok(users
+            .iter()
+            .filter(|u| covers_action(&u.actions, Some(action)))
+            .map(|u| format!("<@{}>", u.user_id))
+            .chain(
+                roles
+                    .iter()
+                    .filter(|r| covers_action(&r.actions, Some(action)))
+                    .map(|r| format!("<@&{}>", r.role_id)),
+            )
+            .collect())
+    }
+}
+
+/// Builds the approval request with the outcome of the last decision, if any.
+/// Once decided, the buttons are removed.
+fn build_request(
+    approval: &pending_approvals::Model,
+    change: &HeldChange,
+    mentions: &[String],
+    outcome: Option<String>,
+    decided: bool,
+    l10n: &L10nProxy,
+) -> Vec<serenity::CreateComponent<'static>> {
+    let mut args = FluentArgs::new();
+    args.set("actor", format!("<@{}>", approval.actor_id));
+    args.set("change", change.describe(l10n));
+
+    let mut text = format!(
+        "{}\n{}",
+        l10n.t("approval-request-title", None),
+        l10n.t("approval-request-desc", Some(&args))
+    );
+    if !mentions.is_empty() {
+        text.push_str(&format!("\n-# {}", mentions.join(" ")));
+    }
+
+    let mut components = vec![serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(text),
+    )];
+
+    if let Some(outcome) = outcome {
+        components.push(serenity::CreateContainerComponent::Separator(
+            serenity::CreateSeparator::new(true),
+        ));
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(outcome),
+        ));
+    }
+    if !decided {
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::buttons(vec![
+                serenity::CreateButton::new(format!("approval_approve_{}", approval.id))
+                    .label(l10n.t("approval-approve-btn", None))
+                    .style(serenity::ButtonStyle::Success),
+                serenity::CreateButton::new(format!("approval_reject_{}", approval.id))
+                    .label(l10n.t("approval-reject-btn", None))
+                    .style(serenity::ButtonStyle::Danger),
+            ]),
+        ));
+    }
+
+    vec![serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(components),
+    )]
+}
+
+/// Handles the approve and reject buttons of an approval request.
+/// Only Head-level members other than the actor can decide.
+pub async fn handle_interaction(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let guild_id = match interaction.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let custom_id = &interaction.data.custom_id;
+    let (approve, id) = if let Some(id) = custom_id.strip_prefix("approval_approve_") {
+        (true, id)
+    } else if let Some(id) = custom_id.strip_prefix("approval_reject_") {
+        (false, id)
+    } else {
+        return Ok(());
+    };
+    let id: i64 = id.parse().unwrap_or(0);
+
+    let l10n = L10nProxy {
+        manager: data.l10n.clone(),
+        locale: interaction.locale.to_string(),
+    };
+    let reply = |content: String| {
+        serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(content)
+                .ephemeral(true),
+        )
+    };
+
+    let approval = pending_approvals::Entity::find_by_id(id)
+        .filter(pending_approvals::Column::GuildId.eq(guild_id.get() as i64))
+        .one(&data.db)
+        .await?;
+    let Some(approval) = approval else {
+        interaction
+            .create_response(&ctx.http, reply(l10n.t("approval-already-decided", None)))
+            .await?;
+        return Ok(());
+    };
+
+    let change: HeldChange = serde_json::from_value(approval.change.clone())?;
+    let level = data
+        .whitelist
+        .get_whitelist_level(
+            ctx,
+            guild_id,
+            interaction.user.id,
+            approval.module_type,
+            Some(change.action()),
+        )
+        .await?;
+    if level != Some(WhitelistLevel::Head) || interaction.user.id.get() as i64 == approval.actor_id
+    {
+        interaction
+            .create_response(&ctx.http, reply(l10n.t("approval-not-allowed", None)))
+            .await?;
+        return Ok(());
+    }
+
+    // Remove the request first, so a second click cannot decide it again
+    let deleted = pending_approvals::Entity::delete_by_id(id)
+        .exec(&data.db)
+        .await?;
+    if deleted.rows_affected == 0 {
+        interaction
+            .create_response(&ctx.http, reply(l10n.t("approval-already-decided", None)))
+            .await?;
+        return Ok(());
+    }
+
+    interaction
+        .create_response(&ctx.http, serenity::CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    let guild_l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
+    let actor_id = serenity::UserId::new(approval.actor_id as u64);
+
+    let mut args = FluentArgs::new();
+    args.set("user", format!("<@{}>", interaction.user.id.get()));
+
+    let mut decided = true;
+    let outcome = if approve {
+        let reason = guild_l10n.t("approval-apply-reason", None);
+        match change.apply(&ctx.http, guild_id, &reason).await {
+            Ok(()) => guild_l10n.t("approval-status-approved", Some(&args)),
+            Err(e) => {
+                error!("Failed to re-apply approved change {}: {:?}", id, e);
+                // Put the request back, so it can be approved again once fixed
+                pending_approvals::ActiveModel::from(approval.clone())
+                    .reset_all()
+                    .insert(&data.db)
+                    .await?;
+                decided = false;
+                guild_l10n.t("approval-status-apply-failed", Some(&args))
+            }
+        }
+    } else {
+        let punishment = data
+            .config_cache
+            .module_config(guild_id, approval.module_type)
+            .await?
+            .map(|c| c.punishment)
+            .unwrap_or(PunishmentType::None);
+
+        let reason = guild_l10n.t("approval-reject-reason", None);
+        if let Err(e) = data
+            .punishment
+            .punish(&ctx.http, guild_id, actor_id, punishment, &reason)
+            .await
+        {
+            error!("Failed to punish rejected change {}: {:?}", id, e);
+        }
+        let punishment_key = format!("config-punishment-type-{:?}", punishment).to_lowercase();
+        args.set("punishment", guild_l10n.t(&punishment_key, None));
+        guild_l10n.t("approval-status-rejected", Some(&args))
+    };
+
+    let edit = serenity::EditInteractionResponse::new()
+        .components(build_request(
+            &approval,
+            &change,
+            &[],
+            Some(outcome.clone()),
+            decided,
+            &guild_l10n,
+        ))
+        .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+        .allowed_mentions(
+            serenity::CreateAllowedMentions::new()
+                .empty_users()
+                .empty_roles(),
+        );
+    interaction.edit_response(&ctx.http, edit).await?;
+
+    let mut desc_args = FluentArgs::new();
+    desc_args.set("actor", format!("<@{}>", actor_id.get()));
+    desc_args.set("change", change.describe(&guild_l10n));
+    data.logger
+        .log_action(
+            guild_id,
+            Some(approval.module_type),
+            None,
+            LogLevel::Audit,
+            guild_l10n.text(
+                if approve {
+                    "log-approval-title-approved"
+                } else {
+                    "log-approval-title-rejected"
+                },
+                None,
+            ),
+            guild_l10n.text("log-approval-desc", Some(&desc_args)),
+            vec![(guild_l10n.text("log-field-action-status", None), outcome)],
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,393 @@
+use crate::Error;
+use crate::db::entities::archived_attachments;
+use chrono::{Duration, Utc};
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// Discord's default upload limit for bots. Larger files could never be re-uploaded,
+/// so there is no point in archiving them.
+const MAX_FILE_BYTES: u32 = 10 * 1024 * 1024;
+
+/// Messages whose attachments are being downloaded. The logging module runs a guild's
+/// events in order, so a delete already comes after its message's download; this keeps
+/// that true for any caller that does not.
+#[derive(Default)]
+struct Downloads {
+    /// Map: MessageID -> lock held for the duration of the download
+    messages: DashMap<u64, Arc<Mutex<()>>>,
+}
+
+impl Downloads {
+    /// Marks the message's download as in progress until the guard is dropped.
+    async fn start(&self, message_id: serenity::MessageId) -> DownloadGuard<'_> {
+        let lock = Arc::new(Mutex::new(()));
+        let guard = lock.clone().lock_owned().await;
+        self.messages.insert(message_id.get(), lock);
+        DownloadGuard {
+            downloads: self,
+            message_id,
+            _guard: guard,
+        }
+    }
+
+    /// Waits for a download of the message that is still in progress.
+    async fn wait(&self, message_id: serenity::MessageId) {
+        let lock = self.messages.get(&message_id.get()).map(|l| l.clone());
+        if let Some(lock) = lock {
+            let _ = lock.lock().await;
+        }
+    }
+}
+
+struct DownloadGuard<'a> {
+    downloads: &'a Downloads,
+    message_id: serenity::MessageId,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for DownloadGuard<'_> {
+    fn drop(&mut self) {
+        // The lock is released after this, waking the waiters
+        self.downloads.messages.remove(&self.message_id.get());
+    }
+}
+
+/// A previously archived attachment, ready to be re-uploaded.
+pub struct ArchivedFile {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Service that keeps local copies of attachments from monitored channels,
+/// so they can be re-uploaded with the delete log after the CDN URL expires.
+///
+/// Configured through environment variables:
+/// - `ATTACHMENT_ARCHIVE_DIR`: storage directory (default `attachment_archive`)
+/// - `ATTACHMENT_ARCHIVE_MAX_MB`: total size cap across all guilds (default 1024)
+/// - `ATTACHMENT_ARCHIVE_RETENTION_DAYS`: how long files are kept (default 7)
+pub struct AttachmentArchiveService {
+    db: DatabaseConnection,
+    root: PathBuf,
+    max_total_bytes: i64,
+    retention_days: i64,
+    downloads: Downloads,
+}
+
+impl AttachmentArchiveService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        let root = std::env::var("ATTACHMENT_ARCHIVE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("attachment_archive"));
+        let max_total_mb: i64 = std::env::var("ATTACHMENT_ARCHIVE_MAX_MB")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1024);
+        let retention_days: i64 = std::env::var("ATTACHMENT_ARCHIVE_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(7);
+
+        Self {
+            db,
+            root,
+            max_total_bytes: max_total_mb * 1024 * 1024,
+            retention_days,
+            downloads: Downloads::default(),
+        }
+    }
+
+    /// Downloads every attachment of the message into the archive directory.
+    /// Files above the upload limit are skipped.
+    pub async fn archive_message(
+        &self,
+        guild_id: serenity::GuildId,
+        message: &serenity::Message,
+    ) -> Result<usize, Error> {
+        let _download = self.downloads.start(message.id).await;
+        let message_dir = self
+            .root
+            .join(guild_id.get().to_string())
+            .join(message.id.get().to_string());
+
+        let mut archived = 0;
+
+        for attachment in message.attachments.iter() {
+            if attachment.size > MAX_FILE_BYTES {
+                continue;
+            }
+
+            let bytes = match attachment.download().await {
+                Ok(b) => b,
+                Err(e) => {
+                    warn!(
+                        "Failed to download attachment {} of message {}: {:?}",
+                        attachment.id.get(),
+                        message.id.get(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            tokio::fs::create_dir_all(&message_dir).await?;
+
+            let filename = attachment.filename.to_string();
+            let path = message_dir.join(format!(
+                "{}-{}",
+                attachment.id.get(),
+                sanitize_filename(&filename)
+            ));
+            tokio::fs::write(&path, &bytes).await?;
+
+            let model = archived_attachments::ActiveModel {
+                guild_id: Set(guild_id.get() as i64),
+                channel_id: Set(message.channel_id.get() as i64),
+                message_id: Set(message.id.get() as i64),
+                attachment_id: Set(attachment.id.get() as i64),
+                filename: Set(filename),
+                file_path: Set(path.to_string_lossy().to_string()),
+                size: Set(bytes.len() as i32),
+                created_at: Set(Utc::now().into()),
+                ..Default::default()
+            };
+            archived_attachments::Entity::insert(model)
+                .exec(&self.db)
+                .await?;
+
+            archived += 1;
+        }
+
+        if archived > 0 {
+            self.enforce_size_cap().await?;
+        }
+
+        Ok(archived)
+    }
+
+    /// Loads the archived files of a deleted message, once a download still in progress
+    /// finished. They stay in the archive until the retention runner removes them, so a
+    /// delete log that fails to deliver can be retried.
+    pub async fn message_files(
+        &self,
+        message_id: serenity::MessageId,
+    ) -> Result<Vec<ArchivedFile>, Error> {
+        self.downloads.wait(message_id).await;
+        let records = self.message_records(message_id).await?;
+
+        let mut files = Vec::with_capacity(records.len());
+        for record in &records {
+            match tokio::fs::read(&record.file_path).await {
+                Ok(bytes) => files.push(ArchivedFile {
+                    filename: record.filename.clone(),
+                    bytes,
+                }),
+                Err(e) => warn!(
+                    "Archived attachment {} is missing on disk: {:?}",
+                    record.file_path, e
+                ),
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Removes the archived files of a deleted message that will not be logged.
+    pub async fn discard_message_files(
+        &self,
+        message_id: serenity::MessageId,
+    ) -> Result<(), Error> {
+        self.downloads.wait(message_id).await;
+        let records = self.message_records(message_id).await?;
+        self.remove_records(records).await
+    }
+
+    async fn message_records(
+        &self,
+        message_id: serenity::MessageId,
+    ) -> Result<Vec<archived_attachments::Model>, Error> {
+        Ok(archived_attachments::Entity::find()
+            .filter(archived_attachments::Column::MessageId.eq(message_id.get() as i64))
+            .order_by_asc(archived_attachments::Column::Id)
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Starts the background cleanup task.
+    /// Runs every hour, drops files past the retention period and trims the archive
+    /// back under the size cap, oldest first.
+    pub fn start_cleanup_runner(self: Arc<Self>) {
+        tokio::spawn(async move {
+            info!("Attachment archive cleanup runner started.");
+            loop {
+                // Sleep for 1 hour between cleanup cycles
+                sleep(std::time::Duration::from_secs(3600)).await;
+
+                let cutoff = Utc::now() - Duration::days(self.retention_days);
+
+                match archived_attachments::Entity::find()
+                    .filter(archived_attachments::Column::CreatedAt.lt(cutoff))
+                    .all(&self.db)
+                    .await
+                {
+                    Ok(expired) => {
+                        let count = expired.len();
+                        if let Err(e) = self.remove_records(expired).await {
+                            error!("Failed to remove expired archived attachments: {:?}", e);
+                        } else if count > 0 {
+                            info!("Cleaned up {} expired archived attachment(s)", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to fetch expired archived attachments: {:?}", e);
+                    }
+                }
+
+                if let Err(e) = self.enforce_size_cap().await {
+                    error!("Failed to enforce attachment archive size cap: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Deletes the oldest archived files until the archive fits in `max_total_bytes`.
+    async fn enforce_size_cap(&self) -> Result<(), Error> {
+        let total: Option<i64> = archived_attachments::Entity::find()
+            .select_only()
+            .column_as(archived_attachments::Column::Size.sum(), "total")
+            .into_tuple::<Option<i64>>()
+            .one(&self.db)
+            .await?
+            .flatten();
+
+        let mut total = total.unwrap_or(0);
+        if total <= self.max_total_bytes {
+            return Ok(());
+        }
+
+        let oldest = archived_attachments::Entity::find()
+            .order_by_asc(archived_attachments::Column::CreatedAt)
+            .limit(500)
+            .all(&self.db)
+            .await?;
+
+        let mut evicted = vec![];
+        for record in oldest {
+            if total <= self.max_total_bytes {
+                break;
+            }
+            total -= record.size as i64;
+            evicted.push(record);
+        }
+
+        info!(
+            "Attachment archive over size cap, evicting {} file(s)",
+            evicted.len()
+        );
+        self.remove_records(evicted).await
+    }
+
+    async fn remove_records(&self, records: Vec<archived_attachments::Model>) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        for record in &records {
+            let path = PathBuf::from(&record.file_path);
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!(
+                        "Failed to remove archived file {}: {:?}",
+                        record.file_path, e
+                    );
+                }
+            }
+            // Remove the message directory once it is empty
+            if let Some(parent) = path.parent() {
+                let _ = tokio::fs::remove_dir(parent).await;
+            }
+        }
+
+        let ids: Vec<i32> = records.iter().map(|r| r.id).collect();
+        archived_attachments::Entity::delete_many()
+            .filter(archived_attachments::Column::Id.is_in(ids))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::{Duration, timeout};
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("photo 1.png"), "photo_1.png");
+        assert_eq!(sanitize_filename("../../etc/passwd"), ".._.._etc_passwd");
+        assert_eq!(
+            sanitize_filename("résumé-v2_final.pdf"),
+            "r_sum_-v2_final.pdf"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_without_download() {
+        let downloads = Downloads::default();
+        timeout(
+            Duration::from_millis(100),
+            downloads.wait(serenity::MessageId::new(1)),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_download() {
+        let downloads = Arc::new(Downloads::default());
+        let message_id = serenity::MessageId::new(1);
+        let download = downloads.start(message_id).await;
+
+        let waiter = {
+            let downloads = downloads.clone();
+            tokio::spawn(async move { downloads.wait(message_id).await })
+        };
+        sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        // Other messages do not wait
+        timeout(
+            Duration::from_millis(100),
+            downloads.wait(serenity::MessageId::new(2)),
+        )
+        .await
+        .unwrap();
+
+        drop(download);
+        timeout(Duration::from_millis(100), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(downloads.messages.is_empty());
+    }
+}
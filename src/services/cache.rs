@@ -5,7 +5,8 @@ use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 pub enum CachedObject {
-    Channel(serenity::GuildChannel),
+    /// A channel and, for categories, the channels it held
+    Channel(serenity::GuildChannel, Vec<serenity::ChannelId>),
     Role(serenity::Role),
 }
 
@@ -32,10 +33,15 @@ impl ObjectCacheService {
         Self { cache }
     }
 
-    pub fn store_channel(&self, guild_id: serenity::GuildId, channel: serenity::GuildChannel) {
+    pub fn store_channel(
+        &self,
+        guild_id: serenity::GuildId,
+        channel: serenity::GuildChannel,
+        children: Vec<serenity::ChannelId>,
+    ) {
         self.cache.insert(
             (guild_id.get(), channel.id.get()),
-            (CachedObject::Channel(channel), Instant::now()),
+            (CachedObject::Channel(channel, children), Instant::now()),
         );
     }
 
@@ -46,22 +52,24 @@ impl ObjectCacheService {
         );
     }
 
+    #[cfg_attr(not(feature = "channel-protection"), allow(dead_code))]
     pub fn take_channel(
         &self,
         guild_id: serenity::GuildId,
         channel_id: serenity::ChannelId,
-    ) -> Option<serenity::GuildChannel> {
+    ) -> Option<(serenity::GuildChannel, Vec<serenity::ChannelId>)> {
         self.cache
             .remove(&(guild_id.get(), channel_id.get()))
             .and_then(|(_, (obj, _))| {
-                if let CachedObject::Channel(c) = obj {
-                    Some(c)
+                if let CachedObject::Channel(c, children) = obj {
+                    Some((c, children))
                 } else {
                     None
                 }
             })
     }
 
+    #[cfg_attr(not(feature = "role-protection"), allow(dead_code))]
     pub fn take_role(
         &self,
         guild_id: serenity::GuildId,
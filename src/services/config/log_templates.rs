@@ -0,0 +1,581 @@
+use crate::services::config::builders::*;
+use crate::services::localization::{L10nProxy, TemplateError};
+use crate::services::log_templates::{
+    LOG_TYPES, LogType, LogTypeGroup, find_log_type, parse_hex_color,
+};
+use crate::services::logger::LogLevel;
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+
+const LEVELS: [LogLevel; 4] = [LogLevel::Info, LogLevel::Warn, LogLevel::Error, LogLevel::Audit];
+
+pub enum LogTemplateInteractionResult {
+    /// Update the menu with new components
+    Components(Vec<serenity::CreateComponent<'static>>),
+    /// Show a modal (must be direct response, no acknowledge)
+    ShowModal(serenity::CreateModal<'static>),
+    None,
+}
+
+/// Cuts a text to a Discord component limit.
+fn truncate(text: String, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text;
+    }
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.push('…');
+    cut
+}
+
+fn level_label(level: LogLevel, l10n: &L10nProxy) -> String {
+    match level {
+        LogLevel::Info => l10n.t("config-logtpl-level-info", None),
+        LogLevel::Warn => l10n.t("config-logtpl-level-warn", None),
+        LogLevel::Error => l10n.t("config-logtpl-level-error", None),
+        LogLevel::Audit => l10n.t("config-logtpl-level-audit", None),
+    }
+}
+
+/// Name shown for a log type: its first title, followed by the default description.
+fn type_label(log_type: &LogType, l10n: &L10nProxy) -> (String, String) {
+    (
+        truncate(l10n.t(log_type.title_keys[0], None), 100),
+        truncate(l10n.t(log_type.key, None), 100),
+    )
+}
+
+fn header_with_back(
+    title: String,
+    back_id: &str,
+    l10n: &L10nProxy,
+) -> serenity::CreateContainerComponent<'static> {
+    serenity::CreateContainerComponent::Section(serenity::CreateSection::new(
+        vec![serenity::CreateSectionComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(title),
+        )],
+        serenity::CreateSectionAccessory::Button(
+            serenity::CreateButton::new(back_id.to_string())
+                .label(l10n.t("config-back-label", None))
+                .style(serenity::ButtonStyle::Secondary),
+        ),
+    ))
+}
+
+/// Builds the log template overview: group selection and level colors.
+pub async fn build_overview_menu(
+    data: &Data,
+    guild_id: serenity::GuildId,
+    group: Option<LogTypeGroup>,
+    l10n: &L10nProxy,
+) -> Result<Vec<serenity::CreateComponent<'static>>, Error> {
+    let templates = data.logger.templates().get(guild_id).await?;
+
+    let mut components = vec![header_with_back(
+        format!("## {}", l10n.t("config-logtpl-header", None)),
+        "config_back_to_main",
+        l10n,
+    )];
+    components.push(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(l10n.t("config-logtpl-desc", None)),
+    ));
+    components.push(serenity::CreateContainerComponent::Separator(
+        serenity::CreateSeparator::new(true),
+    ));
+
+    // Level colors
+    let colors = LEVELS
+        .iter()
+        .map(|level| {
+            let marker = if templates.colors.contains_key(level.key()) {
+                ""
+            } else {
+                " *"
+            };
+            format!(
+                "{} {}: `#{:06x}`{}",
+                level.icon(),
+                level_label(*level, l10n),
+                templates.color(*level),
+                marker
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    components.push(serenity::CreateContainerComponent::Section(
+        serenity::CreateSection::new(
+            vec![serenity::CreateSectionComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!(
+                    "**{}**\n{}",
+                    l10n.t("config-logtpl-colors-label", None),
+                    colors
+                )),
+            )],
+            serenity::CreateSectionAccessory::Button(
+                serenity::CreateButton::new("config_logtpl_colors")
+                    .label(l10n.t("config-logtpl-edit-btn", None))
+                    .style(serenity::ButtonStyle::Primary),
+            ),
+        ),
+    ));
+    components.push(serenity::CreateContainerComponent::Separator(
+        serenity::CreateSeparator::new(true),
+    ));
+
+    // Group and type selection
+    let group_options: Vec<_> = LogTypeGroup::ALL
+        .iter()
+        .map(|g| {
+            serenity::CreateSelectMenuOption::new(l10n.t(g.l10n_key(), None), g.key())
+                .default_selection(Some(*g) == group)
+        })
+        .collect();
+    components.push(create_select_menu_row(
+        "config_logtpl_group".to_string(),
+        serenity::CreateSelectMenuKind::String {
+            options: group_options.into(),
+        },
+        l10n.t("config-logtpl-group-placeholder", None),
+    ));
+
+    if let Some(group) = group {
+        let type_options: Vec<_> = LOG_TYPES
+            .iter()
+            .filter(|t| t.group == group)
+            .map(|t| {
+                let (label, description) = type_label(t, l10n);
+                let label = if templates.types.contains_key(t.key) {
+                    truncate(format!("✏️ {}", label), 100)
+                } else {
+                    label
+                };
+                serenity::CreateSelectMenuOption::new(label, t.key).description(description)
+            })
+            .collect();
+        components.push(create_select_menu_row(
+            "config_logtpl_type".to_string(),
+            serenity::CreateSelectMenuKind::String {
+                options: type_options.into(),
+            },
+            l10n.t("config-logtpl-type-placeholder", None),
+        ));
+    }
+
+    Ok(vec![serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(components),
+    )])
+}
+
+/// Builds the page of one log type: current texts, variables and shown fields.
+pub async fn build_type_menu(
+    data: &Data,
+    guild_id: serenity::GuildId,
+    log_type: &LogType,
+    l10n: &L10nProxy,
+) -> Result<Vec<serenity::CreateComponent<'static>>, Error> {
+    let templates = data.logger.templates().get(guild_id).await?;
+    let template = templates.types.get(log_type.key).cloned().unwrap_or_default();
+
+    let (label, _) = type_label(log_type, l10n);
+    let mut components = vec![header_with_back(
+        format!("## {}", label),
+        &format!("config_logtpl_group_{}", log_type.group.key()),
+        l10n,
+    )];
+
+    // Current texts
+    let mut texts = vec![];
+    for key in log_type.keys() {
+        let (text, marker) = match template.overrides.get(key) {
+            Some(source) => (source.clone(), "✏️"),
+            None => (l10n.t(key, None), "▫️"),
+        };
+        texts.push(format!("{} `{}`\n{}", marker, key, truncate(text, 300)));
+    }
+    components.push(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(texts.join("\n")),
+    ));
+
+    let variables = log_type.variables(&data.l10n);
+    let variables = if variables.is_empty() {
+        l10n.t("log-val-none", None)
+    } else {
+        variables
+            .iter()
+            .map(|v| format!("`{{ ${} }}`", v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut args = fluent::FluentArgs::new();
+    args.set("variables", variables);
+    components.push(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(l10n.t("config-logtpl-variables", Some(&args))),
+    ));
+
+    // Shown fields
+    if !log_type.fields.is_empty() {
+        components.push(serenity::CreateContainerComponent::Separator(
+            serenity::CreateSeparator::new(true),
+        ));
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-logtpl-fields-label", None)),
+        ));
+
+        let field_options: Vec<_> = log_type
+            .fields
+            .iter()
+            .map(|field| {
+                serenity::CreateSelectMenuOption::new(truncate(l10n.t(field, None), 100), *field)
+                    .default_selection(!template.hidden_fields.iter().any(|h| h == field))
+            })
+            .collect();
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    format!("config_logtpl_fields_{}", log_type.key),
+                    serenity::CreateSelectMenuKind::String {
+                        options: field_options.into(),
+                    },
+                )
+                .placeholder(l10n.t("config-logtpl-fields-placeholder", None))
+                .min_values(0)
+                .max_values(log_type.fields.len() as u8),
+            ),
+        ));
+    }
+
+    components.push(serenity::CreateContainerComponent::Separator(
+        serenity::CreateSeparator::new(true),
+    ));
+    components.push(serenity::CreateContainerComponent::ActionRow(
+        serenity::CreateActionRow::buttons(vec![
+            serenity::CreateButton::new(format!("config_logtpl_edit_{}", log_type.key))
+                .label(l10n.t("config-logtpl-edit-btn", None))
+                .style(serenity::ButtonStyle::Primary),
+            serenity::CreateButton::new(format!("config_logtpl_reset_{}", log_type.key))
+                .label(l10n.t("config-logtpl-reset-btn", None))
+                .style(serenity::ButtonStyle::Danger),
+        ]),
+    ));
+
+    Ok(vec![serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(components),
+    )])
+}
+
+/// Modal with one text input per customizable key of a log type.
+fn build_edit_modal(
+    log_type: &LogType,
+    current: &HashMap<String, String>,
+    variables: &[String],
+    l10n: &L10nProxy,
+) -> serenity::CreateModal<'static> {
+    let variables = truncate(
+        variables
+            .iter()
+            .map(|v| format!("{{ ${} }}", v))
+            .collect::<Vec<_>>()
+            .join(" "),
+        100,
+    );
+
+    // Modals hold at most 5 components; log types have at most 4 keys
+    let labels = log_type
+        .keys()
+        .into_iter()
+        .map(|key| {
+            let mut input = serenity::CreateInputText::new(serenity::InputTextStyle::Paragraph, key)
+                .placeholder(truncate(l10n.t(key, None), 100))
+                .required(false)
+                .max_length(1000);
+            if let Some(source) = current.get(key) {
+                input = input.value(source.clone());
+            }
+
+            let label = if key == log_type.key {
+                l10n.t("config-logtpl-modal-desc-label", None)
+            } else {
+                truncate(l10n.t(key, None), 45)
+            };
+
+            serenity::CreateModalComponent::Label(
+                serenity::CreateLabel::input_text(label, input).description(variables.clone()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    serenity::CreateModal::new(
+        format!("logtpl_modal_edit_{}", log_type.key),
+        l10n.t("config-logtpl-modal-edit-title", None),
+    )
+    .components(labels)
+}
+
+/// Modal with a hex color input per log level.
+fn build_colors_modal(
+    colors: &HashMap<String, u32>,
+    l10n: &L10nProxy,
+) -> serenity::CreateModal<'static> {
+    let labels = LEVELS
+        .iter()
+        .map(|level| {
+            let mut input =
+                serenity::CreateInputText::new(serenity::InputTextStyle::Short, level.key())
+                    .placeholder(format!("#{:06x}", level.color()))
+                    .required(false)
+                    .max_length(7);
+            if let Some(color) = colors.get(level.key()) {
+                input = input.value(format!("#{:06x}", color));
+            }
+
+            serenity::CreateModalComponent::Label(
+                serenity::CreateLabel::input_text(level_label(*level, l10n), input)
+                    .description(l10n.t("config-logtpl-modal-color-desc", None)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    serenity::CreateModal::new(
+        "logtpl_modal_colors",
+        l10n.t("config-logtpl-modal-colors-title", None),
+    )
+    .components(labels)
+}
+
+pub async fn handle_interaction(
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<LogTemplateInteractionResult, Error> {
+    let guild_id = match interaction.guild_id {
+        Some(id) => id,
+        None => return Ok(LogTemplateInteractionResult::None),
+    };
+
+    let l10n = L10nProxy {
+        manager: data.l10n.clone(),
+        locale: interaction.locale.to_string(),
+    };
+    let custom_id = &interaction.data.custom_id;
+    let selected = match &interaction.data.kind {
+        serenity::ComponentInteractionDataKind::StringSelect { values } => values.to_vec(),
+        _ => vec![],
+    };
+
+    if custom_id == "config_logtpl_open" {
+        return Ok(LogTemplateInteractionResult::Components(
+            build_overview_menu(data, guild_id, None, &l10n).await?,
+        ));
+    }
+
+    if custom_id == "config_logtpl_colors" {
+        let templates = data.logger.templates().get(guild_id).await?;
+        return Ok(LogTemplateInteractionResult::ShowModal(build_colors_modal(
+            &templates.colors,
+            &l10n,
+        )));
+    }
+
+    if custom_id == "config_logtpl_group" {
+        let group = selected.first().and_then(|g| LogTypeGroup::from_key(g));
+        return Ok(LogTemplateInteractionResult::Components(
+            build_overview_menu(data, guild_id, group, &l10n).await?,
+        ));
+    }
+
+    if let Some(group) = custom_id.strip_prefix("config_logtpl_group_") {
+        return Ok(LogTemplateInteractionResult::Components(
+            build_overview_menu(data, guild_id, LogTypeGroup::from_key(group), &l10n).await?,
+        ));
+    }
+
+    if custom_id == "config_logtpl_type" {
+        if let Some(log_type) = selected.first().and_then(|t| find_log_type(t)) {
+            return Ok(LogTemplateInteractionResult::Components(
+                build_type_menu(data, guild_id, log_type, &l10n).await?,
+            ));
+        }
+    }
+
+    if let Some(log_type) = custom_id
+        .strip_prefix("config_logtpl_edit_")
+        .and_then(find_log_type)
+    {
+        let templates = data.logger.templates().get(guild_id).await?;
+        let current = templates
+            .types
+            .get(log_type.key)
+            .map(|t| t.overrides.clone())
+            .unwrap_or_default();
+        return Ok(LogTemplateInteractionResult::ShowModal(build_edit_modal(
+            log_type,
+            &current,
+            &log_type.variables(&data.l10n),
+            &l10n,
+        )));
+    }
+
+    if let Some(log_type) = custom_id
+        .strip_prefix("config_logtpl_fields_")
+        .and_then(find_log_type)
+    {
+        let hidden = log_type
+            .fields
+            .iter()
+            .filter(|field| !selected.iter().any(|s| s == *field))
+            .map(|field| field.to_string())
+            .collect();
+        data.logger
+            .templates()
+            .save_hidden_fields(guild_id, log_type, hidden)
+            .await?;
+        return Ok(LogTemplateInteractionResult::Components(
+            build_type_menu(data, guild_id, log_type, &l10n).await?,
+        ));
+    }
+
+    if let Some(log_type) = custom_id
+        .strip_prefix("config_logtpl_reset_")
+        .and_then(find_log_type)
+    {
+        data.logger.templates().reset(guild_id, log_type).await?;
+        return Ok(LogTemplateInteractionResult::Components(
+            build_type_menu(data, guild_id, log_type, &l10n).await?,
+        ));
+    }
+
+    Ok(LogTemplateInteractionResult::None)
+}
+
+/// Collects the text input values of a modal by their custom id.
+fn extract_text_inputs(components: &[serenity::Component]) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for component in components {
+        if let serenity::Component::Label(label) = component {
+            if let serenity::LabelComponent::InputText(input) = &label.component {
+                values.insert(
+                    input.custom_id.to_string(),
+                    input.value.as_deref().unwrap_or_default().to_string(),
+                );
+            }
+        }
+    }
+    values
+}
+
+/// Handles template and color modals. Invalid input is reported in an ephemeral message
+/// and nothing is saved.
+pub async fn handle_modal_submit(
+    ctx: &serenity::Context,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let guild_id = match interaction.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let l10n = L10nProxy {
+        manager: data.l10n.clone(),
+        locale: interaction.locale.to_string(),
+    };
+    let custom_id = &interaction.data.custom_id;
+    let values = extract_text_inputs(&interaction.data.components);
+    let templates = data.logger.templates();
+
+    let result: Result<Vec<serenity::CreateComponent<'static>>, String> =
+        if let Some(log_type) = custom_id
+            .strip_prefix("logtpl_modal_edit_")
+            .and_then(find_log_type)
+        {
+            let mut error = None;
+            for (key, source) in &values {
+                if source.trim().is_empty() {
+                    continue;
+                }
+                if let Err(e) = templates.validate(log_type, source) {
+                    let mut args = fluent::FluentArgs::new();
+                    args.set("key", key.clone());
+                    error = Some(match e {
+                        TemplateError::Syntax => {
+                            l10n.t("config-logtpl-error-syntax", Some(&args))
+                        }
+                        TemplateError::UnknownVariable(var) => {
+                            args.set("variable", var);
+                            l10n.t("config-logtpl-error-variable", Some(&args))
+                        }
+                    });
+                    break;
+                }
+            }
+
+            match error {
+                Some(error) => Err(error),
+                None => {
+                    templates
+                        .save_overrides(guild_id, log_type, values)
+                        .await?;
+                    Ok(build_type_menu(data, guild_id, log_type, &l10n).await?)
+                }
+            }
+        } else if custom_id == "logtpl_modal_colors" {
+            let mut colors = vec![];
+            let mut error = None;
+            for level in LEVELS {
+                let input = values.get(level.key()).map(|v| v.trim()).unwrap_or_default();
+                if input.is_empty() {
+                    colors.push((level, None));
+                    continue;
+                }
+                match parse_hex_color(input) {
+                    Some(color) => colors.push((level, Some(color))),
+                    None => {
+                        let mut args = fluent::FluentArgs::new();
+                        args.set("value", input.to_string());
+                        error = Some(l10n.t("config-logtpl-error-color", Some(&args)));
+                        break;
+                    }
+                }
+            }
+
+            match error {
+                Some(error) => Err(error),
+                None => {
+                    templates.save_colors(guild_id, colors).await?;
+                    Ok(build_overview_menu(data, guild_id, None, &l10n).await?)
+                }
+            }
+        } else {
+            return Ok(());
+        };
+
+    match result {
+        Ok(components) => {
+            interaction
+                .create_response(&ctx.http, serenity::CreateInteractionResponse::Acknowledge)
+                .await?;
+            let edit = serenity::EditInteractionResponse::new()
+                .components(components)
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(
+                    serenity::CreateAllowedMentions::new()
+                        .empty_users()
+                        .empty_roles(),
+                );
+            interaction.edit_response(&ctx.http, edit).await?;
+        }
+        Err(error) => {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(error)
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -6,8 +6,10 @@ use poise::serenity_prelude as serenity;
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 
 pub mod builders;
+pub mod log_templates;
 pub mod modules;
 pub mod whitelist;
+pub mod whitelist_command;
 
 pub use builders::*;
 
@@ -49,6 +51,10 @@ pub async fn build_main_menu(
 
     let log_channel_id = g_config.as_ref().and_then(|c| c.log_channel_id);
     let jail_role_id = g_config.as_ref().and_then(|c| c.jail_role_id);
+    let log_webhooks_enabled = g_config
+        .as_ref()
+        .map(|c| c.log_webhooks_enabled)
+        .unwrap_or(false);
 
     let mut inner_components = vec![];
 
@@ -70,6 +76,42 @@ pub async fn build_main_menu(
         l10n.t("config-select-log-channel-placeholder", None),
     ));
 
+    // Webhook delivery toggle
+    inner_components.push(serenity::CreateContainerComponent::Section(
+        serenity::CreateSection::new(
+            vec![serenity::CreateSectionComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(l10n.t("config-log-webhooks-label", None)),
+            )],
+            serenity::CreateSectionAccessory::Button(
+                serenity::CreateButton::new("config_general_webhooks_toggle")
+                    .label(if log_webhooks_enabled {
+                        l10n.t("config-btn-enabled", None)
+                    } else {
+                        l10n.t("config-btn-disabled", None)
+                    })
+                    .style(if log_webhooks_enabled {
+                        serenity::ButtonStyle::Success
+                    } else {
+                        serenity::ButtonStyle::Secondary
+                    }),
+            ),
+        ),
+    ));
+
+    // Log templates
+    inner_components.push(serenity::CreateContainerComponent::Section(
+        serenity::CreateSection::new(
+            vec![serenity::CreateSectionComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(l10n.t("config-logtpl-label", None)),
+            )],
+            serenity::CreateSectionAccessory::Button(
+                serenity::CreateButton::new("config_logtpl_open")
+                    .label(l10n.t("config-logtpl-open-btn", None))
+                    .style(serenity::ButtonStyle::Primary),
+            ),
+        ),
+    ));
+
     // Jail Role Section
     inner_components.push(serenity::CreateContainerComponent::Separator(
         serenity::CreateSeparator::new(false),
@@ -117,106 +159,92 @@ pub async fn build_main_menu(
         }
     };
 
-    let options = vec![
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-channel-protection-label", None),
-                get_status(ModuleType::ChannelProtection)
-            ),
+    // (module, label key, select value, description key)
+    let modules = [
+        (
+            ModuleType::ChannelProtection,
+            "config-channel-protection-label",
             "ChannelProtection",
-        )
-        .description(l10n.t("config-channel-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-channel-permission-protection-label", None),
-                get_status(ModuleType::ChannelPermissionProtection)
-            ),
+            "config-channel-protection-desc",
+        ),
+        (
+            ModuleType::ChannelPermissionProtection,
+            "config-channel-permission-protection-label",
             "ChannelPermissionProtection",
-        )
-        .description(l10n.t("config-channel-permission-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-role-protection-label", None),
-                get_status(ModuleType::RoleProtection)
-            ),
+            "config-channel-permission-protection-desc",
+        ),
+        (
+            ModuleType::RoleProtection,
+            "config-role-protection-label",
             "RoleProtection",
-        )
-        .description(l10n.t("config-role-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-role-permission-protection-label", None),
-                get_status(ModuleType::RolePermissionProtection)
-            ),
+            "config-role-protection-desc",
+        ),
+        (
+            ModuleType::RolePermissionProtection,
+            "config-role-permission-protection-label",
             "RolePermissionProtection",
-        )
-        .description(l10n.t("config-role-permission-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-member-permission-protection-label", None),
-                get_status(ModuleType::MemberPermissionProtection)
-            ),
+            "config-role-permission-protection-desc",
+        ),
+        (
+            ModuleType::MemberPermissionProtection,
+            "config-member-permission-protection-label",
             "MemberPermissionProtection",
-        )
-        .description(l10n.t("config-member-permission-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-bot-adding-protection-label", None),
-                get_status(ModuleType::BotAddingProtection)
-            ),
+            "config-member-permission-protection-desc",
+        ),
+        (
+            ModuleType::BotAddingProtection,
+            "config-bot-adding-protection-label",
             "BotAddingProtection",
-        )
-        .description(l10n.t("config-bot-adding-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-moderation-protection-label", None),
-                get_status(ModuleType::ModerationProtection)
-            ),
+            "config-bot-adding-protection-desc",
+        ),
+        (
+            ModuleType::ModerationProtection,
+            "config-moderation-protection-label",
             "ModerationProtection",
-        )
-        .description(l10n.t("config-moderation-protection-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-logging-label", None),
-                get_status(ModuleType::Logging)
-            ),
+            "config-moderation-protection-desc",
+        ),
+        (
+            ModuleType::Logging,
+            "config-logging-label",
             "Logging",
-        )
-        .description(l10n.t("config-logging-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-sticky-roles-label", None),
-                get_status(ModuleType::StickyRoles)
-            ),
+            "config-logging-desc",
+        ),
+        (
+            ModuleType::StickyRoles,
+            "config-sticky-roles-label",
             "StickyRoles",
-        )
-        .description(l10n.t("config-sticky-roles-desc", None)),
-        serenity::CreateSelectMenuOption::new(
-            format!(
-                "{} - {}",
-                l10n.t("config-invite-tracking-label", None),
-                get_status(ModuleType::InviteTracking)
-            ),
+            "config-sticky-roles-desc",
+        ),
+        (
+            ModuleType::InviteTracking,
+            "config-invite-tracking-label",
             "InviteTracking",
-        )
-        .description(l10n.t("module-invite-tracking-desc", None)),
+            "module-invite-tracking-desc",
+        ),
     ];
 
-    inner_components.push(create_select_menu_row(
-        "config_navigate_modules".to_string(),
-        serenity::CreateSelectMenuKind::String {
-            options: options.into(),
-        },
-        l10n.t("config-select-module-placeholder", None),
-    ));
+    // Only modules compiled into this build can be configured
+    let options: Vec<_> = modules
+        .into_iter()
+        .filter(|(module, ..)| module.is_available())
+        .map(|(module, label_key, value, desc_key)| {
+            serenity::CreateSelectMenuOption::new(
+                format!("{} - {}", l10n.t(label_key, None), get_status(module)),
+                value,
+            )
+            .description(l10n.t(desc_key, None))
+        })
+        .collect();
+
+    if !options.is_empty() {
+        inner_components.push(create_select_menu_row(
+            "config_navigate_modules".to_string(),
+            serenity::CreateSelectMenuKind::String {
+                options: options.into(),
+            },
+            l10n.t("config-select-module-placeholder", None),
+        ));
+    }
 
     Ok(vec![serenity::CreateComponent::Container(
         serenity::CreateContainer::new(inner_components),
@@ -243,7 +271,9 @@ pub async fn build_module_menu(
                 module_type: Set(module),
                 ..Default::default()
             };
-            m.insert(&data.db).await?
+            let m = m.insert(&data.db).await?;
+            data.config_cache.invalidate_module(guild_id, module, None);
+            m
         }
     };
 
@@ -266,7 +296,7 @@ pub async fn build_module_menu(
         ModuleType::InviteTracking => l10n.t("config-invite-tracking-label", None),
     };
 
-    let mut inner_components = if module == ModuleType::Logging && page == 1 {
+    let mut inner_components = if module == ModuleType::Logging && page > 0 {
         create_header(name, true)
     } else if module == ModuleType::Logging {
         // Page 0: General - Header and Global toggle
@@ -401,7 +431,7 @@ pub async fn build_module_menu(
         ));
         inner_components.extend(modules::moderation_protection::build_ui(&mp_config, l10n));
     } else if module == ModuleType::Logging {
-        if page == 1 {
+        if page > 0 {
             let logging_config: crate::db::entities::module_configs::LoggingModuleConfig =
                 serde_json::from_value(m_config.config).unwrap_or_default();
             inner_components.extend(modules::logging::build_ui(page, &logging_config, l10n));
@@ -442,6 +472,13 @@ pub async fn build_module_menu(
                 } else {
                     serenity::ButtonStyle::Secondary
                 }),
+            serenity::CreateButton::new(format!("config_page_{}_2", module))
+                .label(l10n.t("config-page-ignore", None))
+                .style(if page == 2 {
+                    serenity::ButtonStyle::Primary
+                } else {
+                    serenity::ButtonStyle::Secondary
+                }),
         ];
         inner_components.push(serenity::CreateContainerComponent::ActionRow(
             serenity::CreateActionRow::Buttons(buttons.into()),
@@ -518,6 +555,33 @@ pub async fn handle_interaction(
         }
     }
 
+    // Log templates have their own menu and modals
+    if custom_id.starts_with("config_logtpl_") {
+        match log_templates::handle_interaction(interaction, data).await? {
+            log_templates::LogTemplateInteractionResult::ShowModal(modal) => {
+                interaction
+                    .create_response(&ctx.http, serenity::CreateInteractionResponse::Modal(modal))
+                    .await?;
+            }
+            log_templates::LogTemplateInteractionResult::Components(components) => {
+                interaction
+                    .create_response(&ctx.http, serenity::CreateInteractionResponse::Acknowledge)
+                    .await?;
+                let edit = serenity::EditInteractionResponse::new()
+                    .components(components)
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2 | serenity::MessageFlags::EPHEMERAL)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new().empty_users().empty_roles());
+                interaction.edit_response(&ctx.http, edit).await?;
+            }
+            log_templates::LogTemplateInteractionResult::None => {
+                interaction
+                    .create_response(&ctx.http, serenity::CreateInteractionResponse::Acknowledge)
+                    .await?;
+            }
+        }
+        return Ok(());
+    }
+
     // Acknowledge the interaction for non-modal responses
     interaction
         .create_response(&ctx.http, serenity::CreateInteractionResponse::Acknowledge)
@@ -526,7 +590,11 @@ pub async fn handle_interaction(
     let mut updated_reply = None;
 
     let mut page = 0;
-    if custom_id.ends_with("_channel") && custom_id.starts_with("config_log_") {
+    if custom_id.starts_with("config_log_ignore_") {
+        page = 2;
+    } else if (custom_id.ends_with("_channel") || custom_id.ends_with("_channels"))
+        && custom_id.starts_with("config_log_")
+    {
         page = 1;
     } else if custom_id.ends_with("_toggle") && custom_id.starts_with("config_log_") {
         page = 0;
@@ -662,6 +730,9 @@ pub async fn handle_interaction(
                 "invite_tracking" => ModuleType::InviteTracking,
                 _ => return Ok(()),
             };
+            if !module_type.is_available() {
+                return Ok(());
+            }
             updated_reply = Some(build_module_menu(data, guild_id, module_type, 0, &l10n).await?);
         } else if custom_id == "config_general_log_channel" {
             if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
@@ -680,10 +751,33 @@ pub async fn handle_interaction(
                     )
                     .exec(&data.db)
                     .await?;
+                    data.config_cache.invalidate_guild(guild_id);
 
                     updated_reply = Some(build_main_menu(data, guild_id, &l10n).await?);
                 }
             }
+        } else if custom_id == "config_general_webhooks_toggle" {
+            let enabled = guild_configs::Entity::find_by_id(guild_id.get() as i64)
+                .one(&data.db)
+                .await?
+                .map(|c| c.log_webhooks_enabled)
+                .unwrap_or(false);
+
+            guild_configs::Entity::insert(guild_configs::ActiveModel {
+                guild_id: Set(guild_id.get() as i64),
+                log_webhooks_enabled: Set(!enabled),
+                ..Default::default()
+            })
+            .on_conflict(
+                sea_orm::sea_query::OnConflict::column(guild_configs::Column::GuildId)
+                    .update_column(guild_configs::Column::LogWebhooksEnabled)
+                    .to_owned(),
+            )
+            .exec(&data.db)
+            .await?;
+            data.config_cache.invalidate_guild(guild_id);
+
+            updated_reply = Some(build_main_menu(data, guild_id, &l10n).await?);
         } else if custom_id == "config_jail_role" {
             if let serenity::ComponentInteractionDataKind::RoleSelect { values } =
                 &interaction.data.kind
@@ -701,6 +795,7 @@ pub async fn handle_interaction(
                     )
                     .exec(&data.db)
                     .await?;
+                    data.config_cache.invalidate_guild(guild_id);
 
                     updated_reply = Some(build_main_menu(data, guild_id, &l10n).await?);
                 }
@@ -723,6 +818,9 @@ pub async fn handle_interaction(
                         "InviteTracking" => ModuleType::InviteTracking,
                         _ => return Ok(()),
                     };
+                    if !module_type.is_available() {
+                        return Ok(());
+                    }
                     updated_reply =
                         Some(build_module_menu(data, guild_id, module_type, 0, &l10n).await?);
                 }
@@ -755,6 +853,9 @@ pub async fn handle_interaction(
                     "invite_tracking" | "InviteTracking" => ModuleType::InviteTracking,
                     _ => return Ok(()),
                 };
+                if !module_type.is_available() {
+                    return Ok(());
+                }
                 updated_reply =
                     Some(build_module_menu(data, guild_id, module_type, page_num, &l10n).await?);
             }
@@ -775,7 +876,14 @@ pub async fn handle_interaction(
                         "InviteTracking" => ModuleType::InviteTracking,
                         _ => return Ok(()),
                     };
+                    if !module_type.is_available() {
+                        return Ok(());
+                    }
 
+                    let old_config =
+                        module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
+                            .one(&data.db)
+                            .await?;
                     module_configs::Entity::insert(module_configs::ActiveModel {
                         guild_id: Set(guild_id.get() as i64),
                         module_type: Set(module_type),
@@ -792,6 +900,8 @@ pub async fn handle_interaction(
                     )
                     .exec(&data.db)
                     .await?;
+                    data.config_cache
+                        .invalidate_module(guild_id, module_type, old_config);
 
                     updated_reply =
                         Some(build_module_menu(data, guild_id, module_type, page, &l10n).await?);
@@ -814,6 +924,9 @@ pub async fn handle_interaction(
                         "InviteTracking" => ModuleType::InviteTracking,
                         _ => return Ok(()),
                     };
+                    if !module_type.is_available() {
+                        return Ok(());
+                    }
 
                     use crate::db::entities::module_configs::PunishmentType;
                     let punishment = match p_str.as_str() {
@@ -825,6 +938,10 @@ pub async fn handle_interaction(
                         _ => return Ok(()),
                     };
 
+                    let old_config =
+                        module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
+                            .one(&data.db)
+                            .await?;
                     module_configs::Entity::insert(module_configs::ActiveModel {
                         guild_id: Set(guild_id.get() as i64),
                         module_type: Set(module_type),
@@ -841,6 +958,8 @@ pub async fn handle_interaction(
                     )
                     .exec(&data.db)
                     .await?;
+                    data.config_cache
+                        .invalidate_module(guild_id, module_type, old_config);
 
                     updated_reply =
                         Some(build_module_menu(data, guild_id, module_type, page, &l10n).await?);
@@ -867,24 +986,27 @@ pub async fn handle_interaction(
                 "invite_tracking" | "InviteTracking" => ModuleType::InviteTracking,
                 _ => return Ok(()),
             };
+            if !module_type.is_available() {
+                return Ok(());
+            }
 
-            let module_configs::Model { revert, .. } =
-                match module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
+            let old_config =
+                module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
                     .one(&data.db)
-                    .await?
-                {
-                    Some(m) => m,
-                    None => {
-                        // If no config exists, create it with default values
-                        module_configs::ActiveModel {
-                            guild_id: Set(guild_id.get() as i64),
-                            module_type: Set(module_type),
-                            ..Default::default()
-                        }
-                        .insert(&data.db)
-                        .await?
+                    .await?;
+            let module_configs::Model { revert, .. } = match old_config.clone() {
+                Some(m) => m,
+                None => {
+                    // If no config exists, create it with default values
+                    module_configs::ActiveModel {
+                        guild_id: Set(guild_id.get() as i64),
+                        module_type: Set(module_type),
+                        ..Default::default()
                     }
-                };
+                    .insert(&data.db)
+                    .await?
+                }
+            };
 
             module_configs::Entity::update(module_configs::ActiveModel {
                 guild_id: Set(guild_id.get() as i64),
@@ -894,6 +1016,8 @@ pub async fn handle_interaction(
             })
             .exec(&data.db)
             .await?;
+            data.config_cache
+                .invalidate_module(guild_id, module_type, old_config);
 
             updated_reply =
                 Some(build_module_menu(data, guild_id, module_type, page, &l10n).await?);
@@ -953,6 +1077,8 @@ pub async fn handle_interaction(
             } else {
                 am.insert(&data.db).await?;
             }
+            data.config_cache
+                .invalidate_module(guild_id, module_type, config);
             updated_reply =
                 Some(build_module_menu(data, guild_id, module_type, page, &l10n).await?);
         } else if custom_id.starts_with("config_module_toggle_") {
@@ -977,11 +1103,15 @@ pub async fn handle_interaction(
                 "invite_tracking" | "InviteTracking" => ModuleType::InviteTracking,
                 _ => return Ok(()),
             };
+            if !module_type.is_available() {
+                return Ok(());
+            }
 
             let config = module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
                 .one(&data.db)
                 .await?;
 
+            let old_config = config.clone();
             let (mut am, current_enabled): (module_configs::ActiveModel, bool) = match config {
                 Some(m) => {
                     let enabled = m.enabled;
@@ -1002,17 +1132,8 @@ pub async fn handle_interaction(
             let new_enabled = !current_enabled;
             am.enabled = Set(new_enabled);
             am.update(&data.db).await?;
-
-            // If invite tracking is enabled, sync invites
-            if module_type == ModuleType::InviteTracking && new_enabled {
-                if let Err(e) = crate::modules::invite_tracking::tracking::sync_all_guild_invites(
-                    ctx, guild_id, data,
-                )
-                .await
-                {
-                    tracing::error!("Failed to sync invites on module enable: {:?}", e);
-                }
-            }
+            data.config_cache
+                .invalidate_module(guild_id, module_type, old_config);
 
             updated_reply =
                 Some(build_module_menu(data, guild_id, module_type, page, &l10n).await?);
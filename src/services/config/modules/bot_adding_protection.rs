@@ -1,19 +1,90 @@
-use crate::db::entities::module_configs::BotAddingProtectionModuleConfig;
+use crate::Data;
+use crate::db::entities::module_configs::{self, BotAddingProtectionModuleConfig, ModuleType};
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TryIntoModel};
 
 pub fn build_ui(
-    _config: &BotAddingProtectionModuleConfig,
-    _l10n: &L10nProxy,
+    config: &BotAddingProtectionModuleConfig,
+    l10n: &L10nProxy,
 ) -> Vec<serenity::CreateContainerComponent<'static>> {
-    vec![]
+    let mut components = vec![];
+
+    // Punish Adder Section
+    let adder_btn_label = if config.punish_adder {
+        l10n.t("config-btn-enabled", None)
+    } else {
+        l10n.t("config-btn-disabled", None)
+    };
+
+    let adder_btn = serenity::CreateButton::new("config_bap_punish_adder_toggle")
+        .label(adder_btn_label)
+        .style(if config.punish_adder {
+            serenity::ButtonStyle::Success
+        } else {
+            serenity::ButtonStyle::Secondary
+        });
+
+    components.push(serenity::CreateContainerComponent::Section(
+        serenity::CreateSection::new(
+            vec![serenity::CreateSectionComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(l10n.t("config-bap-punish-adder-label", None)),
+            )],
+            serenity::CreateSectionAccessory::Button(adder_btn),
+        ),
+    ));
+
+    components
 }
 
 pub async fn handle_interaction(
     _ctx: &serenity::Context,
-    _interaction: &serenity::ComponentInteraction,
-    _data: &crate::Data,
-    _guild_id: serenity::GuildId,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+    guild_id: serenity::GuildId,
 ) -> Result<bool, crate::Error> {
+    let custom_id = &interaction.data.custom_id;
+
+    if custom_id == "config_bap_punish_adder_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.punish_adder = !config.punish_adder;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
     Ok(false)
 }
+
+async fn get_config(
+    data: &Data,
+    guild_id: serenity::GuildId,
+) -> Result<(module_configs::ActiveModel, BotAddingProtectionModuleConfig), crate::Error> {
+    let db = &data.db;
+    let m_config = module_configs::Entity::find_by_id((
+        guild_id.get() as i64,
+        ModuleType::BotAddingProtection,
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("Config not found"))?;
+
+    let config: BotAddingProtectionModuleConfig =
+        serde_json::from_value(m_config.config.clone()).unwrap_or_default();
+    Ok((m_config.into(), config))
+}
+
+async fn save_config(
+    data: &Data,
+    mut config_active: module_configs::ActiveModel,
+    config: BotAddingProtectionModuleConfig,
+) -> Result<(), crate::Error> {
+    let old = config_active.clone().try_into_model().ok();
+    config_active.config = Set(serde_json::to_value(config)?);
+    let model = config_active.update(&data.db).await?;
+    data.config_cache.invalidate_module(
+        serenity::GuildId::new(model.guild_id as u64),
+        model.module_type,
+        old,
+    );
+    Ok(())
+}
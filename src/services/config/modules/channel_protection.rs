@@ -2,7 +2,7 @@ use crate::Data;
 use crate::db::entities::module_configs::{self, ChannelProtectionModuleConfig, ModuleType};
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TryIntoModel};
 
 pub fn build_ui(
     config: &ChannelProtectionModuleConfig,
@@ -34,6 +34,30 @@ pub fn build_ui(
         ),
     ));
 
+    // Require Approval Section
+    let approval_btn_label = if config.require_approval {
+        l10n.t("config-btn-enabled", None)
+    } else {
+        l10n.t("config-btn-disabled", None)
+    };
+
+    let approval_btn = serenity::CreateButton::new("config_cp_require_approval_toggle")
+        .label(approval_btn_label)
+        .style(if config.require_approval {
+            serenity::ButtonStyle::Success
+        } else {
+            serenity::ButtonStyle::Secondary
+        });
+
+    components.push(serenity::CreateContainerComponent::Section(
+        serenity::CreateSection::new(
+            vec![serenity::CreateSectionComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(l10n.t("config-cp-require-approval-label", None)),
+            )],
+            serenity::CreateSectionAccessory::Button(approval_btn),
+        ),
+    ));
+
     // Punish When Multi-Select
     let options = vec![
         serenity::CreateSelectMenuOption::new(l10n.t("config-cp-punish-create", None), "create")
@@ -76,6 +100,13 @@ pub async fn handle_interaction(
         return Ok(true);
     }
 
+    if custom_id == "config_cp_require_approval_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.require_approval = !config.require_approval;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
     if custom_id == "config_cp_punish_when" {
         if let serenity::ComponentInteractionDataKind::StringSelect { values } =
             &interaction.data.kind
@@ -111,7 +142,13 @@ async fn save_config(
     mut config_active: module_configs::ActiveModel,
     config: ChannelProtectionModuleConfig,
 ) -> Result<(), crate::Error> {
+    let old = config_active.clone().try_into_model().ok();
     config_active.config = Set(serde_json::to_value(config)?);
-    config_active.update(&data.db).await?;
+    let model = config_active.update(&data.db).await?;
+    data.config_cache.invalidate_module(
+        serenity::GuildId::new(model.guild_id as u64),
+        model.module_type,
+        old,
+    );
     Ok(())
 }
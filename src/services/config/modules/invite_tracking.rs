@@ -2,7 +2,7 @@ use crate::Data;
 use crate::db::entities::module_configs::{self, InviteTrackingModuleConfig, ModuleType};
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TryIntoModel};
 
 pub fn build_ui(
     config: &InviteTrackingModuleConfig,
@@ -228,7 +228,13 @@ async fn save_config(
     mut config_active: module_configs::ActiveModel,
     config: InviteTrackingModuleConfig,
 ) -> Result<(), crate::Error> {
+    let old = config_active.clone().try_into_model().ok();
     config_active.config = Set(serde_json::to_value(config)?);
-    config_active.update(&data.db).await?;
+    let model = config_active.update(&data.db).await?;
+    data.config_cache.invalidate_module(
+        serenity::GuildId::new(model.guild_id as u64),
+        model.module_type,
+        old,
+    );
     Ok(())
 }
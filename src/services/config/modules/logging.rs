@@ -2,7 +2,7 @@ use crate::Data;
 use crate::db::entities::module_configs::{self, LoggingModuleConfig, ModuleType};
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TryIntoModel};
 
 pub fn build_ui(
     page: u32,
@@ -37,10 +37,93 @@ pub fn build_ui(
                 serenity::ButtonStyle::Secondary
             });
 
+        let archive_btn = serenity::CreateButton::new("config_log_archive_toggle")
+            .label(l10n.t("config-log-archive-label", None))
+            .style(if config.archive_attachments {
+                serenity::ButtonStyle::Success
+            } else {
+                serenity::ButtonStyle::Secondary
+            });
+
+        let member_update_btn = serenity::CreateButton::new("config_log_member_update_toggle")
+            .label(l10n.t("config-log-member-update-label", None))
+            .style(if config.log_member_updates {
+                serenity::ButtonStyle::Success
+            } else {
+                serenity::ButtonStyle::Secondary
+            });
+
         components.push(serenity::CreateContainerComponent::ActionRow(
-            serenity::CreateActionRow::Buttons(vec![msg_btn, voice_btn, member_btn].into()),
+            serenity::CreateActionRow::Buttons(
+                vec![
+                    msg_btn,
+                    voice_btn,
+                    member_btn,
+                    archive_btn,
+                    member_update_btn,
+                ]
+                .into(),
+            ),
         ));
-    } else {
+
+        // Member update event toggles
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-member-update-events-label", None)),
+        ));
+
+        let event_toggles = [
+            (
+                "config_log_member_nick_toggle",
+                "config-log-member-nick-label",
+                config.log_nickname_changes,
+            ),
+            (
+                "config_log_member_avatar_toggle",
+                "config-log-member-avatar-label",
+                config.log_avatar_changes,
+            ),
+            (
+                "config_log_member_roles_toggle",
+                "config-log-member-roles-label",
+                config.log_role_changes,
+            ),
+            (
+                "config_log_member_timeout_toggle",
+                "config-log-member-timeout-label",
+                config.log_timeout_changes,
+            ),
+        ];
+
+        let event_buttons: Vec<_> = event_toggles
+            .into_iter()
+            .map(|(id, label, enabled)| {
+                serenity::CreateButton::new(id)
+                    .label(l10n.t(label, None))
+                    .style(if enabled {
+                        serenity::ButtonStyle::Success
+                    } else {
+                        serenity::ButtonStyle::Secondary
+                    })
+                    .disabled(!config.log_member_updates)
+            })
+            .collect();
+
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::Buttons(event_buttons.into()),
+        ));
+
+        let server_btn = serenity::CreateButton::new("config_log_server_toggle")
+            .label(l10n.t("config-log-server-label", None))
+            .style(if config.log_server {
+                serenity::ButtonStyle::Success
+            } else {
+                serenity::ButtonStyle::Secondary
+            });
+
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::Buttons(vec![server_btn].into()),
+        ));
+    } else if page == 1 {
         // Page 1: Channels
         // Message Log Channel Select
         components.push(serenity::CreateContainerComponent::TextDisplay(
@@ -98,6 +181,214 @@ pub fn build_ui(
                 .placeholder(l10n.t("config-log-member-channel-placeholder", None)),
             ),
         ));
+
+        // Member Update Log Channel Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-member-update-channel-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_member_update_channel",
+                    serenity::CreateSelectMenuKind::Channel {
+                        channel_types: Some(vec![serenity::ChannelType::Text].into()),
+                        default_channels: config
+                            .member_log_channel_id
+                            .map(|id| vec![serenity::ChannelId::new(id as u64).into()].into()),
+                    },
+                )
+                .placeholder(l10n.t("config-log-member-update-channel-placeholder", None)),
+            ),
+        ));
+
+        // Server Log Channel Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-server-channel-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_server_channel",
+                    serenity::CreateSelectMenuKind::Channel {
+                        channel_types: Some(vec![serenity::ChannelType::Text].into()),
+                        default_channels: config
+                            .server_log_channel_id
+                            .map(|id| vec![serenity::ChannelId::new(id as u64).into()].into()),
+                    },
+                )
+                .placeholder(l10n.t("config-log-server-channel-placeholder", None)),
+            ),
+        ));
+
+        // Attachment Archive Channels Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-archive-channels-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_archive_channels",
+                    serenity::CreateSelectMenuKind::Channel {
+                        channel_types: Some(vec![serenity::ChannelType::Text].into()),
+                        default_channels: if config.archive_channel_ids.is_empty() {
+                            None
+                        } else {
+                            Some(
+                                config
+                                    .archive_channel_ids
+                                    .iter()
+                                    .map(|id| serenity::ChannelId::new(*id as u64).into())
+                                    .collect::<Vec<_>>()
+                                    .into(),
+                            )
+                        },
+                    },
+                )
+                .min_values(0)
+                .max_values(25)
+                .placeholder(l10n.t("config-log-archive-channels-placeholder", None)),
+            ),
+        ));
+    } else {
+        // Page 2: Ignore lists
+        let ignore_bots_btn = serenity::CreateButton::new("config_log_ignore_bots_toggle")
+            .label(if config.ignore_bots {
+                l10n.t("config-btn-enabled", None)
+            } else {
+                l10n.t("config-btn-disabled", None)
+            })
+            .style(if config.ignore_bots {
+                serenity::ButtonStyle::Success
+            } else {
+                serenity::ButtonStyle::Secondary
+            });
+
+        components.push(serenity::CreateContainerComponent::Section(
+            serenity::CreateSection::new(
+                vec![serenity::CreateSectionComponent::TextDisplay(
+                    serenity::CreateTextDisplay::new(l10n.t("config-log-ignore-bots-label", None)),
+                )],
+                serenity::CreateSectionAccessory::Button(ignore_bots_btn),
+            ),
+        ));
+
+        let channel_defaults = |ids: &[i64]| {
+            if ids.is_empty() {
+                None
+            } else {
+                Some(
+                    ids.iter()
+                        .map(|id| serenity::ChannelId::new(*id as u64).into())
+                        .collect::<Vec<_>>()
+                        .into(),
+                )
+            }
+        };
+
+        // Ignored Channels Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-ignore-channels-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_ignore_channels",
+                    serenity::CreateSelectMenuKind::Channel {
+                        channel_types: Some(
+                            vec![
+                                serenity::ChannelType::Text,
+                                serenity::ChannelType::News,
+                                serenity::ChannelType::Voice,
+                                serenity::ChannelType::Stage,
+                                serenity::ChannelType::Forum,
+                            ]
+                            .into(),
+                        ),
+                        default_channels: channel_defaults(&config.ignored_channel_ids),
+                    },
+                )
+                .min_values(0)
+                .max_values(25)
+                .placeholder(l10n.t("config-log-ignore-channels-placeholder", None)),
+            ),
+        ));
+
+        // Ignored Categories Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-ignore-categories-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_ignore_categories",
+                    serenity::CreateSelectMenuKind::Channel {
+                        channel_types: Some(vec![serenity::ChannelType::Category].into()),
+                        default_channels: channel_defaults(&config.ignored_category_ids),
+                    },
+                )
+                .min_values(0)
+                .max_values(25)
+                .placeholder(l10n.t("config-log-ignore-categories-placeholder", None)),
+            ),
+        ));
+
+        // Ignored Users Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-ignore-users-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_ignore_users",
+                    serenity::CreateSelectMenuKind::User {
+                        default_users: if config.ignored_user_ids.is_empty() {
+                            None
+                        } else {
+                            Some(
+                                config
+                                    .ignored_user_ids
+                                    .iter()
+                                    .map(|id| serenity::UserId::new(*id as u64))
+                                    .collect::<Vec<_>>()
+                                    .into(),
+                            )
+                        },
+                    },
+                )
+                .min_values(0)
+                .max_values(25)
+                .placeholder(l10n.t("config-log-ignore-users-placeholder", None)),
+            ),
+        ));
+
+        // Ignored Roles Select
+        components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(l10n.t("config-log-ignore-roles-label", None)),
+        ));
+        components.push(serenity::CreateContainerComponent::ActionRow(
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    "config_log_ignore_roles",
+                    serenity::CreateSelectMenuKind::Role {
+                        default_roles: if config.ignored_role_ids.is_empty() {
+                            None
+                        } else {
+                            Some(
+                                config
+                                    .ignored_role_ids
+                                    .iter()
+                                    .map(|id| serenity::RoleId::new(*id as u64))
+                                    .collect::<Vec<_>>()
+                                    .into(),
+                            )
+                        },
+                    },
+                )
+                .min_values(0)
+                .max_values(25)
+                .placeholder(l10n.t("config-log-ignore-roles-placeholder", None)),
+            ),
+        ));
     }
 
     components
@@ -138,7 +429,7 @@ pub async fn handle_interaction(
             let db = data.db.clone();
             tokio::spawn(async move {
                 if let Err(e) =
-                    crate::modules::logging::events::membership::fetch_and_store_all_members(
+                    crate::services::event_manager::shared_events::fetch_and_store_all_members(
                         http, guild_id, db,
                     )
                     .await
@@ -152,13 +443,14 @@ pub async fn handle_interaction(
             });
         } else if !config.log_membership && was_enabled {
             // Disabling: delete all member roles for this guild ONLY IF sticky roles is also disabled
-            let is_sticky_enabled =
-                crate::modules::sticky_roles::tracking::get_sticky_roles_config(guild_id, data)
-                    .await?
-                    .is_some();
+            let is_sticky_enabled = data
+                .config_cache
+                .module_config(guild_id, ModuleType::StickyRoles)
+                .await?
+                .is_some_and(|m| m.enabled);
             if !is_sticky_enabled {
                 if let Err(e) =
-                    crate::modules::logging::events::membership::delete_all_guild_member_roles(
+                    crate::services::event_manager::shared_events::delete_all_guild_member_roles(
                         guild_id, data,
                     )
                     .await
@@ -175,6 +467,62 @@ pub async fn handle_interaction(
         return Ok(true);
     }
 
+    if custom_id == "config_log_archive_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.archive_attachments = !config.archive_attachments;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_member_update_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.log_member_updates = !config.log_member_updates;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_member_nick_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.log_nickname_changes = !config.log_nickname_changes;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_member_avatar_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.log_avatar_changes = !config.log_avatar_changes;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_member_roles_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.log_role_changes = !config.log_role_changes;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_member_timeout_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.log_timeout_changes = !config.log_timeout_changes;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_server_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.log_server = !config.log_server;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
+    if custom_id == "config_log_ignore_bots_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.ignore_bots = !config.ignore_bots;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
     // Channel select handlers
     if custom_id == "config_log_msg_channel" {
         if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
@@ -209,6 +557,78 @@ pub async fn handle_interaction(
         }
     }
 
+    if custom_id == "config_log_member_update_channel" {
+        if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
+            &interaction.data.kind
+        {
+            let (config_active, mut config) = get_config(data, guild_id).await?;
+            config.member_log_channel_id = values.first().map(|c| c.get() as i64);
+            save_config(data, config_active, config).await?;
+            return Ok(true);
+        }
+    }
+
+    if custom_id == "config_log_server_channel" {
+        if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
+            &interaction.data.kind
+        {
+            let (config_active, mut config) = get_config(data, guild_id).await?;
+            config.server_log_channel_id = values.first().map(|c| c.get() as i64);
+            save_config(data, config_active, config).await?;
+            return Ok(true);
+        }
+    }
+
+    if custom_id == "config_log_archive_channels" {
+        if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
+            &interaction.data.kind
+        {
+            let (config_active, mut config) = get_config(data, guild_id).await?;
+            config.archive_channel_ids = values.iter().map(|c| c.get() as i64).collect();
+            save_config(data, config_active, config).await?;
+            return Ok(true);
+        }
+    }
+
+    // Ignore list handlers
+    if custom_id == "config_log_ignore_channels" || custom_id == "config_log_ignore_categories" {
+        if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
+            &interaction.data.kind
+        {
+            let (config_active, mut config) = get_config(data, guild_id).await?;
+            let ids = values.iter().map(|c| c.get() as i64).collect();
+            if custom_id == "config_log_ignore_channels" {
+                config.ignored_channel_ids = ids;
+            } else {
+                config.ignored_category_ids = ids;
+            }
+            save_config(data, config_active, config).await?;
+            return Ok(true);
+        }
+    }
+
+    if custom_id == "config_log_ignore_users" {
+        if let serenity::ComponentInteractionDataKind::UserSelect { values } =
+            &interaction.data.kind
+        {
+            let (config_active, mut config) = get_config(data, guild_id).await?;
+            config.ignored_user_ids = values.iter().map(|u| u.get() as i64).collect();
+            save_config(data, config_active, config).await?;
+            return Ok(true);
+        }
+    }
+
+    if custom_id == "config_log_ignore_roles" {
+        if let serenity::ComponentInteractionDataKind::RoleSelect { values } =
+            &interaction.data.kind
+        {
+            let (config_active, mut config) = get_config(data, guild_id).await?;
+            config.ignored_role_ids = values.iter().map(|r| r.get() as i64).collect();
+            save_config(data, config_active, config).await?;
+            return Ok(true);
+        }
+    }
+
     Ok(false)
 }
 
@@ -232,7 +652,13 @@ async fn save_config(
     mut config_active: module_configs::ActiveModel,
     config: LoggingModuleConfig,
 ) -> Result<(), crate::Error> {
+    let old = config_active.clone().try_into_model().ok();
     config_active.config = Set(serde_json::to_value(config)?);
-    config_active.update(&data.db).await?;
+    let model = config_active.update(&data.db).await?;
+    data.config_cache.invalidate_module(
+        serenity::GuildId::new(model.guild_id as u64),
+        model.module_type,
+        old,
+    );
     Ok(())
 }
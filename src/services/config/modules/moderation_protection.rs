@@ -2,7 +2,7 @@ use crate::Data;
 use crate::db::entities::module_configs::{self, ModerationProtectionModuleConfig, ModuleType};
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TryIntoModel};
 
 pub fn build_ui(
     config: &ModerationProtectionModuleConfig,
@@ -16,6 +16,8 @@ pub fn build_ui(
             .default_selection(config.punish_when.contains(&"ban".to_string())),
         serenity::CreateSelectMenuOption::new(l10n.t("config-mp-punish-kick", None), "kick")
             .default_selection(config.punish_when.contains(&"kick".to_string())),
+        serenity::CreateSelectMenuOption::new(l10n.t("config-mp-punish-prune", None), "prune")
+            .default_selection(config.punish_when.contains(&"prune".to_string())),
         serenity::CreateSelectMenuOption::new(l10n.t("config-mp-punish-timeout", None), "timeout")
             .default_selection(config.punish_when.contains(&"timeout".to_string())),
     ];
@@ -28,7 +30,7 @@ pub fn build_ui(
     )
     .placeholder(l10n.t("config-mp-punish-when-placeholder", None))
     .min_values(0)
-    .max_values(3);
+    .max_values(4);
 
     components.push(serenity::CreateContainerComponent::ActionRow(
         serenity::CreateActionRow::SelectMenu(select_menu),
@@ -88,7 +90,13 @@ async fn save_config(
     mut config_active: module_configs::ActiveModel,
     config: ModerationProtectionModuleConfig,
 ) -> Result<(), crate::Error> {
+    let old = config_active.clone().try_into_model().ok();
     config_active.config = Set(serde_json::to_value(config)?);
-    config_active.update(&data.db).await?;
+    let model = config_active.update(&data.db).await?;
+    data.config_cache.invalidate_module(
+        serenity::GuildId::new(model.guild_id as u64),
+        model.module_type,
+        old,
+    );
     Ok(())
 }
@@ -1,21 +1,96 @@
-use crate::db::entities::module_configs::RolePermissionProtectionModuleConfig;
+use crate::Data;
+use crate::db::entities::module_configs::{self, ModuleType, RolePermissionProtectionModuleConfig};
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TryIntoModel};
 
 pub fn build_ui(
-    _config: &RolePermissionProtectionModuleConfig,
-    _l10n: &L10nProxy,
+    config: &RolePermissionProtectionModuleConfig,
+    l10n: &L10nProxy,
 ) -> Vec<serenity::CreateContainerComponent<'static>> {
-    // Role Permission Protection currently has no specific configuration options
-    // besides the common ones (punishment, log channel, etc.)
-    vec![]
+    let mut components = vec![];
+
+    // Require Approval Section
+    let approval_btn_label = if config.require_approval {
+        l10n.t("config-btn-enabled", None)
+    } else {
+        l10n.t("config-btn-disabled", None)
+    };
+
+    let approval_btn = serenity::CreateButton::new("config_rpp_require_approval_toggle")
+        .label(approval_btn_label)
+        .style(if config.require_approval {
+            serenity::ButtonStyle::Success
+        } else {
+            serenity::ButtonStyle::Secondary
+        });
+
+    components.push(serenity::CreateContainerComponent::Section(
+        serenity::CreateSection::new(
+            vec![serenity::CreateSectionComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(l10n.t("config-rpp-require-approval-label", None)),
+            )],
+            serenity::CreateSectionAccessory::Button(approval_btn),
+        ),
+    ));
+
+    components
 }
 
 pub async fn handle_interaction(
     _ctx: &serenity::Context,
-    _interaction: &serenity::ComponentInteraction,
-    _data: &crate::Data,
-    _guild_id: serenity::GuildId,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+    guild_id: serenity::GuildId,
 ) -> Result<bool, crate::Error> {
+    let custom_id = &interaction.data.custom_id;
+
+    if custom_id == "config_rpp_require_approval_toggle" {
+        let (config_active, mut config) = get_config(data, guild_id).await?;
+        config.require_approval = !config.require_approval;
+        save_config(data, config_active, config).await?;
+        return Ok(true);
+    }
+
     Ok(false)
 }
+
+async fn get_config(
+    data: &Data,
+    guild_id: serenity::GuildId,
+) -> Result<
+    (
+        module_configs::ActiveModel,
+        RolePermissionProtectionModuleConfig,
+    ),
+    crate::Error,
+> {
+    let db = &data.db;
+    let m_config = module_configs::Entity::find_by_id((
+        guild_id.get() as i64,
+        ModuleType::RolePermissionProtection,
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("Config not found"))?;
+
+    let config: RolePermissionProtectionModuleConfig =
+        serde_json::from_value(m_config.config.clone()).unwrap_or_default();
+    Ok((m_config.into(), config))
+}
+
+async fn save_config(
+    data: &Data,
+    mut config_active: module_configs::ActiveModel,
+    config: RolePermissionProtectionModuleConfig,
+) -> Result<(), crate::Error> {
+    let old = config_active.clone().try_into_model().ok();
+    config_active.config = Set(serde_json::to_value(config)?);
+    let model = config_active.update(&data.db).await?;
+    data.config_cache.invalidate_module(
+        serenity::GuildId::new(model.guild_id as u64),
+        model.module_type,
+        old,
+    );
+    Ok(())
+}
@@ -13,7 +13,7 @@ pub fn build_ui(
 }
 
 pub async fn handle_interaction(
-    ctx: &serenity::Context,
+    _ctx: &serenity::Context,
     interaction: &serenity::ComponentInteraction,
     data: &Data,
     guild_id: serenity::GuildId,
@@ -23,40 +23,14 @@ pub async fn handle_interaction(
     if custom_id.starts_with("config_module_toggle_sticky_roles")
         || custom_id == "config_module_toggle_StickyRoles"
     {
-        let (config_active, enabled) = get_config_status(data, guild_id).await?;
-        let new_enabled = !enabled;
+        let old_config = get_config(data, guild_id).await?;
 
-        let mut active = config_active;
-        active.enabled = Set(new_enabled);
+        let mut active: module_configs::ActiveModel = old_config.clone().into();
+        active.enabled = Set(!old_config.enabled);
         active.update(&data.db).await?;
-
-        if new_enabled {
-            // Enabling: fetch all members and store their roles in background
-            let http = ctx.http.clone();
-            let db = data.db.clone();
-            tokio::spawn(async move {
-                if let Err(e) =
-                    crate::modules::logging::events::membership::fetch_and_store_all_members(
-                        http, guild_id, db,
-                    )
-                    .await
-                {
-                    tracing::error!(
-                        "Failed to fetch and store members for guild {} (StickyRoles): {:?}",
-                        guild_id,
-                        e
-                    );
-                }
-            });
-        } else {
-            // Disabling: check if logging also disabled
-            if !is_logging_membership_enabled(data, guild_id).await? {
-                crate::modules::logging::events::membership::delete_all_guild_member_roles(
-                    guild_id, data,
-                )
-                .await?;
-            }
-        }
+        // Member roles are fetched or deleted by the module
+        data.config_cache
+            .invalidate_module(guild_id, ModuleType::StickyRoles, Some(old_config));
 
         return Ok(true);
     }
@@ -64,10 +38,10 @@ pub async fn handle_interaction(
     Ok(false)
 }
 
-async fn get_config_status(
+async fn get_config(
     data: &Data,
     guild_id: serenity::GuildId,
-) -> Result<(module_configs::ActiveModel, bool), crate::Error> {
+) -> Result<module_configs::Model, crate::Error> {
     let db = &data.db;
     let m_config =
         module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::StickyRoles))
@@ -75,24 +49,5 @@ async fn get_config_status(
             .await?
             .ok_or_else(|| anyhow::anyhow!("Config not found"))?;
 
-    Ok((m_config.clone().into(), m_config.enabled))
-}
-
-async fn is_logging_membership_enabled(
-    data: &Data,
-    guild_id: serenity::GuildId,
-) -> Result<bool, crate::Error> {
-    let m_config = module_configs::Entity::find_by_id((guild_id.get() as i64, ModuleType::Logging))
-        .one(&data.db)
-        .await?;
-
-    if let Some(m) = m_config {
-        if !m.enabled {
-            return Ok(false);
-        }
-        let config: crate::db::entities::module_configs::LoggingModuleConfig =
-            serde_json::from_value(m.config).unwrap_or_default();
-        return Ok(config.log_membership);
-    }
-    Ok(false)
+    Ok(m_config)
 }
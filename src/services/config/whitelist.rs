@@ -1,7 +1,13 @@
 use crate::db::entities::{
-    module_configs::ModuleType, whitelist_role, whitelist_user, whitelists::WhitelistLevel,
+    guild_configs, module_configs::ModuleType, whitelist_role, whitelist_user,
+    whitelists::WhitelistLevel,
 };
+use crate::services::duration::{format_duration, parse_duration};
 use crate::services::localization::L10nProxy;
+use crate::services::whitelist::{
+    EntrySnapshot, PendingGrant, WhitelistTarget, describe_entry, entry_actions, not_expired_role,
+    not_expired_user, scoped_actions,
+};
 use crate::{Data, Error};
 use poise::serenity_prelude as serenity;
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, Iterable, QueryFilter, Set};
@@ -40,6 +46,7 @@ pub async fn check_permission(
         .filter(whitelist_user::Column::UserId.eq(member.user.id.get() as i64))
         .filter(whitelist_user::Column::Level.eq(WhitelistLevel::Head))
         .filter(cond)
+        .filter(not_expired_user())
         .one(&data.db)
         .await?;
 
@@ -63,6 +70,7 @@ pub async fn check_permission(
             .filter(whitelist_role::Column::RoleId.is_in(role_ids))
             .filter(whitelist_role::Column::Level.eq(WhitelistLevel::Head))
             .filter(cond)
+            .filter(not_expired_role())
             .one(&data.db)
             .await?;
 
@@ -107,6 +115,32 @@ impl WhitelistItem {
             WhitelistItem::Role(r) => r.level,
         }
     }
+
+    fn expires_at(&self) -> Option<sea_orm::prelude::DateTimeWithTimeZone> {
+        match self {
+            WhitelistItem::User(u) => u.expires_at,
+            WhitelistItem::Role(r) => r.expires_at,
+        }
+    }
+
+    fn actions(&self) -> Vec<String> {
+        match self {
+            WhitelistItem::User(u) => entry_actions(&u.actions),
+            WhitelistItem::Role(r) => entry_actions(&r.actions),
+        }
+    }
+
+    pub fn snapshot(&self) -> EntrySnapshot {
+        match self {
+            WhitelistItem::User(u) => EntrySnapshot::from(u),
+            WhitelistItem::Role(r) => EntrySnapshot::from(r),
+        }
+    }
+}
+
+/// Localized label of a `punish_when` action value.
+fn action_label(action: &str, l10n: &L10nProxy) -> String {
+    l10n.t(&format!("config-whitelist-action-{}", action), None)
 }
 
 /// Builds the whitelist configuration menu with pagination
@@ -174,6 +208,40 @@ pub async fn build_whitelist_menu(
         serenity::CreateSeparator::new(true),
     ));
 
+    // Owner DM notifications for whitelist changes (global menu only)
+    if module.is_none() {
+        let owner_dm = guild_configs::Entity::find_by_id(guild_id.get() as i64)
+            .one(&data.db)
+            .await?
+            .is_some_and(|c| c.whitelist_owner_dm);
+        components.push(serenity::CreateContainerComponent::Section(
+            serenity::CreateSection::new(
+                vec![serenity::CreateSectionComponent::TextDisplay(
+                    serenity::CreateTextDisplay::new(
+                        l10n.t("config-whitelist-owner-dm-label", None),
+                    ),
+                )],
+                serenity::CreateSectionAccessory::Button(
+                    serenity::CreateButton::new("config_whitelist_owner_dm_toggle")
+                        .label(if owner_dm {
+                            l10n.t("config-btn-enabled", None)
+                        } else {
+                            l10n.t("config-btn-disabled", None)
+                        })
+                        .style(if owner_dm {
+                            serenity::ButtonStyle::Success
+                        } else {
+                            serenity::ButtonStyle::Secondary
+                        })
+                        .disabled(!is_head),
+                ),
+            ),
+        ));
+        components.push(serenity::CreateContainerComponent::Separator(
+            serenity::CreateSeparator::new(true),
+        ));
+    }
+
     // Fetch entries
     let users = whitelist_user::Entity::find()
         .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
@@ -239,7 +307,7 @@ pub async fn build_whitelist_menu(
             components.push(serenity::CreateContainerComponent::Section(
                 serenity::CreateSection::new(
                     vec![serenity::CreateSectionComponent::TextDisplay(
-                        serenity::CreateTextDisplay::new(format!("### {}", level_label)),
+                        serenity::CreateTextDisplay::new(entry_details(item, level_label, l10n)),
                     )],
                     serenity::CreateSectionAccessory::Button(
                         serenity::CreateButton::new(format!(
@@ -321,12 +389,111 @@ pub async fn build_whitelist_menu(
     )])
 }
 
+/// Level line of an entry, followed by its action scope and expiry when set.
+fn entry_details(item: &WhitelistItem, level_label: String, l10n: &L10nProxy) -> String {
+    let mut text = format!("### {}", level_label);
+
+    let actions = item.actions();
+    if !actions.is_empty() {
+        let mut args = fluent::FluentArgs::new();
+        args.set(
+            "actions",
+            actions
+                .iter()
+                .map(|a| action_label(a, l10n))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        text.push_str(&format!(
+            "\n-# {}",
+            l10n.t("config-whitelist-actions", Some(&args))
+        ));
+    }
+
+    if let Some(expires_at) = item.expires_at() {
+        let mut args = fluent::FluentArgs::new();
+        args.set("time", format!("<t:{}:R>", expires_at.timestamp()));
+        text.push_str(&format!(
+            "\n-# {}",
+            l10n.t("config-whitelist-expires", Some(&args))
+        ));
+    }
+
+    text
+}
+
+/// Optional action scope select shared by the whitelist modals; nothing selected
+/// means every action. Modules without `punish_when` actions get no select.
+fn build_actions_label(
+    module: Option<ModuleType>,
+    current_actions: &[String],
+    l10n: &L10nProxy,
+) -> Option<serenity::CreateLabel<'static>> {
+    let actions = scoped_actions(module);
+    if actions.is_empty() {
+        return None;
+    }
+
+    let options: Vec<_> = actions
+        .iter()
+        .map(|action| {
+            serenity::CreateSelectMenuOption::new(action_label(action, l10n), *action)
+                .default_selection(current_actions.iter().any(|a| a == action))
+        })
+        .collect();
+
+    let select = serenity::CreateSelectMenu::new(
+        "whitelist_modal_actions_select",
+        serenity::CreateSelectMenuKind::String {
+            options: options.into(),
+        },
+    )
+    .min_values(0)
+    .max_values(actions.len() as u8)
+    .required(false);
+
+    Some(
+        serenity::CreateLabel::select_menu(
+            l10n.t("config-whitelist-modal-actions-label", None),
+            select,
+        )
+        .description(l10n.t("config-whitelist-modal-actions-description", None)),
+    )
+}
+
+/// Optional duration input shared by the whitelist modals; empty means permanent.
+/// When editing a temporary entry, the remaining time is prefilled.
+fn build_duration_label(
+    current_expiry: Option<sea_orm::prelude::DateTimeWithTimeZone>,
+    l10n: &L10nProxy,
+) -> serenity::CreateLabel<'static> {
+    let mut input = serenity::CreateInputText::new(
+        serenity::InputTextStyle::Short,
+        "whitelist_modal_duration_input",
+    )
+    .placeholder(l10n.t("config-whitelist-modal-duration-placeholder", None))
+    .required(false)
+    .max_length(32);
+
+    if let Some(expires_at) = current_expiry {
+        let remaining = (expires_at.to_utc() - chrono::Utc::now()).num_seconds();
+        if remaining > 0 {
+            input = input.value(format_duration(remaining));
+        }
+    }
+
+    serenity::CreateLabel::input_text(l10n.t("config-whitelist-modal-duration-label", None), input)
+        .description(l10n.t("config-whitelist-modal-duration-description", None))
+}
+
 /// Creates a modal for adding/editing a whitelist user entry
 pub fn build_whitelist_user_modal<'a>(
     entry_id: Option<i32>,
     module: Option<ModuleType>,
     current_user_id: Option<serenity::UserId>,
     current_level: Option<WhitelistLevel>,
+    current_expiry: Option<sea_orm::prelude::DateTimeWithTimeZone>,
+    current_actions: &[String],
     l10n: &L10nProxy,
 ) -> serenity::CreateModal<'a> {
     let suffix = module
@@ -396,10 +563,19 @@ pub fn build_whitelist_user_modal<'a>(
     )
     .description(l10n.t("config-whitelist-modal-level-description", None));
 
-    serenity::CreateModal::new(custom_id, title).components(vec![
+    let mut components = vec![
         serenity::CreateModalComponent::Label(user_label),
         serenity::CreateModalComponent::Label(level_label),
-    ])
+    ];
+    if let Some(actions_label) = build_actions_label(module, current_actions, l10n) {
+        components.push(serenity::CreateModalComponent::Label(actions_label));
+    }
+    components.push(serenity::CreateModalComponent::Label(build_duration_label(
+        current_expiry,
+        l10n,
+    )));
+
+    serenity::CreateModal::new(custom_id, title).components(components)
 }
 
 /// Creates a modal for adding/editing a whitelist role entry
@@ -408,6 +584,8 @@ pub fn build_whitelist_role_modal<'a>(
     module: Option<ModuleType>,
     current_role_id: Option<serenity::RoleId>,
     current_level: Option<WhitelistLevel>,
+    current_expiry: Option<sea_orm::prelude::DateTimeWithTimeZone>,
+    current_actions: &[String],
     l10n: &L10nProxy,
 ) -> serenity::CreateModal<'a> {
     let suffix = module
@@ -477,10 +655,249 @@ pub fn build_whitelist_role_modal<'a>(
     )
     .description(l10n.t("config-whitelist-modal-level-description", None));
 
-    serenity::CreateModal::new(custom_id, title).components(vec![
+    let mut components = vec![
         serenity::CreateModalComponent::Label(role_label),
         serenity::CreateModalComponent::Label(level_label),
-    ])
+    ];
+    if let Some(actions_label) = build_actions_label(module, current_actions, l10n) {
+        components.push(serenity::CreateModalComponent::Label(actions_label));
+    }
+    components.push(serenity::CreateModalComponent::Label(build_duration_label(
+        current_expiry,
+        l10n,
+    )));
+
+    serenity::CreateModal::new(custom_id, title).components(components)
+}
+
+/// Asks the member to confirm a Head-level grant before it is saved.
+pub fn build_grant_confirmation(
+    id: u64,
+    grant: &PendingGrant,
+    l10n: &L10nProxy,
+) -> Vec<serenity::CreateComponent<'static>> {
+    let suffix = grant
+        .entry
+        .module_type
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| "global".to_string());
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("target", grant.entry.target.mention());
+
+    vec![serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(vec![
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "{}\n{}\n-# {}",
+                    l10n.t("config-whitelist-confirm-head-title", None),
+                    l10n.t("config-whitelist-confirm-head-desc", Some(&args)),
+                    describe_entry(&grant.entry, l10n),
+                ),
+            )),
+            serenity::CreateContainerComponent::ActionRow(serenity::CreateActionRow::buttons(
+                vec![
+                    serenity::CreateButton::new(format!(
+                        "config_whitelist_grant_confirm_{}_{}",
+                        id, suffix
+                    ))
+                    .label(l10n.t("config-whitelist-confirm-head-btn", None))
+                    .style(serenity::ButtonStyle::Danger),
+                    serenity::CreateButton::new(format!(
+                        "config_whitelist_grant_cancel_{}_{}",
+                        id, suffix
+                    ))
+                    .label(l10n.t("config-whitelist-cancel-btn", None))
+                    .style(serenity::ButtonStyle::Secondary),
+                ],
+            )),
+        ]),
+    )]
+}
+
+/// Finds an entry of this guild by id.
+async fn find_entry(
+    data: &Data,
+    guild_id: serenity::GuildId,
+    is_user: bool,
+    id: i32,
+) -> Result<Option<WhitelistItem>, Error> {
+    Ok(if is_user {
+        whitelist_user::Entity::find_by_id(id)
+            .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
+            .one(&data.db)
+            .await?
+            .map(WhitelistItem::User)
+    } else {
+        whitelist_role::Entity::find_by_id(id)
+            .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
+            .one(&data.db)
+            .await?
+            .map(WhitelistItem::Role)
+    })
+}
+
+/// Finds the entry a grant would overwrite: the edited entry, or the target's
+/// existing entry for the same module.
+async fn find_existing(data: &Data, grant: &PendingGrant) -> Result<Option<WhitelistItem>, Error> {
+    if let Some(id) = grant.entry_id {
+        return find_entry(
+            data,
+            grant.guild_id,
+            matches!(grant.entry.target, WhitelistTarget::User(_)),
+            id,
+        )
+        .await;
+    }
+
+    find_target_entry(
+        data,
+        grant.guild_id,
+        grant.entry.target,
+        grant.entry.module_type,
+    )
+    .await
+}
+
+/// Finds the target's entry for a module, or its global entry when `module` is `None`.
+pub async fn find_target_entry(
+    data: &Data,
+    guild_id: serenity::GuildId,
+    target: WhitelistTarget,
+    module: Option<ModuleType>,
+) -> Result<Option<WhitelistItem>, Error> {
+    Ok(match target {
+        WhitelistTarget::User(user_id) => whitelist_user::Entity::find()
+            .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(whitelist_user::Column::UserId.eq(user_id as i64))
+            .filter(match module {
+                Some(m) => whitelist_user::Column::ModuleType.eq(Some(m)),
+                None => whitelist_user::Column::ModuleType.is_null(),
+            })
+            .one(&data.db)
+            .await?
+            .map(WhitelistItem::User),
+        WhitelistTarget::Role(role_id) => whitelist_role::Entity::find()
+            .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(whitelist_role::Column::RoleId.eq(role_id as i64))
+            .filter(match module {
+                Some(m) => whitelist_role::Column::ModuleType.eq(Some(m)),
+                None => whitelist_role::Column::ModuleType.is_null(),
+            })
+            .one(&data.db)
+            .await?
+            .map(WhitelistItem::Role),
+    })
+}
+
+/// Whether a grant would newly make its target Head. A Head can manage the whitelist
+/// itself, so such grants are held until the member confirms them.
+pub async fn needs_confirmation(data: &Data, grant: &PendingGrant) -> Result<bool, Error> {
+    if grant.entry.level != WhitelistLevel::Head {
+        return Ok(false);
+    }
+
+    let already_head = find_existing(data, grant)
+        .await?
+        .map(|item| item.snapshot())
+        .is_some_and(|e| e.level == WhitelistLevel::Head && e.target == grant.entry.target);
+    Ok(!already_head)
+}
+
+/// Saves a grant, updating the existing entry or inserting a new one, and records
+/// the change in the whitelist history.
+pub async fn apply_grant(
+    ctx: &serenity::Context,
+    data: &Data,
+    grant: &PendingGrant,
+) -> Result<(), Error> {
+    let existing = find_existing(data, grant).await?;
+    let before = existing.as_ref().map(WhitelistItem::snapshot);
+    let entry = &grant.entry;
+    let actions = serde_json::json!(entry.actions);
+
+    let after = match (entry.target, existing) {
+        (WhitelistTarget::User(user_id), Some(WhitelistItem::User(existing))) => {
+            let mut active: whitelist_user::ActiveModel = existing.into();
+            active.user_id = Set(user_id as i64);
+            active.level = Set(entry.level);
+            active.expires_at = Set(entry.expires_at);
+            active.actions = Set(actions);
+            EntrySnapshot::from(&active.update(&data.db).await?)
+        }
+        (WhitelistTarget::User(user_id), _) => {
+            let model = whitelist_user::ActiveModel {
+                guild_id: Set(grant.guild_id.get() as i64),
+                user_id: Set(user_id as i64),
+                level: Set(entry.level),
+                module_type: Set(entry.module_type),
+                expires_at: Set(entry.expires_at),
+                actions: Set(actions),
+                ..Default::default()
+            };
+            EntrySnapshot::from(&model.insert(&data.db).await?)
+        }
+        (WhitelistTarget::Role(role_id), Some(WhitelistItem::Role(existing))) => {
+            let mut active: whitelist_role::ActiveModel = existing.into();
+            active.role_id = Set(role_id as i64);
+            active.level = Set(entry.level);
+            active.expires_at = Set(entry.expires_at);
+            active.actions = Set(actions);
+            EntrySnapshot::from(&active.update(&data.db).await?)
+        }
+        (WhitelistTarget::Role(role_id), _) => {
+            let model = whitelist_role::ActiveModel {
+                guild_id: Set(grant.guild_id.get() as i64),
+                role_id: Set(role_id as i64),
+                level: Set(entry.level),
+                module_type: Set(entry.module_type),
+                expires_at: Set(entry.expires_at),
+                actions: Set(actions),
+                ..Default::default()
+            };
+            EntrySnapshot::from(&model.insert(&data.db).await?)
+        }
+    };
+
+    data.whitelist
+        .record_change(ctx, grant.guild_id, grant.actor_id, before, Some(after))
+        .await
+}
+
+/// Deletes an entry and records the removal in the whitelist history.
+pub async fn delete_entry(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    actor_id: serenity::UserId,
+    item: WhitelistItem,
+) -> Result<(), Error> {
+    match &item {
+        WhitelistItem::User(u) => {
+            whitelist_user::Entity::delete_by_id(u.id)
+                .exec(&data.db)
+                .await?;
+        }
+        WhitelistItem::Role(r) => {
+            whitelist_role::Entity::delete_by_id(r.id)
+                .exec(&data.db)
+                .await?;
+        }
+    }
+
+    data.whitelist
+        .record_change(ctx, guild_id, actor_id, Some(item.snapshot()), None)
+        .await
+}
+
+/// Result type for whitelist modal submissions
+pub enum WhitelistModalResult {
+    /// Update the message with new components
+    Components(Vec<serenity::CreateComponent<'static>>),
+    /// The input was rejected; the message is shown to the user
+    Invalid(String),
+    /// No action needed
+    None,
 }
 
 /// Result type for whitelist interaction handling
@@ -598,6 +1015,8 @@ pub async fn handle_interaction(
                         module,
                         Some(serenity::UserId::new(entry.user_id as u64)),
                         Some(entry.level),
+                        entry.expires_at,
+                        &entry_actions(&entry.actions),
                         &l10n,
                     );
                     return Ok(WhitelistInteractionResult::ShowModal(modal));
@@ -613,6 +1032,8 @@ pub async fn handle_interaction(
                         module,
                         Some(serenity::RoleId::new(entry.role_id as u64)),
                         Some(entry.level),
+                        entry.expires_at,
+                        &entry_actions(&entry.actions),
                         &l10n,
                     );
                     return Ok(WhitelistInteractionResult::ShowModal(modal));
@@ -627,7 +1048,7 @@ pub async fn handle_interaction(
         if !check_perm(module).await? {
             return Ok(WhitelistInteractionResult::None);
         }
-        let modal = build_whitelist_user_modal(None, module, None, None, &l10n);
+        let modal = build_whitelist_user_modal(None, module, None, None, None, &[], &l10n);
         return Ok(WhitelistInteractionResult::ShowModal(modal));
     }
     if let Some(rest) = custom_id.strip_prefix("config_whitelist_add_role_page_") {
@@ -635,7 +1056,7 @@ pub async fn handle_interaction(
         if !check_perm(module).await? {
             return Ok(WhitelistInteractionResult::None);
         }
-        let modal = build_whitelist_role_modal(None, module, None, None, &l10n);
+        let modal = build_whitelist_role_modal(None, module, None, None, None, &[], &l10n);
         return Ok(WhitelistInteractionResult::ShowModal(modal));
     }
 
@@ -651,14 +1072,8 @@ pub async fn handle_interaction(
                 return Ok(WhitelistInteractionResult::None);
             }
 
-            if is_user {
-                whitelist_user::Entity::delete_by_id(id)
-                    .exec(&data.db)
-                    .await?;
-            } else {
-                whitelist_role::Entity::delete_by_id(id)
-                    .exec(&data.db)
-                    .await?;
+            if let Some(item) = find_entry(data, guild_id, is_user, id).await? {
+                delete_entry(ctx, data, guild_id, interaction.user.id, item).await?;
             }
 
             return Ok(WhitelistInteractionResult::Components(
@@ -667,18 +1082,112 @@ pub async fn handle_interaction(
         }
     }
 
+    // Confirm or cancel a held Head-level grant
+    if let Some(rest) = custom_id.strip_prefix("config_whitelist_grant_confirm_") {
+        if let Some((id, suffix)) = rest.split_once('_') {
+            let module = parse_module(suffix);
+            let grant =
+                data.whitelist
+                    .take_grant(id.parse().unwrap_or(0), guild_id, interaction.user.id);
+
+            let Some(grant) = grant else {
+                return Ok(WhitelistInteractionResult::Components(build_grant_expired(
+                    module, &l10n,
+                )));
+            };
+
+            if !check_perm(module).await? {
+                return Ok(WhitelistInteractionResult::None);
+            }
+
+            apply_grant(ctx, data, &grant).await?;
+            return Ok(WhitelistInteractionResult::Components(
+                build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
+            ));
+        }
+    }
+    if let Some(rest) = custom_id.strip_prefix("config_whitelist_grant_cancel_") {
+        if let Some((id, suffix)) = rest.split_once('_') {
+            let module = parse_module(suffix);
+            data.whitelist
+                .take_grant(id.parse().unwrap_or(0), guild_id, interaction.user.id);
+            let is_head = check_perm(module).await?;
+            return Ok(WhitelistInteractionResult::Components(
+                build_whitelist_menu(data, guild_id, module, 0, is_head, &l10n).await?,
+            ));
+        }
+    }
+
+    // Owner DM notifications toggle
+    if custom_id == "config_whitelist_owner_dm_toggle" {
+        if !check_perm(None).await? {
+            return Ok(WhitelistInteractionResult::None);
+        }
+
+        let enabled = guild_configs::Entity::find_by_id(guild_id.get() as i64)
+            .one(&data.db)
+            .await?
+            .is_some_and(|c| c.whitelist_owner_dm);
+
+        guild_configs::Entity::insert(guild_configs::ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            whitelist_owner_dm: Set(!enabled),
+            ..Default::default()
+        })
+        .on_conflict(
+            sea_orm::sea_query::OnConflict::column(guild_configs::Column::GuildId)
+                .update_column(guild_configs::Column::WhitelistOwnerDm)
+                .to_owned(),
+        )
+        .exec(&data.db)
+        .await?;
+        data.config_cache.invalidate_guild(guild_id);
+
+        return Ok(WhitelistInteractionResult::Components(
+            build_whitelist_menu(data, guild_id, None, 0, true, &l10n).await?,
+        ));
+    }
+
     Ok(WhitelistInteractionResult::None)
 }
 
+/// Shown when a held grant is confirmed after it timed out.
+fn build_grant_expired(
+    module: Option<ModuleType>,
+    l10n: &L10nProxy,
+) -> Vec<serenity::CreateComponent<'static>> {
+    let back_id = match module {
+        Some(m) => format!("config_whitelist_view_module_{}", m),
+        None => "config_whitelist_view_global".to_string(),
+    };
+
+    vec![serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::Section(
+            serenity::CreateSection::new(
+                vec![serenity::CreateSectionComponent::TextDisplay(
+                    serenity::CreateTextDisplay::new(
+                        l10n.t("config-whitelist-confirm-expired", None),
+                    ),
+                )],
+                serenity::CreateSectionAccessory::Button(
+                    serenity::CreateButton::new(back_id)
+                        .label(l10n.t("config-back-label", None))
+                        .style(serenity::ButtonStyle::Secondary),
+                ),
+            ),
+        )]),
+    )]
+}
+
 /// Handle modal submissions for whitelist entries
 pub async fn handle_modal_submit(
     ctx: &serenity::Context,
     interaction: &serenity::ModalInteraction,
     data: &Data,
-) -> Result<Option<Vec<serenity::CreateComponent<'static>>>, Error> {
+) -> Result<WhitelistModalResult, Error> {
     let guild_id = match interaction.guild_id {
         Some(id) => id,
-        None => return Ok(None),
+        None => return Ok(WhitelistModalResult::None),
     };
 
     let member = interaction
@@ -730,244 +1239,166 @@ pub async fn handle_modal_submit(
         None
     }
 
-    // For user/role selects, use the resolved data directly (not Option)
-    let resolved = &interaction.data.resolved;
-
-    // Handle new user modal submission
-    if let Some(suffix) = custom_id.strip_prefix("whitelist_modal_user_new_") {
-        let module = parse_module(suffix);
-        if !check_permission(ctx, data, guild_id, member, module).await? {
-            return Ok(None);
-        }
-
-        // Get selected user from resolved data
-        let selected_user_id = resolved.users.iter().next().map(|user| user.id);
-
-        // Get level from string select
-        let level_str = extract_string_select_value(
-            &interaction.data.components,
-            "whitelist_modal_level_select",
-        );
-        let selected_level = match level_str.as_deref() {
-            Some("head") => WhitelistLevel::Head,
-            Some("admin") => WhitelistLevel::Admin,
-            _ => WhitelistLevel::Invulnerable,
-        };
-
-        if let Some(user_id) = selected_user_id {
-            // Check if user already exists for this guild/module
-            let existing = whitelist_user::Entity::find()
-                .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
-                .filter(whitelist_user::Column::UserId.eq(user_id.get() as i64))
-                .filter(match module {
-                    Some(m) => whitelist_user::Column::ModuleType.eq(Some(m)),
-                    None => whitelist_user::Column::ModuleType.is_null(),
-                })
-                .one(&data.db)
-                .await?;
-
-            if let Some(existing) = existing {
-                // Update existing entry's level
-                let mut active: whitelist_user::ActiveModel = existing.into();
-                active.level = Set(selected_level);
-                active.update(&data.db).await?;
-            } else {
-                // Insert new entry
-                let model = whitelist_user::ActiveModel {
-                    guild_id: Set(guild_id.get() as i64),
-                    user_id: Set(user_id.get() as i64),
-                    level: Set(selected_level),
-                    module_type: Set(module),
-                    ..Default::default()
-                };
-                model.insert(&data.db).await?;
+    // Helper to extract all selected values of a string select
+    fn extract_string_select_values(
+        components: &[serenity::Component],
+        target_custom_id: &str,
+    ) -> Vec<String> {
+        for component in components {
+            if let serenity::Component::Label(label) = component {
+                if let serenity::LabelComponent::SelectMenu(menu) = &label.component {
+                    if &*menu.custom_id == target_custom_id {
+                        return menu.values.iter().map(|s| s.to_string()).collect();
+                    }
+                }
             }
-
-            return Ok(Some(
-                build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
-            ));
         }
+        vec![]
     }
 
-    // Handle edit user modal submission
-    if let Some(rest) = custom_id.strip_prefix("whitelist_modal_user_edit_") {
-        let parts: Vec<&str> = rest.splitn(2, '_').collect();
-        if parts.len() == 2 {
-            let id: i32 = parts[0].parse().unwrap_or(0);
-            let module = parse_module(parts[1]);
-
-            if !check_permission(ctx, data, guild_id, member, module).await? {
-                return Ok(None);
-            }
-
-            let existing = whitelist_user::Entity::find_by_id(id)
-                .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
-                .one(&data.db)
-                .await?;
-
-            if existing.is_some() {
-                // Get selected user from resolved data
-                let selected_user = resolved.users.iter().next();
-
-                // If no user is selected (cleared), delete the entry
-                if selected_user.is_none() {
-                    whitelist_user::Entity::delete_by_id(id)
-                        .exec(&data.db)
-                        .await?;
-
-                    return Ok(Some(
-                        build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
-                    ));
-                }
-
-                // Otherwise, update the entry
-                let existing = existing.unwrap();
-                let mut active: whitelist_user::ActiveModel = existing.into();
-
-                if let Some(user) = selected_user {
-                    active.user_id = Set(user.id.get() as i64);
-                }
-
-                // Get level from string select
-                let level_str = extract_string_select_value(
-                    &interaction.data.components,
-                    "whitelist_modal_level_select",
-                );
-                if let Some(level) = match level_str.as_deref() {
-                    Some("head") => Some(WhitelistLevel::Head),
-                    Some("admin") => Some(WhitelistLevel::Admin),
-                    Some("invulnerable") => Some(WhitelistLevel::Invulnerable),
-                    _ => None,
-                } {
-                    active.level = Set(level);
+    // Helper to extract text input values from modal components
+    fn extract_text_value(
+        components: &[serenity::Component],
+        target_custom_id: &str,
+    ) -> Option<String> {
+        for component in components {
+            if let serenity::Component::Label(label) = component {
+                if let serenity::LabelComponent::InputText(input) = &label.component {
+                    if &*input.custom_id == target_custom_id {
+                        return input.value.as_deref().map(|s| s.trim().to_string());
+                    }
                 }
-
-                active.update(&data.db).await?;
-
-                return Ok(Some(
-                    build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
-                ));
             }
         }
+        None
     }
 
-    // Handle new role modal submission
-    if let Some(suffix) = custom_id.strip_prefix("whitelist_modal_role_new_") {
-        let module = parse_module(suffix);
-        if !check_permission(ctx, data, guild_id, member, module).await? {
-            return Ok(None);
-        }
-
-        // Get selected role from resolved data
-        let selected_role_id = resolved.roles.iter().next().map(|role| role.id);
-
-        // Get level from string select
-        let level_str = extract_string_select_value(
-            &interaction.data.components,
-            "whitelist_modal_level_select",
-        );
-        let selected_level = match level_str.as_deref() {
-            Some("head") => WhitelistLevel::Head,
-            Some("admin") => WhitelistLevel::Admin,
-            _ => WhitelistLevel::Invulnerable,
-        };
-
-        if let Some(role_id) = selected_role_id {
-            // Check if role already exists for this guild/module
-            let existing = whitelist_role::Entity::find()
-                .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
-                .filter(whitelist_role::Column::RoleId.eq(role_id.get() as i64))
-                .filter(match module {
-                    Some(m) => whitelist_role::Column::ModuleType.eq(Some(m)),
-                    None => whitelist_role::Column::ModuleType.is_null(),
-                })
-                .one(&data.db)
-                .await?;
-
-            if let Some(existing) = existing {
-                // Update existing entry's level
-                let mut active: whitelist_role::ActiveModel = existing.into();
-                active.level = Set(selected_level);
-                active.update(&data.db).await?;
-            } else {
-                // Insert new entry
-                let model = whitelist_role::ActiveModel {
-                    guild_id: Set(guild_id.get() as i64),
-                    role_id: Set(role_id.get() as i64),
-                    level: Set(selected_level),
-                    module_type: Set(module),
-                    ..Default::default()
-                };
-                model.insert(&data.db).await?;
+    // Optional expiry; an empty input makes the entry permanent
+    let expires_at = match extract_text_value(
+        &interaction.data.components,
+        "whitelist_modal_duration_input",
+    )
+    .filter(|s| !s.is_empty())
+    {
+        Some(input) => match parse_duration(&input).filter(|d| d.num_seconds() > 0) {
+            Some(duration) => Some((chrono::Utc::now() + duration).fixed_offset()),
+            None => {
+                let mut args = fluent::FluentArgs::new();
+                args.set("input", input);
+                return Ok(WhitelistModalResult::Invalid(
+                    l10n.t("config-whitelist-invalid-duration", Some(&args)),
+                ));
             }
+        },
+        None => None,
+    };
 
-            return Ok(Some(
-                build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
-            ));
-        }
-    }
+    // Optional action scope; nothing selected means every action
+    let actions: Vec<String> = extract_string_select_values(
+        &interaction.data.components,
+        "whitelist_modal_actions_select",
+    )
+    .into_iter()
+    .filter(|a| scoped_actions(None).contains(&a.as_str()))
+    .collect();
 
-    // Handle edit role modal submission
-    if let Some(rest) = custom_id.strip_prefix("whitelist_modal_role_edit_") {
-        let parts: Vec<&str> = rest.splitn(2, '_').collect();
-        if parts.len() == 2 {
-            let id: i32 = parts[0].parse().unwrap_or(0);
-            let module = parse_module(parts[1]);
+    // For user/role selects, use the resolved data directly (not Option)
+    let resolved = &interaction.data.resolved;
 
-            if !check_permission(ctx, data, guild_id, member, module).await? {
-                return Ok(None);
+    // Which entry the modal is for: a user or a role, new or existing
+    let (is_user, entry_id, suffix) =
+        if let Some(suffix) = custom_id.strip_prefix("whitelist_modal_user_new_") {
+            (true, None, suffix)
+        } else if let Some(suffix) = custom_id.strip_prefix("whitelist_modal_role_new_") {
+            (false, None, suffix)
+        } else if let Some(rest) = custom_id.strip_prefix("whitelist_modal_user_edit_") {
+            match rest.split_once('_') {
+                Some((id, suffix)) => (true, Some(id.parse::<i32>().unwrap_or(0)), suffix),
+                None => return Ok(WhitelistModalResult::None),
             }
+        } else if let Some(rest) = custom_id.strip_prefix("whitelist_modal_role_edit_") {
+            match rest.split_once('_') {
+                Some((id, suffix)) => (false, Some(id.parse::<i32>().unwrap_or(0)), suffix),
+                None => return Ok(WhitelistModalResult::None),
+            }
+        } else {
+            return Ok(WhitelistModalResult::None);
+        };
 
-            let existing = whitelist_role::Entity::find_by_id(id)
-                .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
-                .one(&data.db)
-                .await?;
-
-            if existing.is_some() {
-                // Get selected role from resolved data
-                let selected_role = resolved.roles.iter().next();
-
-                // If no role is selected (cleared), delete the entry
-                if selected_role.is_none() {
-                    whitelist_role::Entity::delete_by_id(id)
-                        .exec(&data.db)
-                        .await?;
+    let module = parse_module(suffix);
+    if !check_permission(ctx, data, guild_id, member, module).await? {
+        return Ok(WhitelistModalResult::None);
+    }
 
-                    return Ok(Some(
-                        build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
-                    ));
-                }
+    let current = match entry_id {
+        Some(id) => match find_entry(data, guild_id, is_user, id).await? {
+            Some(item) => Some(item),
+            None => return Ok(WhitelistModalResult::None),
+        },
+        None => None,
+    };
 
-                // Otherwise, update the entry
-                let existing = existing.unwrap();
-                let mut active: whitelist_role::ActiveModel = existing.into();
+    let target = if is_user {
+        resolved
+            .users
+            .iter()
+            .next()
+            .map(|user| WhitelistTarget::User(user.id.get()))
+    } else {
+        resolved
+            .roles
+            .iter()
+            .next()
+            .map(|role| WhitelistTarget::Role(role.id.get()))
+    };
 
-                if let Some(role) = selected_role {
-                    active.role_id = Set(role.id.get() as i64);
-                }
+    let Some(target) = target else {
+        // If no user or role is selected (cleared), delete the entry
+        if let Some(item) = current {
+            delete_entry(ctx, data, guild_id, member.user.id, item).await?;
+            return Ok(WhitelistModalResult::Components(
+                build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
+            ));
+        }
+        return Ok(WhitelistModalResult::None);
+    };
 
-                // Get level from string select
-                let level_str = extract_string_select_value(
-                    &interaction.data.components,
-                    "whitelist_modal_level_select",
-                );
-                if let Some(level) = match level_str.as_deref() {
-                    Some("head") => Some(WhitelistLevel::Head),
-                    Some("admin") => Some(WhitelistLevel::Admin),
-                    Some("invulnerable") => Some(WhitelistLevel::Invulnerable),
-                    _ => None,
-                } {
-                    active.level = Set(level);
-                }
+    // Get level from string select; edits keep the current level if none is given
+    let level_str =
+        extract_string_select_value(&interaction.data.components, "whitelist_modal_level_select");
+    let level = match level_str.as_deref() {
+        Some("head") => WhitelistLevel::Head,
+        Some("admin") => WhitelistLevel::Admin,
+        Some("invulnerable") => WhitelistLevel::Invulnerable,
+        _ => current
+            .as_ref()
+            .map(|item| item.level())
+            .unwrap_or(WhitelistLevel::Invulnerable),
+    };
 
-                active.update(&data.db).await?;
+    let grant = PendingGrant {
+        guild_id,
+        actor_id: member.user.id,
+        entry_id,
+        entry: EntrySnapshot {
+            target,
+            level,
+            module_type: module,
+            actions,
+            expires_at,
+        },
+        created_at: chrono::Utc::now(),
+    };
 
-                return Ok(Some(
-                    build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
-                ));
-            }
-        }
+    if needs_confirmation(data, &grant).await? {
+        let id = data.whitelist.hold_grant(grant.clone());
+        let components = build_grant_confirmation(id, &grant, &l10n);
+        return Ok(WhitelistModalResult::Components(components));
     }
 
-    Ok(None)
+    apply_grant(ctx, data, &grant).await?;
+
+    Ok(WhitelistModalResult::Components(
+        build_whitelist_menu(data, guild_id, module, 0, true, &l10n).await?,
+    ))
 }
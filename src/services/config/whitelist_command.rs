@@ -0,0 +1,720 @@
+use crate::db::entities::{
+    module_configs::ModuleType, whitelist_role, whitelist_user, whitelists::WhitelistLevel,
+};
+use crate::services::config::whitelist::{
+    apply_grant, build_grant_confirmation, check_permission, delete_entry, find_target_entry,
+    needs_confirmation,
+};
+use crate::services::duration::parse_duration;
+use crate::services::localization::{ContextL10nExt, L10nProxy};
+use crate::services::whitelist::{
+    EntrySnapshot, PendingGrant, WhitelistSource, WhitelistTarget, describe_entry,
+    not_expired_role, not_expired_user, scoped_actions, single_action,
+};
+use crate::{Context, Error};
+use fluent::FluentArgs;
+use poise::serenity_prelude as serenity;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+/// Longest list text sent in a single message; the rest is left to the export.
+const MAX_LIST_LENGTH: usize = 3500;
+
+/// A whitelist entry in an export file. Role entries carry the role's name, so an
+/// import into another server can find the matching role.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedEntry {
+    #[serde(flatten)]
+    entry: EntrySnapshot,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role_name: Option<String>,
+}
+
+/// Whitelist management commands
+#[poise::command(
+    slash_command,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "ADMINISTRATOR",
+    subcommands("add", "remove", "list", "check", "export", "import"),
+    ephemeral
+)]
+pub async fn whitelist(_ctx: Context<'_>) -> Result<(), Error> {
+    // This is the parent command, subcommands will handle actual functionality
+    Ok(())
+}
+
+/// Add users and roles to the whitelist
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[description = "Users and roles to whitelist, as mentions or IDs"] targets: String,
+    #[description = "Whitelist level"] level: WhitelistLevel,
+    #[description = "Module the entries apply to (defaults to all modules)"] module: Option<
+        ModuleType,
+    >,
+    #[description = "How long the entries last (e.g. 7d, 12h); permanent if empty"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let l10n = ctx.l10n_guild();
+
+    if !is_head(ctx, module).await? {
+        send_text(ctx, l10n.t("whitelist-cmd-not-allowed", None)).await?;
+        return Ok(());
+    }
+
+    // Every module is offered as a choice, including ones left out of this build
+    if let Some(module) = module.filter(|m| !m.is_available()) {
+        let mut args = FluentArgs::new();
+        args.set("module", module.to_string());
+        send_text(ctx, l10n.t("whitelist-cmd-module-unavailable", Some(&args))).await?;
+        return Ok(());
+    }
+
+    let parsed = parse_targets(ctx, guild_id, &targets).await;
+    if parsed.is_empty() {
+        let mut args = FluentArgs::new();
+        args.set("input", targets);
+        send_text(ctx, l10n.t("whitelist-cmd-no-targets", Some(&args))).await?;
+        return Ok(());
+    }
+
+    let expires_at = match duration.filter(|s| !s.is_empty()) {
+        Some(input) => match parse_duration(&input).filter(|d| d.num_seconds() > 0) {
+            Some(duration) => Some((chrono::Utc::now() + duration).fixed_offset()),
+            None => {
+                let mut args = FluentArgs::new();
+                args.set("input", input);
+                send_text(
+                    ctx,
+                    l10n.t("config-whitelist-invalid-duration", Some(&args)),
+                )
+                .await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let mut lines = vec![];
+    for target in parsed {
+        let grant = PendingGrant {
+            guild_id,
+            actor_id: ctx.author().id,
+            entry_id: None,
+            entry: EntrySnapshot {
+                target,
+                level,
+                module_type: module,
+                actions: vec![],
+                expires_at,
+            },
+            created_at: chrono::Utc::now(),
+        };
+
+        if needs_confirmation(&data, &grant).await? {
+            // Each held grant gets its own confirmation message
+            let id = data.whitelist.hold_grant(grant.clone());
+            let components = build_grant_confirmation(id, &grant, &l10n);
+            ctx.send(poise::CreateReply::default().components(components).flags(
+                serenity::MessageFlags::IS_COMPONENTS_V2 | serenity::MessageFlags::EPHEMERAL,
+            ))
+            .await?;
+            lines.push(format!(
+                "⏸️ {} — {}",
+                target.mention(),
+                l10n.t("whitelist-cmd-held", None)
+            ));
+        } else {
+            apply_grant(ctx.serenity_context(), &data, &grant).await?;
+            lines.push(format!(
+                "✅ {} — {}",
+                target.mention(),
+                describe_entry(&grant.entry, &l10n)
+            ));
+        }
+    }
+
+    send_text(
+        ctx,
+        format!(
+            "{}\n{}",
+            l10n.t("whitelist-cmd-added-title", None),
+            lines.join("\n")
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Remove users and roles from the whitelist
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Users and roles to remove, as mentions or IDs"] targets: String,
+    #[description = "Module of the entries (defaults to the all-modules entries)"] module: Option<
+        ModuleType,
+    >,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let l10n = ctx.l10n_guild();
+
+    if !is_head(ctx, module).await? {
+        send_text(ctx, l10n.t("whitelist-cmd-not-allowed", None)).await?;
+        return Ok(());
+    }
+
+    let parsed = parse_targets(ctx, guild_id, &targets).await;
+    if parsed.is_empty() {
+        let mut args = FluentArgs::new();
+        args.set("input", targets);
+        send_text(ctx, l10n.t("whitelist-cmd-no-targets", Some(&args))).await?;
+        return Ok(());
+    }
+
+    let mut lines = vec![];
+    for target in parsed {
+        match find_target_entry(&data, guild_id, target, module).await? {
+            Some(item) => {
+                delete_entry(
+                    ctx.serenity_context(),
+                    &data,
+                    guild_id,
+                    ctx.author().id,
+                    item,
+                )
+                .await?;
+                lines.push(format!("🗑️ {}", target.mention()));
+            }
+            None => lines.push(format!(
+                "➖ {} — {}",
+                target.mention(),
+                l10n.t("whitelist-cmd-not-found", None)
+            )),
+        }
+    }
+
+    send_text(
+        ctx,
+        format!(
+            "{}\n{}",
+            l10n.t("whitelist-cmd-removed-title", None),
+            lines.join("\n")
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// List the whitelist entries of this server
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn list(
+    ctx: Context<'_>,
+    #[description = "Only show entries of this module"] module: Option<ModuleType>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let l10n = ctx.l10n_guild();
+
+    if !is_head(ctx, module).await? {
+        send_text(ctx, l10n.t("whitelist-cmd-not-allowed", None)).await?;
+        return Ok(());
+    }
+
+    let entries: Vec<EntrySnapshot> = fetch_entries(ctx, guild_id)
+        .await?
+        .into_iter()
+        .filter(|e| module.is_none() || e.module_type == module)
+        .collect();
+
+    if entries.is_empty() {
+        send_text(ctx, l10n.t("whitelist-cmd-list-empty", None)).await?;
+        return Ok(());
+    }
+
+    let mut text = l10n.t("whitelist-cmd-list-title", None);
+    for (i, entry) in entries.iter().enumerate() {
+        let line = format!(
+            "\n{} — {}",
+            entry.target.mention(),
+            describe_entry(entry, &l10n)
+        );
+        if text.len() + line.len() > MAX_LIST_LENGTH {
+            let mut args = FluentArgs::new();
+            args.set("count", entries.len() - i);
+            text.push_str(&format!(
+                "\n-# {}",
+                l10n.t("whitelist-cmd-list-more", Some(&args))
+            ));
+            break;
+        }
+        text.push_str(&line);
+    }
+
+    send_text(ctx, text).await?;
+    Ok(())
+}
+
+/// Show a user's whitelist level in every module and where it comes from
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn check(
+    ctx: Context<'_>,
+    #[description = "User to check"] user: serenity::User,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let l10n = ctx.l10n_guild();
+
+    // The result covers every module
+    if !is_head(ctx, None).await? {
+        send_text(ctx, l10n.t("whitelist-cmd-not-allowed", None)).await?;
+        return Ok(());
+    }
+
+    let grants = ctx
+        .data()
+        .whitelist
+        .load_grants(ctx.serenity_context(), guild_id, user.id)
+        .await?;
+
+    let describe = |resolved: Option<(WhitelistLevel, WhitelistSource)>| match resolved {
+        Some((level, source)) => format!(
+            "**{}** · {}",
+            level_label(level, &l10n),
+            source_label(source, &l10n)
+        ),
+        None => l10n.t("whitelist-check-none", None),
+    };
+
+    let mut args = FluentArgs::new();
+    args.set("user", format!("<@{}>", user.id.get()));
+    let mut text = l10n.t("whitelist-check-title", Some(&args));
+
+    for module in ModuleType::available() {
+        let base = grants.resolve(module, single_action(module));
+        text.push_str(&format!("\n`{}` — {}", module, describe(base)));
+
+        // Entries scoped to single actions only count for those actions
+        for action in scoped_actions(Some(module)) {
+            let scoped = grants.resolve(module, Some(action));
+            if scoped.map(|(level, _)| level) != base.map(|(level, _)| level) {
+                text.push_str(&format!(
+                    "\n-# ↳ {}: {}",
+                    l10n.t(&format!("config-whitelist-action-{}", action), None),
+                    describe(scoped)
+                ));
+            }
+        }
+    }
+
+    send_text(ctx, text).await?;
+    Ok(())
+}
+
+/// Export the whitelist of this server as a JSON file
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn export(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let l10n = ctx.l10n_guild();
+
+    // The export covers every module
+    if !is_head(ctx, None).await? {
+        send_text(ctx, l10n.t("whitelist-cmd-not-allowed", None)).await?;
+        return Ok(());
+    }
+
+    let roles = guild_roles(ctx, guild_id).await;
+    let entries: Vec<ExportedEntry> = fetch_entries(ctx, guild_id)
+        .await?
+        .into_iter()
+        .map(|entry| {
+            let role_name = match entry.target {
+                WhitelistTarget::Role(id) => roles
+                    .iter()
+                    .find(|(role_id, _)| role_id.get() == id)
+                    .map(|(_, name)| name.clone()),
+                WhitelistTarget::User(_) => None,
+            };
+            ExportedEntry { entry, role_name }
+        })
+        .collect();
+    let bytes = serde_json::to_vec_pretty(&entries)?;
+
+    let mut args = FluentArgs::new();
+    args.set("count", entries.len());
+    ctx.send(
+        poise::CreateReply::default()
+            .content(l10n.t("whitelist-cmd-export-done", Some(&args)))
+            .attachment(serenity::CreateAttachment::bytes(
+                bytes,
+                format!("whitelist-{}.json", guild_id.get()),
+            )),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Import whitelist entries from a JSON file made by /whitelist export
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn import(
+    ctx: Context<'_>,
+    #[description = "JSON file made by /whitelist export"] file: serenity::Attachment,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let l10n = ctx.l10n_guild();
+
+    // Importing can create entries for any module, so it needs a global Head
+    if !is_head(ctx, None).await? {
+        send_text(ctx, l10n.t("whitelist-cmd-not-allowed", None)).await?;
+        return Ok(());
+    }
+
+    let entries: Vec<ExportedEntry> = match file.download().await {
+        Ok(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(entries) => entries,
+            Err(_) => {
+                send_text(ctx, l10n.t("whitelist-cmd-import-invalid", None)).await?;
+                return Ok(());
+            }
+        },
+        Err(_) => {
+            send_text(ctx, l10n.t("whitelist-cmd-import-invalid", None)).await?;
+            return Ok(());
+        }
+    };
+
+    // Roles are per server; a role missing here is looked up by its name
+    let roles = guild_roles(ctx, guild_id).await;
+    let now = chrono::Utc::now();
+
+    let (mut applied, mut held) = (0, 0);
+    let mut skipped = vec![];
+    for ExportedEntry {
+        mut entry,
+        role_name,
+    } in entries
+    {
+        let mut args = FluentArgs::new();
+        if let WhitelistTarget::Role(id) = entry.target {
+            match match_role(id, role_name.as_deref(), &roles) {
+                Some(role_id) => entry.target = WhitelistTarget::Role(role_id.get()),
+                None => {
+                    let role = match role_name {
+                        Some(name) => format!("@{}", name),
+                        None => format!("`{}`", id),
+                    };
+                    args.set("role", role);
+                    skipped.push(l10n.t("whitelist-cmd-import-skipped-role", Some(&args)));
+                    continue;
+                }
+            }
+        }
+        args.set("target", entry.target.mention());
+        if let Some(module) = entry.module_type.filter(|m| !m.is_available()) {
+            args.set("module", module.to_string());
+            skipped.push(l10n.t("whitelist-cmd-import-skipped-module", Some(&args)));
+            continue;
+        }
+        if entry.expires_at.is_some_and(|e| e <= now) {
+            skipped.push(l10n.t("whitelist-cmd-import-skipped-expired", Some(&args)));
+            continue;
+        }
+        let allowed = scoped_actions(entry.module_type);
+        entry.actions.retain(|a| allowed.contains(&a.as_str()));
+
+        let grant = PendingGrant {
+            guild_id,
+            actor_id: ctx.author().id,
+            entry_id: None,
+            entry,
+            created_at: now,
+        };
+
+        if needs_confirmation(&data, &grant).await? {
+            let id = data.whitelist.hold_grant(grant.clone());
+            let components = build_grant_confirmation(id, &grant, &l10n);
+            ctx.send(poise::CreateReply::default().components(components).flags(
+                serenity::MessageFlags::IS_COMPONENTS_V2 | serenity::MessageFlags::EPHEMERAL,
+            ))
+            .await?;
+            held += 1;
+        } else {
+            apply_grant(ctx.serenity_context(), &data, &grant).await?;
+            applied += 1;
+        }
+    }
+
+    let mut args = FluentArgs::new();
+    args.set("applied", applied);
+    args.set("held", held);
+    args.set("skipped", skipped.len());
+    let mut text = l10n.t("whitelist-cmd-import-done", Some(&args));
+    for (i, reason) in skipped.iter().enumerate() {
+        let line = format!("\n➖ {}", reason);
+        if text.len() + line.len() > MAX_LIST_LENGTH {
+            let mut args = FluentArgs::new();
+            args.set("count", skipped.len() - i);
+            text.push_str(&format!(
+                "\n-# {}",
+                l10n.t("whitelist-cmd-list-more", Some(&args))
+            ));
+            break;
+        }
+        text.push_str(&line);
+    }
+    send_text(ctx, text).await?;
+    Ok(())
+}
+
+fn level_label(level: WhitelistLevel, l10n: &L10nProxy) -> String {
+    match level {
+        WhitelistLevel::Head => l10n.t("config-level-head", None),
+        WhitelistLevel::Admin => l10n.t("config-level-admin", None),
+        WhitelistLevel::Invulnerable => l10n.t("config-level-invulnerable", None),
+    }
+}
+
+fn source_label(source: WhitelistSource, l10n: &L10nProxy) -> String {
+    let scope = |module: Option<ModuleType>| match module {
+        Some(m) => format!("`{}`", m),
+        None => l10n.t("config-whitelist-global-btn", None),
+    };
+
+    let mut args = FluentArgs::new();
+    match source {
+        WhitelistSource::Owner => l10n.t("whitelist-source-owner", None),
+        WhitelistSource::Hierarchy => l10n.t("whitelist-source-hierarchy", None),
+        WhitelistSource::Administrator => l10n.t("whitelist-source-administrator", None),
+        WhitelistSource::User(module) => {
+            args.set("scope", scope(module));
+            l10n.t("whitelist-source-user", Some(&args))
+        }
+        WhitelistSource::Role(role_id, module) => {
+            args.set("role", format!("<@&{}>", role_id));
+            args.set("scope", scope(module));
+            l10n.t("whitelist-source-role", Some(&args))
+        }
+    }
+}
+
+/// Whether the author is Head for `module`, or globally when it is `None`.
+async fn is_head(ctx: Context<'_>, module: Option<ModuleType>) -> Result<bool, Error> {
+    let Some(member) = ctx.author_member().await else {
+        return Ok(false);
+    };
+    check_permission(
+        ctx.serenity_context(),
+        &ctx.data(),
+        ctx.guild_id().unwrap(),
+        &member,
+        module,
+    )
+    .await
+}
+
+/// Parses user and role mentions or raw IDs. Raw IDs are treated as roles when
+/// the server has a role with that ID, otherwise as users.
+async fn parse_targets(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+    input: &str,
+) -> Vec<WhitelistTarget> {
+    let role_ids: Vec<serenity::RoleId> = guild_roles(ctx, guild_id)
+        .await
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    parse_target_list(input, &role_ids)
+}
+
+/// Parses the targets of `parse_targets`, given the IDs of the server's roles.
+fn parse_target_list(input: &str, role_ids: &[serenity::RoleId]) -> Vec<WhitelistTarget> {
+    let mut targets = vec![];
+
+    for token in input.split(|c: char| c.is_whitespace() || c == ',') {
+        let target = if let Some(id) = token.strip_prefix("<@&").and_then(|t| t.strip_suffix('>')) {
+            id.parse().ok().map(WhitelistTarget::Role)
+        } else if let Some(id) = token.strip_prefix("<@").and_then(|t| t.strip_suffix('>')) {
+            id.trim_start_matches('!')
+                .parse()
+                .ok()
+                .map(WhitelistTarget::User)
+        } else {
+            token.parse::<u64>().ok().filter(|id| *id != 0).map(|id| {
+                if role_ids.contains(&serenity::RoleId::new(id)) {
+                    WhitelistTarget::Role(id)
+                } else {
+                    WhitelistTarget::User(id)
+                }
+            })
+        };
+
+        if let Some(target) = target.filter(|t| t.id() != 0) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+
+    targets
+}
+
+/// Finds the role of an imported entry: the role with the same ID, or else the only
+/// role with the exported name.
+fn match_role(
+    id: u64,
+    name: Option<&str>,
+    roles: &[(serenity::RoleId, String)],
+) -> Option<serenity::RoleId> {
+    if let Some((role_id, _)) = roles.iter().find(|(role_id, _)| role_id.get() == id) {
+        return Some(*role_id);
+    }
+    let name = name?;
+    let mut named = roles.iter().filter(|(_, role_name)| role_name == name);
+    match (named.next(), named.next()) {
+        (Some((role_id, _)), None) => Some(*role_id),
+        _ => None,
+    }
+}
+
+/// IDs and names of the server's roles.
+async fn guild_roles(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+) -> Vec<(serenity::RoleId, String)> {
+    let cached = ctx
+        .cache()
+        .guild(guild_id)
+        .map(|g| g.roles.iter().map(|r| (r.id, r.name.to_string())).collect());
+    match cached {
+        Some(roles) => roles,
+        None => guild_id
+            .roles(ctx.http())
+            .await
+            .map(|roles| roles.iter().map(|r| (r.id, r.name.to_string())).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// All active whitelist entries of a guild, users first.
+async fn fetch_entries(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+) -> Result<Vec<EntrySnapshot>, Error> {
+    let data = ctx.data();
+    let db = &data.db;
+
+    let users = whitelist_user::Entity::find()
+        .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
+        .filter(not_expired_user())
+        .all(db)
+        .await?;
+    let roles = whitelist_role::Entity::find()
+        .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
+        .filter(not_expired_role())
+        .all(db)
+        .await?;
+
+    Ok(users
+        .iter()
+        .map(EntrySnapshot::from)
+        .chain(roles.iter().map(EntrySnapshot::from))
+        .collect())
+}
+
+async fn send_text(ctx: Context<'_>, text: String) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+            .components(vec![serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(vec![
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(text),
+                    ),
+                ]),
+            )])
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2 | serenity::MessageFlags::EPHEMERAL)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target_list() {
+        let roles = [serenity::RoleId::new(30)];
+        assert_eq!(
+            parse_target_list("<@10> <@!11>, <@&20> 30 40", &roles),
+            vec![
+                WhitelistTarget::User(10),
+                WhitelistTarget::User(11),
+                WhitelistTarget::Role(20),
+                WhitelistTarget::Role(30),
+                WhitelistTarget::User(40),
+            ]
+        );
+        assert_eq!(
+            parse_target_list("<@10> 10 <@!10>", &roles),
+            vec![WhitelistTarget::User(10)]
+        );
+        assert_eq!(parse_target_list("0 <@0> abc <@&x>", &roles), vec![]);
+    }
+
+    #[test]
+    fn test_match_role() {
+        let roles = [
+            (serenity::RoleId::new(1), "Mods".to_string()),
+            (serenity::RoleId::new(2), "Helpers".to_string()),
+            (serenity::RoleId::new(3), "Helpers".to_string()),
+        ];
+        // Same server: the ID wins over the name
+        assert_eq!(
+            match_role(1, Some("Helpers"), &roles),
+            Some(serenity::RoleId::new(1))
+        );
+        // Another server: matched by name
+        assert_eq!(
+            match_role(10, Some("Mods"), &roles),
+            Some(serenity::RoleId::new(1))
+        );
+        // Ambiguous, unknown or unnamed roles are skipped
+        assert_eq!(match_role(10, Some("Helpers"), &roles), None);
+        assert_eq!(match_role(10, Some("Admins"), &roles), None);
+        assert_eq!(match_role(10, None, &roles), None);
+    }
+
+    #[test]
+    fn test_exported_entry_reads_plain_snapshots() {
+        let entry = EntrySnapshot {
+            target: WhitelistTarget::Role(10),
+            level: WhitelistLevel::Admin,
+            module_type: None,
+            actions: vec![],
+            expires_at: None,
+        };
+        let exported = serde_json::to_value(ExportedEntry {
+            entry: entry.clone(),
+            role_name: Some("Mods".to_string()),
+        })
+        .unwrap();
+        assert_eq!(exported["role_name"], "Mods");
+
+        // Exports made before role names were added still import
+        let plain: ExportedEntry =
+            serde_json::from_value(serde_json::to_value(&entry).unwrap()).unwrap();
+        assert_eq!(plain.entry, entry);
+        assert_eq!(plain.role_name, None);
+    }
+}
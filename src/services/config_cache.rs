@@ -0,0 +1,335 @@
+use crate::Error;
+use crate::db::entities::{guild_configs, module_configs, module_configs::ModuleType};
+use poise::serenity_prelude as serenity;
+use sea_orm::{DatabaseConnection, EntityTrait, Iterable};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep};
+use tracing::{error, info, warn};
+
+/// Postgres channel the config tables notify on; the payload is the guild id.
+/// The triggers are created by the `m000022_add_config_notify_triggers` migration.
+pub const CONFIG_NOTIFY_CHANNEL: &str = "config_changed";
+
+/// A module config that was written, with the row as it was before.
+/// `old` is None if the guild had no config row for the module.
+#[derive(Debug)]
+pub struct ConfigChange {
+    pub guild_id: serenity::GuildId,
+    pub module: ModuleType,
+    pub old: Option<module_configs::Model>,
+}
+
+/// In-memory copy of `guild_configs` and `module_configs`, filled on first use.
+/// Misses are cached too, so unconfigured guilds do not hit the database either.
+///
+/// Module config writes are also queued as [`ConfigChange`]s, for the modules'
+/// `on_config_changed` hooks.
+pub struct ConfigCacheService {
+    db: DatabaseConnection,
+    /// Map: GuildID -> guild config, or None if the guild has none
+    guilds: papaya::HashMap<u64, Option<Arc<guild_configs::Model>>>,
+    /// Map: (GuildID, ModuleType) -> module config, or None if the module is not configured
+    modules: papaya::HashMap<(u64, ModuleType), Option<Arc<module_configs::Model>>>,
+    /// Bumped on every invalidation, so a load that raced with one is not cached
+    generation: AtomicU64,
+    changes_tx: mpsc::UnboundedSender<ConfigChange>,
+    /// Taken by the task that runs the hooks
+    changes_rx: Mutex<Option<mpsc::UnboundedReceiver<ConfigChange>>>,
+}
+
+impl ConfigCacheService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        let (changes_tx, changes_rx) = mpsc::unbounded_channel();
+        Self {
+            db,
+            guilds: papaya::HashMap::new(),
+            modules: papaya::HashMap::new(),
+            generation: AtomicU64::new(0),
+            changes_tx,
+            changes_rx: Mutex::new(Some(changes_rx)),
+        }
+    }
+
+    /// Returns the guild's config row.
+    pub async fn guild_config(
+        &self,
+        guild_id: serenity::GuildId,
+    ) -> Result<Option<guild_configs::Model>, Error> {
+        if let Some(cached) = self.guilds.pin().get(&guild_id.get()) {
+            return Ok(cached.as_deref().cloned());
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let model = guild_configs::Entity::find_by_id(guild_id.get() as i64)
+            .one(&self.db)
+            .await?;
+        if self.generation.load(Ordering::Acquire) == generation {
+            self.guilds
+                .pin()
+                .insert(guild_id.get(), model.clone().map(Arc::new));
+        }
+        Ok(model)
+    }
+
+    /// Returns the config row of one module in the guild.
+    /// Modules left out of the build count as not configured.
+    pub async fn module_config(
+        &self,
+        guild_id: serenity::GuildId,
+        module: ModuleType,
+    ) -> Result<Option<module_configs::Model>, Error> {
+        if !module.is_available() {
+            return Ok(None);
+        }
+
+        let key = (guild_id.get(), module);
+        if let Some(cached) = self.modules.pin().get(&key) {
+            return Ok(cached.as_deref().cloned());
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let model = module_configs::Entity::find_by_id((guild_id.get() as i64, module))
+            .one(&self.db)
+            .await?;
+        if self.generation.load(Ordering::Acquire) == generation {
+            self.modules.pin().insert(key, model.clone().map(Arc::new));
+        }
+        Ok(model)
+    }
+
+    /// Drops everything cached for a guild. Call after writing any of its configs.
+    pub fn invalidate_guild(&self, guild_id: serenity::GuildId) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.guilds.pin().remove(&guild_id.get());
+        let modules = self.modules.pin();
+        for module in ModuleType::iter() {
+            modules.remove(&(guild_id.get(), module));
+        }
+    }
+
+    /// Drops everything cached for a guild after one of its module configs was written,
+    /// and queues the change. `old` is the row as it was before the write.
+    pub fn invalidate_module(
+        &self,
+        guild_id: serenity::GuildId,
+        module: ModuleType,
+        old: Option<module_configs::Model>,
+    ) {
+        self.invalidate_guild(guild_id);
+        let _ = self.changes_tx.send(ConfigChange {
+            guild_id,
+            module,
+            old,
+        });
+    }
+
+    /// Returns the queue of module config changes. Only the first caller gets it.
+    pub fn take_changes(&self) -> Option<mpsc::UnboundedReceiver<ConfigChange>> {
+        self.changes_rx.lock().unwrap().take()
+    }
+
+    /// Invalidates a guild whose configs were written elsewhere. Modules whose config
+    /// was cached are queued as changed; the hook runner skips rows that are unchanged.
+    /// Uncached modules have no known old row, so their change is not queued.
+    fn invalidate_notified(&self, guild_id: serenity::GuildId) {
+        let cached: Vec<_> = {
+            let modules = self.modules.pin();
+            ModuleType::iter()
+                .filter_map(|module| {
+                    let old = modules.get(&(guild_id.get(), module))?;
+                    Some((module, old.as_deref().cloned()))
+                })
+                .collect()
+        };
+
+        self.invalidate_guild(guild_id);
+        for (module, old) in cached {
+            let _ = self.changes_tx.send(ConfigChange {
+                guild_id,
+                module,
+                old,
+            });
+        }
+    }
+
+    /// Drops everything cached, for when notifications may have been missed.
+    pub fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.guilds.pin().clear();
+        self.modules.pin().clear();
+    }
+
+    /// Starts listening for config changes made by other processes or by hand.
+    pub fn start_listener(self: &Arc<Self>) {
+        let notified = Arc::clone(self);
+        let lost = Arc::clone(self);
+        spawn_guild_listener(
+            &self.db,
+            CONFIG_NOTIFY_CHANNEL,
+            move |guild_id| notified.invalidate_notified(guild_id),
+            move || lost.clear(),
+        );
+    }
+}
+
+/// Listens on a Postgres notification channel whose payload is a guild id, reconnecting
+/// when the connection drops. `on_guild` runs for every notification and `on_lost`
+/// whenever notifications may have been missed.
+pub fn spawn_guild_listener(
+    db: &DatabaseConnection,
+    channel: &'static str,
+    on_guild: impl Fn(serenity::GuildId) + Send + 'static,
+    on_lost: impl Fn() + Send + 'static,
+) {
+    let pool = db.get_postgres_connection_pool().clone();
+    tokio::spawn(async move {
+        loop {
+            let mut listener = match sea_orm::sqlx::postgres::PgListener::connect_with(&pool).await
+            {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to connect {} listener: {:?}", channel, e);
+                    sleep(Duration::from_secs(10)).await;
+                    continue;
+                }
+            };
+            if let Err(e) = listener.listen(channel).await {
+                error!("Failed to listen on {}: {:?}", channel, e);
+                sleep(Duration::from_secs(10)).await;
+                continue;
+            }
+            info!("Listening on {}.", channel);
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => match notification.payload().parse::<u64>() {
+                        Ok(guild_id) if guild_id != 0 => on_guild(serenity::GuildId::new(guild_id)),
+                        _ => warn!("Unexpected {} payload: {}", channel, notification.payload()),
+                    },
+                    Ok(None) => {
+                        // The connection dropped; changes in the meantime were not seen
+                        warn!("{} listener connection lost", channel);
+                        on_lost();
+                    }
+                    Err(e) => {
+                        error!("{} listener failed: {:?}", channel, e);
+                        on_lost();
+                        break;
+                    }
+                }
+            }
+
+            sleep(Duration::from_secs(10)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::entities::module_configs::PunishmentType;
+
+    const GUILD: serenity::GuildId = serenity::GuildId::new(1);
+
+    fn module_row(module: ModuleType) -> module_configs::Model {
+        module_configs::Model {
+            guild_id: GUILD.get() as i64,
+            module_type: module,
+            log_channel_id: None,
+            punishment: PunishmentType::None,
+            punishment_at: 0,
+            punishment_at_interval: 0,
+            enabled: true,
+            revert: false,
+            config: serde_json::json!({}),
+        }
+    }
+
+    /// A cache holding one module's row, whose database is unreachable so every miss fails.
+    fn cached(module: ModuleType, row: Option<module_configs::Model>) -> ConfigCacheService {
+        let cache = ConfigCacheService::new(DatabaseConnection::Disconnected);
+        cache
+            .modules
+            .pin()
+            .insert((GUILD.get(), module), row.map(Arc::new));
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_module_config_hit() {
+        let module = ModuleType::available().next().unwrap();
+        let cache = cached(module, Some(module_row(module)));
+        assert_eq!(
+            cache.module_config(GUILD, module).await.unwrap(),
+            Some(module_row(module))
+        );
+
+        // Misses are cached too
+        let cache = cached(module, None);
+        assert_eq!(cache.module_config(GUILD, module).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_module() {
+        let module = ModuleType::available().next().unwrap();
+        let cache = cached(module, Some(module_row(module)));
+        cache.guilds.pin().insert(GUILD.get(), None);
+        let mut changes = cache.take_changes().unwrap();
+
+        cache.invalidate_module(GUILD, module, Some(module_row(module)));
+        // Both reads go to the database again
+        assert!(cache.guild_config(GUILD).await.is_err());
+        assert!(cache.module_config(GUILD, module).await.is_err());
+
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.guild_id, GUILD);
+        assert_eq!(change.module, module);
+        assert_eq!(change.old, Some(module_row(module)));
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_notified_queues_cached_modules() {
+        let mut modules = ModuleType::available();
+        let (configured, unconfigured) = (modules.next().unwrap(), modules.next().unwrap());
+        let cache = cached(configured, Some(module_row(configured)));
+        cache
+            .modules
+            .pin()
+            .insert((GUILD.get(), unconfigured), None);
+        let mut changes = cache.take_changes().unwrap();
+
+        cache.invalidate_notified(GUILD);
+        assert!(cache.module_config(GUILD, configured).await.is_err());
+        assert!(cache.module_config(GUILD, unconfigured).await.is_err());
+
+        let mut queued = vec![changes.try_recv().unwrap(), changes.try_recv().unwrap()];
+        queued.sort_by_key(|change| change.old.is_none());
+        assert_eq!(queued[0].module, configured);
+        assert_eq!(queued[0].old, Some(module_row(configured)));
+        assert_eq!(queued[1].module, unconfigured);
+        assert_eq!(queued[1].old, None);
+        // Uncached modules have no old row to compare against
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clear() {
+        let module = ModuleType::available().next().unwrap();
+        let cache = cached(module, Some(module_row(module)));
+        let mut changes = cache.take_changes().unwrap();
+
+        cache.clear();
+        assert!(cache.module_config(GUILD, module).await.is_err());
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_take_changes_once() {
+        let cache = ConfigCacheService::new(DatabaseConnection::Disconnected);
+        assert!(cache.take_changes().is_some());
+        assert!(cache.take_changes().is_none());
+    }
+}
@@ -0,0 +1,380 @@
+use super::Handler;
+use crate::db::entities::audit_log_cursors;
+use crate::db::entities::module_configs::ModuleType;
+use crate::modules::EventKind;
+use crate::{Data, Error};
+use chrono::Utc;
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use std::collections::{BTreeSet, HashMap};
+use tokio::time::{Duration, sleep};
+use tracing::{info, warn};
+
+/// How often the audit log of each guild is checked for entries the gateway missed.
+const POLL_INTERVAL: Duration = Duration::from_secs(300);
+/// Wait after a shard connects, so its guilds are in the cache before the first check.
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+/// Entries fetched per request, the API maximum.
+const PAGE_SIZE: u8 = 100;
+/// Pages fetched per guild and check; anything older is given up on.
+const MAX_PAGES: usize = 5;
+/// Missed entries older than this are not replayed, since reverting them now could undo
+/// changes made after them.
+const MAX_REPLAY_AGE_SECS: i64 = 3600;
+
+#[derive(Default)]
+struct GuildCursor {
+    /// Entries up to this id count as handled. None until the guild's first check.
+    floor: Option<u64>,
+    /// Handled entries above the floor, which may have skipped some ids. Only a check of
+    /// the audit log can tell which ids were skipped, so only `advance` raises the floor.
+    recent: BTreeSet<u64>,
+    /// Value last written to the database
+    persisted: Option<u64>,
+    /// Whether the stored cursor was read
+    loaded: bool,
+}
+
+impl GuildCursor {
+    fn is_handled(&self, id: u64) -> bool {
+        self.floor.is_some_and(|floor| id <= floor) || self.recent.contains(&id)
+    }
+
+    fn mark(&mut self, id: u64) -> bool {
+        if self.is_handled(id) {
+            return false;
+        }
+        self.recent.insert(id);
+        true
+    }
+
+    fn advance(&mut self, id: u64) {
+        self.floor = Some(id).max(self.floor);
+        self.recent.retain(|recent| *recent > id);
+    }
+
+    /// The floor, if it moved since the last write. Ids above it are not persisted, as
+    /// entries skipped below them could otherwise be lost across a restart.
+    fn unpersisted_floor(&self) -> Option<u64> {
+        self.floor.filter(|floor| Some(*floor) != self.persisted)
+    }
+
+    /// Takes in the stored cursor, keeping anything handled before it was read.
+    fn merge_stored(&mut self, stored: Option<u64>) {
+        if self.loaded {
+            return;
+        }
+        self.floor = self.floor.max(stored);
+        self.recent.retain(|recent| Some(*recent) > self.floor);
+        self.persisted = stored;
+        self.loaded = true;
+    }
+}
+
+/// Remembers which audit log entries were handled in each guild, so entries the gateway
+/// did not deliver can be fetched and replayed without handling any entry twice.
+pub struct AuditLogCursors {
+    db: DatabaseConnection,
+    guilds: DashMap<serenity::GuildId, GuildCursor>,
+}
+
+impl AuditLogCursors {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            guilds: DashMap::new(),
+        }
+    }
+
+    /// Reads the stored cursors of the guilds not loaded yet, in one query.
+    /// Called when a shard is ready and when a guild becomes available, so handling
+    /// an entry never waits for the database.
+    pub async fn preload(&self, guild_ids: &[serenity::GuildId]) -> Result<(), Error> {
+        let missing: Vec<i64> = guild_ids
+            .iter()
+            .filter(|id| !self.guilds.get(id).is_some_and(|c| c.loaded))
+            .map(|id| id.get() as i64)
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let stored: HashMap<i64, u64> = audit_log_cursors::Entity::find()
+            .filter(audit_log_cursors::Column::GuildId.is_in(missing.clone()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|m| (m.guild_id, m.last_entry_id as u64))
+            .collect();
+
+        for id in missing {
+            self.guilds
+                .entry(serenity::GuildId::new(id as u64))
+                .or_default()
+                .merge_stored(stored.get(&id).copied());
+        }
+        Ok(())
+    }
+
+    /// Records an entry as handled. Returns false if it already was.
+    pub fn mark_handled(
+        &self,
+        guild_id: serenity::GuildId,
+        entry_id: serenity::AuditLogEntryId,
+    ) -> bool {
+        self.guilds
+            .entry(guild_id)
+            .or_default()
+            .mark(entry_id.get())
+    }
+
+    /// Writes the guild's cursor if its floor moved since the last write.
+    async fn persist(&self, guild_id: serenity::GuildId) -> Result<(), Error> {
+        let floor = self
+            .guilds
+            .get(&guild_id)
+            .and_then(|cursor| cursor.unpersisted_floor());
+        let Some(last) = floor else {
+            return Ok(());
+        };
+
+        audit_log_cursors::Entity::insert(audit_log_cursors::ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            last_entry_id: Set(last as i64),
+            updated_at: Set(Utc::now().into()),
+        })
+        .on_conflict(
+            sea_orm::sea_query::OnConflict::column(audit_log_cursors::Column::GuildId)
+                .update_columns([
+                    audit_log_cursors::Column::LastEntryId,
+                    audit_log_cursors::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(&self.db)
+        .await?;
+
+        if let Some(mut cursor) = self.guilds.get_mut(&guild_id) {
+            cursor.persisted = Some(last);
+        }
+        Ok(())
+    }
+
+    /// Forgets a guild the bot left.
+    pub async fn remove(&self, guild_id: serenity::GuildId) -> Result<(), Error> {
+        self.guilds.remove(&guild_id);
+        audit_log_cursors::Entity::delete_by_id(guild_id.get() as i64)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Checks the audit logs of the shard's guilds until the shard reconnects, when a new
+/// poller replaces this one. The first check right after connecting recovers entries
+/// created while the bot was offline.
+pub(super) async fn poll_shard(handler: Handler, ctx: serenity::Context) {
+    let data = ctx.data::<Data>();
+    sleep(STARTUP_DELAY).await;
+
+    loop {
+        let shard_count = data
+            .shard_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+            .max(1);
+        let guild_ids: Vec<_> = ctx
+            .cache
+            .guilds()
+            .into_iter()
+            .filter(|id| (id.get() >> 22) % shard_count as u64 == ctx.shard_id.get() as u64)
+            .collect();
+
+        for guild_id in guild_ids {
+            if let Err(e) = recover_guild(&handler, &ctx, &data, guild_id).await {
+                warn!(
+                    "Failed to check audit log of guild {} for missed entries: {:?}",
+                    guild_id, e
+                );
+            }
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Whether any enabled module of the guild handles audit log entries.
+async fn uses_audit_log(guild_id: serenity::GuildId, data: &Data) -> Result<bool, Error> {
+    for module in &data.modules {
+        if !module
+            .events()
+            .contains(&EventKind::GuildAuditLogEntryCreate)
+        {
+            continue;
+        }
+        let id = module.definition().id;
+        let Some(module_type) = ModuleType::available().find(|m| m.to_string() == id) else {
+            continue;
+        };
+        let config = data
+            .config_cache
+            .module_config(guild_id, module_type)
+            .await?;
+        if config.is_some_and(|c| c.enabled) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Fetches the newest audit log entries of a guild and replays the ones that were not
+/// handled yet, oldest first.
+async fn recover_guild(
+    handler: &Handler,
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+) -> Result<(), Error> {
+    if !uses_audit_log(guild_id, data).await? {
+        return Ok(());
+    }
+
+    let cursors = &handler.audit_cursors;
+    cursors.preload(&[guild_id]).await?;
+    let floor = cursors.guilds.get(&guild_id).and_then(|c| c.floor);
+    let cutoff = Utc::now().timestamp() - MAX_REPLAY_AGE_SECS;
+    let page_size = serenity::nonmax::NonMaxU8::new(PAGE_SIZE).unwrap();
+
+    let mut newest = None;
+    let mut missed = vec![];
+    let mut before = None;
+    'pages: for _ in 0..MAX_PAGES {
+        let page = guild_id
+            .audit_logs(&ctx.http, None, None, before, Some(page_size))
+            .await?;
+
+        // Entries come newest first
+        for entry in page.entries.iter() {
+            newest = newest.max(Some(entry.id.get()));
+            before = Some(entry.id);
+
+            // Without a cursor yet, only the position is recorded; history is not replayed
+            let Some(floor) = floor else {
+                break 'pages;
+            };
+            if entry.id.get() <= floor || entry.id.created_at().unix_timestamp() < cutoff {
+                break 'pages;
+            }
+            let handled = cursors
+                .guilds
+                .get(&guild_id)
+                .is_some_and(|c| c.is_handled(entry.id.get()));
+            if !handled {
+                missed.push(entry.clone());
+            }
+        }
+
+        if page.entries.len() < PAGE_SIZE as usize {
+            break;
+        }
+    }
+
+    if !missed.is_empty() {
+        info!(
+            "Replaying {} missed audit log entries in guild {}",
+            missed.len(),
+            guild_id
+        );
+    }
+    for entry in missed.into_iter().rev() {
+        // The gateway may have delivered it in the meantime
+        if !cursors.mark_handled(guild_id, entry.id) {
+            continue;
+        }
+        let event = serenity::FullEvent::GuildAuditLogEntryCreate { entry, guild_id };
+        handler.dispatch_modules(ctx, &event);
+    }
+
+    // Everything up to the newest entry was handled or given up on
+    let newest = newest.or(floor).unwrap_or(0);
+    cursors.guilds.entry(guild_id).or_default().advance(newest);
+    cursors.persist(guild_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark() {
+        let mut cursor = GuildCursor::default();
+        assert!(cursor.mark(5));
+        assert!(!cursor.mark(5));
+        assert!(cursor.mark(3));
+
+        let mut cursor = GuildCursor {
+            floor: Some(10),
+            ..Default::default()
+        };
+        assert!(!cursor.mark(7));
+        assert!(cursor.mark(12));
+    }
+
+    #[test]
+    fn test_mark_keeps_skipped_ids() {
+        let mut cursor = GuildCursor {
+            floor: Some(0),
+            ..Default::default()
+        };
+        for id in (2..2000).step_by(2) {
+            assert!(cursor.mark(id));
+        }
+        assert_eq!(cursor.floor, Some(0));
+        assert!(!cursor.is_handled(1));
+        assert!(!cursor.is_handled(999));
+    }
+
+    #[test]
+    fn test_advance() {
+        let mut cursor = GuildCursor::default();
+        cursor.mark(3);
+        cursor.mark(8);
+        cursor.advance(5);
+        assert_eq!(cursor.floor, Some(5));
+        assert_eq!(cursor.recent, BTreeSet::from([8]));
+        assert!(cursor.is_handled(4));
+
+        // The floor never moves back
+        cursor.advance(2);
+        assert_eq!(cursor.floor, Some(5));
+    }
+
+    #[test]
+    fn test_unpersisted_floor() {
+        let mut cursor = GuildCursor::default();
+        assert_eq!(cursor.unpersisted_floor(), None);
+        cursor.advance(5);
+        assert_eq!(cursor.unpersisted_floor(), Some(5));
+
+        // Ids above a gap are not written
+        cursor.mark(9);
+        assert_eq!(cursor.unpersisted_floor(), Some(5));
+        cursor.persisted = Some(5);
+        assert_eq!(cursor.unpersisted_floor(), None);
+    }
+
+    #[test]
+    fn test_merge_stored() {
+        let mut cursor = GuildCursor::default();
+        cursor.mark(3);
+        cursor.mark(9);
+        cursor.merge_stored(Some(5));
+        assert_eq!(cursor.floor, Some(5));
+        assert_eq!(cursor.recent, BTreeSet::from([9]));
+        assert_eq!(cursor.unpersisted_floor(), None);
+
+        // Only the first read counts
+        cursor.merge_stored(Some(20));
+        assert_eq!(cursor.floor, Some(5));
+    }
+}
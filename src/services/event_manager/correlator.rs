@@ -0,0 +1,477 @@
+use super::Handler;
+use crate::db::entities::module_configs::ModuleType;
+use crate::services::logger::LogLevel;
+use crate::{Data, Error};
+use chrono::Utc;
+use dashmap::{DashMap, DashSet};
+use poise::serenity_prelude as serenity;
+use serenity::model::guild::audit_log::{Action, ChannelAction, MemberAction, RoleAction};
+use tokio::time::{Duration, sleep};
+use tracing::{info, warn};
+
+/// How long a gateway event waits for its audit log entry before the audit log is fetched.
+const WINDOW: Duration = Duration::from_secs(10);
+/// Entries fetched per request, the API maximum.
+const PAGE_SIZE: u8 = 100;
+/// Pages fetched per guild when looking for missing entries.
+const MAX_PAGES: usize = 5;
+/// Audit log fetches an action waits through before it is reported without one.
+const MAX_FETCH_ATTEMPTS: u8 = 3;
+
+/// Destructive actions that are announced by both a gateway event and an audit log entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKind {
+    ChannelDelete,
+    RoleDelete,
+    BanAdd,
+}
+
+impl ActionKind {
+    /// Returns the kind of an audit log action, or None if it is not correlated.
+    fn of_action(action: &Action) -> Option<Self> {
+        Some(match action {
+            Action::Channel(ChannelAction::Delete) => Self::ChannelDelete,
+            Action::Role(RoleAction::Delete) => Self::RoleDelete,
+            Action::Member(MemberAction::BanAdd) => Self::BanAdd,
+            _ => return None,
+        })
+    }
+
+    /// The module that handles the audit log entry of this action.
+    fn module(self) -> ModuleType {
+        match self {
+            Self::ChannelDelete => ModuleType::ChannelProtection,
+            Self::RoleDelete => ModuleType::RoleProtection,
+            Self::BanAdd => ModuleType::ModerationProtection,
+        }
+    }
+
+    fn type_key(self) -> &'static str {
+        match self {
+            Self::ChannelDelete => "log-unattributed-type-channel-delete",
+            Self::RoleDelete => "log-unattributed-type-role-delete",
+            Self::BanAdd => "log-unattributed-type-ban-add",
+        }
+    }
+}
+
+type ActionKey = (serenity::GuildId, ActionKind, u64);
+
+/// A gateway event still waiting for its audit log entry.
+struct PendingAction {
+    /// Unix time the gateway event arrived
+    seen_at: i64,
+    /// Name of the deleted channel or role, or the banned user's tag
+    name: String,
+    /// Failed audit log fetches so far
+    attempts: u8,
+}
+
+/// Pairs destructive gateway events with their audit log entries, so actions the audit
+/// log never explains can be attributed by fetching it, or reported when that fails.
+#[derive(Default)]
+pub struct ActionCorrelator {
+    /// Map: (GuildID, kind, target ID) -> gateway event waiting for its entry
+    pending: DashMap<ActionKey, PendingAction>,
+    /// Map: (GuildID, kind, target ID) -> unix time of an entry that came before its event
+    attributed: DashMap<ActionKey, i64>,
+    /// Guilds with a resolver waiting to look up their pending actions
+    scheduled: DashSet<serenity::GuildId>,
+}
+
+impl ActionCorrelator {
+    /// Records a gateway event. Returns true if it has to wait for its audit log entry.
+    async fn record_event(&self, data: &Data, key: ActionKey, name: String) -> Result<bool, Error> {
+        let (guild_id, kind, _) = key;
+        if self.attributed.remove(&key).is_some() {
+            return Ok(false);
+        }
+
+        // Only guilds protecting against this action care who did it
+        let config = data
+            .config_cache
+            .module_config(guild_id, kind.module())
+            .await?;
+        if !config.is_some_and(|c| c.enabled) {
+            return Ok(false);
+        }
+
+        let seen_at = Utc::now().timestamp();
+        self.pending.insert(
+            key,
+            PendingAction {
+                seen_at,
+                name,
+                attempts: 0,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Takes the guild's pending actions that waited a full window for their entry.
+    fn take_due(&self, guild_id: serenity::GuildId) -> Vec<(ActionKey, PendingAction)> {
+        let due_before = Utc::now().timestamp() - WINDOW.as_secs() as i64;
+        let keys: Vec<ActionKey> = self
+            .pending
+            .iter()
+            .filter(|p| p.key().0 == guild_id && p.seen_at <= due_before)
+            .map(|p| *p.key())
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| self.pending.remove(&key))
+            .collect()
+    }
+
+    /// Puts actions back to wait for another fetch of the audit log. Returns the actions
+    /// that already used up their attempts.
+    fn requeue(&self, due: Vec<(ActionKey, PendingAction)>) -> Vec<(ActionKey, PendingAction)> {
+        let mut exhausted = vec![];
+        for (key, mut pending) in due {
+            pending.attempts += 1;
+            if pending.attempts >= MAX_FETCH_ATTEMPTS {
+                exhausted.push((key, pending));
+            } else {
+                self.pending.insert(key, pending);
+            }
+        }
+        exhausted
+    }
+
+    fn has_pending(&self, guild_id: serenity::GuildId) -> bool {
+        self.pending.iter().any(|p| p.key().0 == guild_id)
+    }
+
+    /// Records an audit log entry, settling the gateway event it belongs to.
+    pub fn record_entry(&self, guild_id: serenity::GuildId, entry: &serenity::AuditLogEntry) {
+        let Some(kind) = ActionKind::of_action(&entry.action) else {
+            return;
+        };
+        let Some(target_id) = entry.target_id else {
+            return;
+        };
+
+        let key = (guild_id, kind, target_id.get());
+        if self.pending.remove(&key).is_none() {
+            // The entry came first; its gateway event is still on the way
+            let now = Utc::now().timestamp();
+            let window = WINDOW.as_secs() as i64;
+            self.attributed.retain(|_, at| now - *at < window * 2);
+            self.attributed.insert(key, now);
+        }
+    }
+}
+
+/// Starts correlating a destructive gateway event with its audit log entry.
+pub(super) async fn track(
+    handler: &Handler,
+    ctx: &serenity::Context,
+    key: ActionKey,
+    name: String,
+) {
+    let data = ctx.data::<Data>();
+    match handler.correlator.record_event(&data, key, name).await {
+        Ok(true) => schedule(handler, ctx, key.0),
+        Ok(false) => {}
+        Err(e) => warn!("Failed to track {:?} in guild {}: {:?}", key.1, key.0, e),
+    }
+}
+
+/// Starts a resolver for the guild, unless one is already waiting.
+fn schedule(handler: &Handler, ctx: &serenity::Context, guild_id: serenity::GuildId) {
+    if handler.correlator.scheduled.insert(guild_id) {
+        tokio::spawn(resolve(handler.clone(), ctx.clone(), guild_id));
+    }
+}
+
+/// Waits for the audit log entries of a guild's gateway events. Entries the gateway never
+/// delivers are looked up in one fetch of the audit log and replayed; actions the audit
+/// log does not have either are reported as unattributed.
+async fn resolve(handler: Handler, ctx: serenity::Context, guild_id: serenity::GuildId) {
+    sleep(WINDOW).await;
+    handler.correlator.scheduled.remove(&guild_id);
+
+    let due = handler.correlator.take_due(guild_id);
+    // Actions that arrived during the wait get their own window
+    if handler.correlator.has_pending(guild_id) {
+        schedule(&handler, &ctx, guild_id);
+    }
+    if due.is_empty() {
+        return;
+    }
+
+    let data = ctx.data::<Data>();
+    let since =
+        due.iter().map(|(_, p)| p.seen_at).min().unwrap_or_default() - WINDOW.as_secs() as i64;
+    let mut entries = match fetch_entries(&ctx, guild_id, since).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!(
+                "Failed to fetch audit log of guild {} for {} unattributed action(s): {:?}",
+                guild_id,
+                due.len(),
+                e
+            );
+            // Retry on the next window; actions out of attempts are reported unattributed
+            let exhausted = handler.correlator.requeue(due);
+            if handler.correlator.has_pending(guild_id) {
+                schedule(&handler, &ctx, guild_id);
+            }
+            for (key, pending) in exhausted {
+                if let Err(e) = report(&data, key, &pending).await {
+                    warn!(
+                        "Failed to report {:?} of {} in guild {}: {:?}",
+                        key.1, key.2, guild_id, e
+                    );
+                }
+            }
+            return;
+        }
+    };
+
+    for (key, pending) in due {
+        let (_, kind, target_id) = key;
+        let result = match take_entry(&mut entries, key, pending.seen_at) {
+            Some(entry) => replay(&handler, &ctx, guild_id, entry).await,
+            None => report(&data, key, &pending).await,
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to correlate {:?} of {} in guild {}: {:?}",
+                kind, target_id, guild_id, e
+            );
+        }
+    }
+}
+
+/// Fetches the guild's audit log entries created since the given unix time, newest first.
+async fn fetch_entries(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    since: i64,
+) -> Result<Vec<serenity::AuditLogEntry>, Error> {
+    let page_size = serenity::nonmax::NonMaxU8::new(PAGE_SIZE).unwrap();
+    let mut entries = vec![];
+    let mut before = None;
+
+    for _ in 0..MAX_PAGES {
+        let page = guild_id
+            .audit_logs(&ctx.http, None, None, before, Some(page_size))
+            .await?;
+        let full = page.entries.len() == PAGE_SIZE as usize;
+
+        let mut reached_since = false;
+        for entry in page.entries {
+            before = Some(entry.id);
+            if entry.id.created_at().unix_timestamp() < since {
+                reached_since = true;
+                break;
+            }
+            entries.push(entry);
+        }
+
+        if reached_since || !full {
+            break;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Takes the audit log entry of an action out of the fetched entries.
+fn take_entry(
+    entries: &mut Vec<serenity::AuditLogEntry>,
+    (_, kind, target_id): ActionKey,
+    seen_at: i64,
+) -> Option<serenity::AuditLogEntry> {
+    // A user can be banned more than once, so older entries do not count
+    let since = seen_at - WINDOW.as_secs() as i64;
+    let idx = entries.iter().position(|entry| {
+        ActionKind::of_action(&entry.action) == Some(kind)
+            && entry.target_id.is_some_and(|id| id.get() == target_id)
+            && entry.id.created_at().unix_timestamp() >= since
+    })?;
+    Some(entries.remove(idx))
+}
+
+/// Hands an entry the gateway did not deliver to the modules.
+async fn replay(
+    handler: &Handler,
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    entry: serenity::AuditLogEntry,
+) -> Result<(), Error> {
+    // The poller may have replayed it already
+    if !handler.audit_cursors.mark_handled(guild_id, entry.id) {
+        return Ok(());
+    }
+    info!(
+        "Attributed {:?} in guild {} from the audit log",
+        entry.action, guild_id
+    );
+    let event = serenity::FullEvent::GuildAuditLogEntryCreate { entry, guild_id };
+    handler.dispatch_modules(ctx, &event);
+    Ok(())
+}
+
+/// Alerts the guild that a destructive action happened without an audit log entry.
+async fn report(
+    data: &Data,
+    (guild_id, kind, target_id): ActionKey,
+    pending: &PendingAction,
+) -> Result<(), Error> {
+    let l10n = data.l10n.get_l10n_for_guild(guild_id, &data.db).await;
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("type", l10n.t(kind.type_key(), None));
+    args.set("target", pending.name.clone());
+    args.set("seconds", WINDOW.as_secs());
+
+    let target_field = match kind {
+        ActionKind::ChannelDelete => (
+            l10n.text("log-field-channel-id", None),
+            target_id.to_string(),
+        ),
+        ActionKind::RoleDelete => (l10n.text("log-field-role-id", None), target_id.to_string()),
+        ActionKind::BanAdd => (
+            l10n.text("log-field-user", None),
+            format!("<@{}>", target_id),
+        ),
+    };
+
+    data.logger
+        .log_action(
+            guild_id,
+            Some(kind.module()),
+            None,
+            LogLevel::Warn,
+            l10n.text("log-unattributed-title", None),
+            l10n.text("log-unattributed-desc", Some(&args)),
+            vec![
+                (
+                    l10n.text("log-field-type", None),
+                    l10n.t(kind.type_key(), None),
+                ),
+                target_field,
+            ],
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: serenity::GuildId = serenity::GuildId::new(1);
+    /// Unix time the tests' gateway events arrive
+    const SEEN_AT: i64 = 1_700_000_000;
+
+    /// Builds an audit log entry of the given action type created at the given unix time.
+    fn entry(action_type: u8, target_id: u64, created_at: i64) -> serenity::AuditLogEntry {
+        // Snowflakes hold milliseconds since the Discord epoch above bit 22
+        let id = ((created_at * 1000 - 1_420_070_400_000) as u64) << 22;
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "action_type": action_type,
+            "target_id": target_id.to_string(),
+            "user_id": "2",
+        }))
+        .unwrap()
+    }
+
+    fn pending(seen_at: i64) -> PendingAction {
+        PendingAction {
+            seen_at,
+            name: "name".to_string(),
+            attempts: 0,
+        }
+    }
+
+    #[test]
+    fn test_take_entry_matches_kind_and_target() {
+        let mut entries = vec![
+            entry(12, 10, SEEN_AT),
+            entry(32, 20, SEEN_AT),
+            entry(22, 30, SEEN_AT),
+        ];
+
+        let taken = take_entry(&mut entries, (GUILD, ActionKind::RoleDelete, 20), SEEN_AT);
+        assert_eq!(taken.and_then(|e| e.target_id).map(|id| id.get()), Some(20));
+        assert_eq!(entries.len(), 2);
+
+        // Same target but a different action, and same action but a different target
+        assert!(take_entry(&mut entries, (GUILD, ActionKind::RoleDelete, 10), SEEN_AT).is_none());
+        assert!(take_entry(&mut entries, (GUILD, ActionKind::BanAdd, 31), SEEN_AT).is_none());
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn test_take_entry_respects_window() {
+        let window = WINDOW.as_secs() as i64;
+        let mut entries = vec![
+            entry(22, 30, SEEN_AT - window - 1),
+            entry(22, 30, SEEN_AT - window),
+        ];
+
+        // An earlier ban of the same user is not this one
+        let taken = take_entry(&mut entries, (GUILD, ActionKind::BanAdd, 30), SEEN_AT).unwrap();
+        assert_eq!(taken.id.created_at().unix_timestamp(), SEEN_AT - window);
+        assert!(take_entry(&mut entries, (GUILD, ActionKind::BanAdd, 30), SEEN_AT).is_none());
+    }
+
+    #[test]
+    fn test_take_due_waits_full_window() {
+        let correlator = ActionCorrelator::default();
+        let now = Utc::now().timestamp();
+        let window = WINDOW.as_secs() as i64;
+        let other = serenity::GuildId::new(2);
+        correlator
+            .pending
+            .insert((GUILD, ActionKind::ChannelDelete, 1), pending(now - window));
+        correlator
+            .pending
+            .insert((GUILD, ActionKind::ChannelDelete, 2), pending(now));
+        correlator
+            .pending
+            .insert((other, ActionKind::ChannelDelete, 3), pending(now - window));
+
+        let due = correlator.take_due(GUILD);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, (GUILD, ActionKind::ChannelDelete, 1));
+        assert!(correlator.has_pending(GUILD));
+        assert!(correlator.has_pending(other));
+    }
+
+    #[test]
+    fn test_record_entry_settles_pending() {
+        let correlator = ActionCorrelator::default();
+        let key = (GUILD, ActionKind::RoleDelete, 20);
+        correlator.pending.insert(key, pending(SEEN_AT));
+
+        correlator.record_entry(GUILD, &entry(32, 20, SEEN_AT));
+        assert!(!correlator.has_pending(GUILD));
+        assert!(correlator.attributed.is_empty());
+
+        // An entry ahead of its gateway event is remembered for it
+        correlator.record_entry(GUILD, &entry(12, 10, SEEN_AT));
+        assert!(
+            correlator
+                .attributed
+                .contains_key(&(GUILD, ActionKind::ChannelDelete, 10))
+        );
+    }
+
+    #[test]
+    fn test_requeue_is_bounded() {
+        let correlator = ActionCorrelator::default();
+        let key = (GUILD, ActionKind::BanAdd, 30);
+        let mut due = vec![(key, pending(SEEN_AT))];
+
+        for _ in 1..MAX_FETCH_ATTEMPTS {
+            assert!(correlator.requeue(due).is_empty());
+            due = vec![correlator.pending.remove(&key).unwrap()];
+        }
+        let exhausted = correlator.requeue(due);
+        assert_eq!(exhausted.len(), 1);
+        assert!(!correlator.has_pending(GUILD));
+    }
+}
@@ -1,77 +1,323 @@
 use crate::Data;
+use crate::modules::{DispatchMode, EventKind, GuardModule};
+use dashmap::DashMap;
 use poise::serenity_prelude as serenity;
+use sea_orm::DatabaseConnection;
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::{error, info};
 
+pub mod audit_recovery;
+pub mod correlator;
+pub mod queue;
 pub mod shared_events;
+pub mod stats;
+
+pub use stats::EventStats;
+
+/// A module hook the dispatcher calls, with what it needs to outlive the event.
+#[derive(Clone)]
+enum Hook {
+    Ready,
+    GuildJoin {
+        guild: Arc<serenity::Guild>,
+        is_new: bool,
+    },
+    GuildLeave(serenity::GuildId),
+    Event(Arc<serenity::FullEvent>),
+}
+
+impl Hook {
+    fn name(&self) -> &'static str {
+        match self {
+            Hook::Ready => "on_ready",
+            Hook::GuildJoin { .. } => "on_guild_join",
+            Hook::GuildLeave(_) => "on_guild_leave",
+            Hook::Event(_) => "on_event",
+        }
+    }
+}
+
+/// Where a module's hook runs.
+enum Place {
+    /// A place taken in the guild's queue of an ordered module.
+    Queue(queue::Slot),
+    /// A task of its own.
+    Task,
+}
 
 /// Custom event handler for non-command Discord events
+#[derive(Clone)]
 pub struct Handler {
-    modules: Vec<crate::modules::Module>,
+    queues: Arc<queue::OrderedQueues>,
+    audit_cursors: Arc<audit_recovery::AuditLogCursors>,
+    correlator: Arc<correlator::ActionCorrelator>,
+    /// Map: ShardID -> task checking its guilds' audit logs for missed entries
+    audit_pollers: Arc<DashMap<serenity::ShardId, tokio::task::JoinHandle<()>>>,
 }
 
 impl Handler {
-    pub fn new() -> Self {
+    pub fn new(db: DatabaseConnection) -> Self {
         Self {
-            modules: crate::modules::get_modules(),
+            queues: Arc::new(queue::OrderedQueues::new()),
+            audit_cursors: Arc::new(audit_recovery::AuditLogCursors::new(db)),
+            correlator: Arc::new(correlator::ActionCorrelator::default()),
+            audit_pollers: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Takes the module's place in the guild's queue if it is ordered.
+    /// Returns None if the module's queue is full and the hook is dropped.
+    fn reserve(
+        &self,
+        module: &Arc<dyn GuardModule>,
+        guild_id: Option<serenity::GuildId>,
+    ) -> Option<Place> {
+        match (module.dispatch_mode(), guild_id) {
+            (DispatchMode::Ordered, Some(guild_id)) => self
+                .queues
+                .reserve(guild_id, module.definition().id)
+                .map(Place::Queue),
+            _ => Some(Place::Task),
+        }
+    }
+
+    /// Runs a hook of one module, in its reserved place or in a task of its own.
+    fn run_hook(
+        ctx: &serenity::Context,
+        data: &Arc<Data>,
+        module: &Arc<dyn GuardModule>,
+        place: Place,
+        hook: Hook,
+    ) {
+        let ctx = ctx.clone();
+        let data = data.clone();
+        let module = module.clone();
+        let module_id = module.definition().id;
+
+        let job = async move {
+            let started = Instant::now();
+            let result = match &hook {
+                Hook::Ready => module.on_ready(&ctx, &data).await,
+                Hook::GuildJoin { guild, is_new } => {
+                    module.on_guild_join(&ctx, guild, *is_new, &data).await
+                }
+                Hook::GuildLeave(guild_id) => module.on_guild_leave(&ctx, *guild_id, &data).await,
+                Hook::Event(event) => module.on_event(&ctx, event, &data).await,
+            };
+            if let Err(e) = result {
+                error!(
+                    "Error in {} hook of module {}: {:?}",
+                    hook.name(),
+                    module_id,
+                    e
+                );
+            }
+            data.event_stats.record(module_id, started.elapsed());
+        };
+
+        match place {
+            Place::Queue(slot) => slot.fill(Box::pin(job)),
+            Place::Task => {
+                tokio::spawn(job);
+            }
+        }
+    }
+
+    /// Runs a hook of every module.
+    fn run_hook_all(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: Option<serenity::GuildId>,
+        hook: Hook,
+    ) {
+        let data = ctx.data::<Data>();
+        for module in &data.modules {
+            if let Some(place) = self.reserve(module, guild_id) {
+                Self::run_hook(ctx, &data, module, place, hook.clone());
+            }
         }
     }
+
+    /// Takes the places of the modules subscribed to the event's kind.
+    /// Must run before anything awaits, so ordered modules see events in gateway order.
+    fn reserve_modules(
+        &self,
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+    ) -> Vec<(Arc<dyn GuardModule>, Place)> {
+        // Only modules subscribed to this kind of event get a task; events nobody
+        // subscribed to (typing, presences, ...) are not even cloned.
+        let Some(kind) = EventKind::of(event) else {
+            return vec![];
+        };
+        let data = ctx.data::<Data>();
+        let guild_id = queue::event_guild_id(event);
+
+        data.modules
+            .iter()
+            .filter(|m| m.events().contains(&kind))
+            .filter_map(|m| self.reserve(m, guild_id).map(|place| (m.clone(), place)))
+            .collect()
+    }
+
+    /// Hands an event to the modules whose places were taken for it.
+    fn run_modules(
+        ctx: &serenity::Context,
+        event: &serenity::FullEvent,
+        reserved: Vec<(Arc<dyn GuardModule>, Place)>,
+    ) {
+        if reserved.is_empty() {
+            return;
+        }
+        let data = ctx.data::<Data>();
+
+        // We clone the event once to put it in an Arc, allowing multiple spawned tasks to access it cheaply.
+        let hook = Hook::Event(Arc::new(event.clone()));
+
+        for (module, place) in reserved {
+            Self::run_hook(ctx, &data, &module, place, hook.clone());
+        }
+    }
+
+    /// Hands an event to the modules subscribed to its kind.
+    fn dispatch_modules(&self, ctx: &serenity::Context, event: &serenity::FullEvent) {
+        let reserved = self.reserve_modules(ctx, event);
+        Self::run_modules(ctx, event, reserved);
+    }
 }
 
 #[serenity::async_trait]
 impl serenity::EventHandler for Handler {
     async fn dispatch(&self, ctx: &serenity::Context, event: &serenity::FullEvent) {
+        // Queue places are taken before anything below awaits
+        let reserved = self.reserve_modules(ctx, event);
+
         // 1. Core / Service handling
         match event {
             serenity::FullEvent::Ready { data_about_bot, .. } => {
                 info!("Logged in as {}", data_about_bot.user.name);
+                self.run_hook_all(ctx, None, Hook::Ready);
+
+                // Only the first shard's task gets the queue, later ones return right away
+                tokio::spawn(crate::modules::run_config_hooks(ctx.clone()));
+
+                // Entries created while the shard was disconnected are never delivered
+                let poller = tokio::spawn(audit_recovery::poll_shard(self.clone(), ctx.clone()));
+                if let Some(previous) = self.audit_pollers.insert(ctx.shard_id, poller) {
+                    previous.abort();
+                }
+
+                let guild_ids: Vec<_> = data_about_bot.guilds.iter().map(|g| g.id).collect();
+                if let Err(e) = self.audit_cursors.preload(&guild_ids).await {
+                    error!("Failed to load audit log cursors: {:?}", e);
+                }
             }
             serenity::FullEvent::GuildCreate { guild, is_new, .. } => {
                 if is_new.unwrap_or(false) {
                     info!("Joined new guild: {} ({})", guild.name, guild.id.get());
                 }
 
-                // Sync invites if invite tracking is enabled
+                let hook = Hook::GuildJoin {
+                    guild: Arc::new(guild.clone()),
+                    is_new: is_new.unwrap_or(false),
+                };
+                self.run_hook_all(ctx, Some(guild.id), hook);
+
+                if let Err(e) = self.audit_cursors.preload(&[guild.id]).await {
+                    error!("Failed to load audit log cursor: {:?}", e);
+                }
+            }
+            serenity::FullEvent::GuildDelete { incomplete, .. } => {
                 let data = ctx.data::<Data>();
-                let guild_id = guild.id;
-                let ctx_clone = ctx.clone();
-                let data_clone = data.clone();
-                tokio::spawn(async move {
-                    if let Ok(Some(_)) =
-                        crate::modules::invite_tracking::tracking::get_config(guild_id, &data_clone)
-                            .await
-                    {
-                        if let Err(e) = crate::modules::invite_tracking::tracking::sync_all_guild_invites(
-                            &ctx_clone, guild_id, &data_clone,
-                        )
-                        .await
-                        {
-                            error!("Failed to sync invites for guild {}: {:?}", guild_id, e);
-                        }
+                data.whitelist.invalidate_guild(incomplete.id);
+                if !incomplete.unavailable {
+                    info!("Left guild: {}", incomplete.id.get());
+                    let hook = Hook::GuildLeave(incomplete.id);
+                    self.run_hook_all(ctx, Some(incomplete.id), hook);
+                    if let Err(e) = self.audit_cursors.remove(incomplete.id).await {
+                        error!("Failed to remove audit log cursor: {:?}", e);
                     }
-                });
+                }
             }
-            serenity::FullEvent::GuildDelete { incomplete, .. } => {
-                info!("Left guild: {}", incomplete.id.get());
+            serenity::FullEvent::GuildUpdate { new_data, .. } => {
+                let data = ctx.data::<Data>();
+                data.whitelist.invalidate_guild(new_data.id);
+            }
+            serenity::FullEvent::GuildRoleCreate { new, .. } => {
+                let data = ctx.data::<Data>();
+                data.whitelist.invalidate_guild(new.guild_id);
+            }
+            serenity::FullEvent::GuildRoleUpdate { new, .. } => {
+                let data = ctx.data::<Data>();
+                data.whitelist.invalidate_guild(new.guild_id);
             }
             serenity::FullEvent::ChannelDelete { channel, .. } => {
                 let data = ctx.data::<Data>();
+                // Children of a category become uncategorized, remember them for a restore
+                let children = if channel.base.kind == serenity::ChannelType::Category {
+                    ctx.cache
+                        .guild(channel.base.guild_id)
+                        .map(|guild| {
+                            guild
+                                .channels
+                                .iter()
+                                .filter(|c| c.parent_id == Some(channel.id))
+                                .map(|c| c.id)
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                } else {
+                    vec![]
+                };
                 data.cache
-                    .store_channel(channel.base.guild_id, channel.clone());
+                    .store_channel(channel.base.guild_id, channel.clone(), children);
+                let key = (
+                    channel.base.guild_id,
+                    correlator::ActionKind::ChannelDelete,
+                    channel.id.get(),
+                );
+                correlator::track(self, ctx, key, format!("#{}", channel.base.name)).await;
             }
             serenity::FullEvent::GuildRoleDelete {
                 guild_id,
+                removed_role_id,
                 removed_role_data_if_available,
                 ..
             } => {
+                let data = ctx.data::<Data>();
+                data.whitelist.invalidate_guild(*guild_id);
                 if let Some(role) = removed_role_data_if_available {
-                    let data = ctx.data::<Data>();
                     data.cache.store_role(*guild_id, role.clone());
                 }
+                let name = removed_role_data_if_available
+                    .as_ref()
+                    .map(|role| format!("@{}", role.name))
+                    .unwrap_or_else(|| removed_role_id.to_string());
+                let key = (
+                    *guild_id,
+                    correlator::ActionKind::RoleDelete,
+                    removed_role_id.get(),
+                );
+                correlator::track(self, ctx, key, name).await;
+            }
+            serenity::FullEvent::GuildBanAddition {
+                guild_id,
+                banned_user,
+                ..
+            } => {
+                let key = (
+                    *guild_id,
+                    correlator::ActionKind::BanAdd,
+                    banned_user.id.get(),
+                );
+                correlator::track(self, ctx, key, banned_user.tag()).await;
             }
             // Shared Logic Dispatch
             serenity::FullEvent::GuildMemberAddition { new_member, .. } => {
                 let data = ctx.data::<Data>();
+                data.whitelist
+                    .invalidate_member(new_member.guild_id, new_member.user.id);
                 if let Err(e) = shared_events::role_cache::handle_guild_member_add(
                     ctx,
                     new_member.guild_id,
@@ -90,6 +336,8 @@ impl serenity::EventHandler for Handler {
                 ..
             } => {
                 let data = ctx.data::<Data>();
+                data.whitelist
+                    .invalidate_member(event.guild_id, event.user.id);
                 if let Err(e) = shared_events::role_cache::handle_guild_member_update(
                     ctx,
                     old_if_available.clone(),
@@ -109,6 +357,7 @@ impl serenity::EventHandler for Handler {
                 ..
             } => {
                 let data = ctx.data::<Data>();
+                data.whitelist.invalidate_member(*guild_id, user.id);
                 if let Err(e) = shared_events::role_cache::handle_guild_member_remove(
                     ctx,
                     *guild_id,
@@ -128,25 +377,15 @@ impl serenity::EventHandler for Handler {
         }
 
         // 2. Systematic Module Dispatch
-        // We clone the event once to put it in an Arc, allowing multiple spawned tasks to access it cheaply.
-        let event_arc = Arc::new(event.clone());
-        let data = ctx.data::<Data>();
-
-        for module in &self.modules {
-            for handler in &module.event_handlers {
-                let ctx = ctx.clone();
-                let event_arc = event_arc.clone();
-                let data = data.clone();
-                let handler = *handler;
-                let module_id = module.definition.id;
-
-                tokio::spawn(async move {
-                    if let Err(e) = handler(&ctx, &event_arc, &data).await {
-                        error!("Error in event handler for module {}: {:?}", module_id, e);
-                    }
-                });
+        // Audit log entries replayed by the poller must not be handled again;
+        // returning drops the reserved places, which skips them
+        if let serenity::FullEvent::GuildAuditLogEntryCreate { entry, guild_id } = event {
+            if !self.audit_cursors.mark_handled(*guild_id, entry.id) {
+                return;
             }
+            self.correlator.record_entry(*guild_id, entry);
         }
+        Self::run_modules(ctx, event, reserved);
     }
 }
 
@@ -189,6 +428,13 @@ async fn handle_interactions(ctx: &serenity::Context, interaction: &serenity::In
                 {
                     error!("Error handling whitelist interaction: {:?}", e);
                 }
+            } else if custom_id.starts_with("approval_") {
+                if let Err(e) =
+                    crate::services::approval::handle_interaction(&ctx, &component_interaction, &data)
+                        .await
+                {
+                    error!("Error handling approval interaction: {:?}", e);
+                }
             } else if custom_id.starts_with("setup_") {
                 if let Err(e) =
                     crate::services::setup::handle_interaction(&ctx, &component_interaction, &data)
@@ -215,7 +461,9 @@ async fn handle_interactions(ctx: &serenity::Context, interaction: &serenity::In
                 )
                 .await
                 {
-                    Ok(Some(components)) => {
+                    Ok(crate::services::config::whitelist::WhitelistModalResult::Components(
+                        components,
+                    )) => {
                         // First acknowledge the modal, then update the message
                         if let Err(e) = modal_interaction
                             .create_response(
@@ -236,7 +484,25 @@ async fn handle_interactions(ctx: &serenity::Context, interaction: &serenity::In
                             error!("Error updating response after modal submit: {:?}", e);
                         }
                     }
-                    Ok(None) => {
+                    Ok(crate::services::config::whitelist::WhitelistModalResult::Invalid(
+                        message,
+                    )) => {
+                        // Keep the menu as is and explain what was wrong
+                        if let Err(e) = modal_interaction
+                            .create_response(
+                                &ctx.http,
+                                serenity::CreateInteractionResponse::Message(
+                                    serenity::CreateInteractionResponseMessage::new()
+                                        .content(message)
+                                        .ephemeral(true),
+                                ),
+                            )
+                            .await
+                        {
+                            error!("Error responding to invalid modal: {:?}", e);
+                        }
+                    }
+                    Ok(crate::services::config::whitelist::WhitelistModalResult::None) => {
                         // Just acknowledge the modal without editing
                         if let Err(e) = modal_interaction
                             .create_response(
@@ -252,6 +518,16 @@ async fn handle_interactions(ctx: &serenity::Context, interaction: &serenity::In
                         error!("Error handling whitelist modal submit: {:?}", e);
                     }
                 }
+            } else if custom_id.starts_with("logtpl_modal_") {
+                if let Err(e) = crate::services::config::log_templates::handle_modal_submit(
+                    &ctx,
+                    &modal_interaction,
+                    &data,
+                )
+                .await
+                {
+                    error!("Error handling log template modal submit: {:?}", e);
+                }
             }
         });
     }
@@ -0,0 +1,184 @@
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Duration, timeout};
+use tracing::error;
+
+/// Events buffered per guild and module; further events are dropped until it drains.
+const QUEUE_CAPACITY: usize = 256;
+/// How long a worker waits for the next event before shutting down.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A module handler call for one event.
+pub type Job = poise::BoxFuture<'static, ()>;
+
+type QueueKey = (serenity::GuildId, &'static str);
+
+/// A place in a queue, taken as soon as the event arrives and filled with the handler
+/// call once the event has been prepared. Dropping it skips the place.
+pub struct Slot(oneshot::Sender<Job>);
+
+impl Slot {
+    pub fn fill(self, job: Job) {
+        // The worker only goes away when its queue is dropped, so this cannot fail
+        let _ = self.0.send(job);
+    }
+}
+
+/// Runs the handlers of ordered modules one event at a time per guild, in the order
+/// the gateway delivered the events.
+///
+/// Each guild and module pair gets its own worker, so a slow guild or module does not
+/// hold back the others. Places are taken without waiting, so gateway order is kept
+/// even when preparing an event awaits. When a queue is full, its events are dropped
+/// and logged instead of holding back the gateway for every guild. Workers shut down
+/// after being idle for a while and are recreated on the next event.
+pub struct OrderedQueues {
+    senders: DashMap<QueueKey, mpsc::Sender<oneshot::Receiver<Job>>>,
+}
+
+impl OrderedQueues {
+    pub fn new() -> Self {
+        Self {
+            senders: DashMap::new(),
+        }
+    }
+
+    /// Takes the next place in the queue of the guild and module.
+    /// Returns None if the queue is full and the event is dropped.
+    pub fn reserve(
+        self: &Arc<Self>,
+        guild_id: serenity::GuildId,
+        module_id: &'static str,
+    ) -> Option<Slot> {
+        let key = (guild_id, module_id);
+        let (slot, mut place) = oneshot::channel();
+
+        loop {
+            let sender = self
+                .senders
+                .entry(key)
+                .or_insert_with(|| {
+                    let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+                    tokio::spawn(self.clone().run_worker(key, rx));
+                    tx
+                })
+                .clone();
+
+            match sender.try_send(place) {
+                Ok(()) => return Some(Slot(slot)),
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    error!(
+                        "Event queue of module {} in guild {} is full, dropping event",
+                        module_id, guild_id
+                    );
+                    return None;
+                }
+                Err(mpsc::error::TrySendError::Closed(returned)) => {
+                    // Only happens if the worker panicked; start a new one and retry
+                    error!(
+                        "Event worker of module {} in guild {} is gone",
+                        module_id, guild_id
+                    );
+                    self.senders.remove_if(&key, |_, tx| tx.is_closed());
+                    place = returned;
+                }
+            }
+        }
+    }
+
+    async fn run_worker(
+        self: Arc<Self>,
+        key: QueueKey,
+        mut rx: mpsc::Receiver<oneshot::Receiver<Job>>,
+    ) {
+        loop {
+            match timeout(IDLE_TIMEOUT, rx.recv()).await {
+                Ok(Some(place)) => {
+                    // Waits for the event to be prepared; a dropped slot is skipped
+                    if let Ok(job) = place.await {
+                        job.await;
+                    }
+                }
+                Ok(None) => return,
+                Err(_) => {
+                    // Only the map holds a sender while its shard is locked, so nothing
+                    // can be sent between this check and the removal
+                    if self
+                        .senders
+                        .remove_if(&key, |_, tx| tx.strong_count() == 1 && rx.is_empty())
+                        .is_some()
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the guild an event belongs to, or None for events outside guilds.
+pub fn event_guild_id(event: &serenity::FullEvent) -> Option<serenity::GuildId> {
+    match event {
+        serenity::FullEvent::GuildCreate { guild, .. } => Some(guild.id),
+        serenity::FullEvent::GuildAuditLogEntryCreate { guild_id, .. } => Some(*guild_id),
+        serenity::FullEvent::GuildMemberAddition { new_member, .. } => Some(new_member.guild_id),
+        serenity::FullEvent::GuildMemberUpdate { event, .. } => Some(event.guild_id),
+        serenity::FullEvent::GuildMemberRemoval { guild_id, .. } => Some(*guild_id),
+        serenity::FullEvent::InviteCreate { data, .. } => data.guild_id,
+        serenity::FullEvent::InviteDelete { data, .. } => data.guild_id,
+        serenity::FullEvent::Message { new_message, .. } => new_message.guild_id,
+        serenity::FullEvent::MessageUpdate { event, .. } => event.message.guild_id,
+        serenity::FullEvent::MessageDelete { guild_id, .. } => *guild_id,
+        serenity::FullEvent::VoiceStateUpdate { new, .. } => new.guild_id,
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_reserve_keeps_order() {
+        let queues = Arc::new(OrderedQueues::new());
+        let guild_id = serenity::GuildId::new(1);
+        let ran = Arc::new(Mutex::new(vec![]));
+
+        let slots: Vec<_> = (0..3)
+            .map(|_| queues.reserve(guild_id, "test").unwrap())
+            .collect();
+        let last = queues.reserve(guild_id, "test").unwrap();
+
+        // Filled in reverse, run in the order the places were taken
+        for (i, slot) in slots.into_iter().enumerate().rev() {
+            let ran = ran.clone();
+            slot.fill(Box::pin(async move {
+                ran.lock().unwrap().push(i);
+            }));
+        }
+        let (done_tx, done_rx) = oneshot::channel();
+        last.fill(Box::pin(async move {
+            let _ = done_tx.send(());
+        }));
+
+        done_rx.await.unwrap();
+        assert_eq!(*ran.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_reserve_drops_when_full() {
+        let queues = Arc::new(OrderedQueues::new());
+        let guild_id = serenity::GuildId::new(1);
+
+        // The test runtime is single threaded, so the worker takes nothing meanwhile
+        let _slots: Vec<_> = (0..QUEUE_CAPACITY)
+            .map(|_| queues.reserve(guild_id, "test").unwrap())
+            .collect();
+
+        assert!(queues.reserve(guild_id, "test").is_none());
+        assert!(queues.reserve(serenity::GuildId::new(2), "test").is_some());
+    }
+}
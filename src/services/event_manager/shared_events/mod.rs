@@ -1,3 +1,6 @@
 pub mod role_cache;
 
-pub use role_cache::{get_stored_member_roles, touch_guild_stats};
+pub use role_cache::{
+    delete_all_guild_member_roles, fetch_and_store_all_members, get_stored_member_roles,
+    touch_guild_stats,
+};
@@ -1,10 +1,10 @@
 use crate::db::entities::logging_guilds;
 use crate::db::entities::member_old_roles;
-use crate::db::entities::module_configs::{self, LoggingModuleConfig, ModuleType};
+use crate::db::entities::module_configs::{LoggingModuleConfig, ModuleType};
 use crate::{Data, Error};
 use chrono::Utc;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set};
+use sea_orm::{EntityTrait, Set};
 use serde_json::json;
 use tracing::error;
 
@@ -40,29 +40,21 @@ pub async fn store_member_roles(
 ) -> Result<(), Error> {
     // Check if we should store roles
     // We store roles if either Logging (with membership logging enabled) or Sticky Roles is enabled
-    let configs = module_configs::Entity::find()
-        .filter(
-            module_configs::Column::GuildId
-                .eq(guild_id.get() as i64)
-                .and(module_configs::Column::Enabled.eq(true))
-                .and(
-                    module_configs::Column::ModuleType
-                        .eq(ModuleType::Logging)
-                        .or(module_configs::Column::ModuleType.eq(ModuleType::StickyRoles)),
-                ),
-        )
-        .all(&data.db)
+    let sticky_roles = data
+        .config_cache
+        .module_config(guild_id, ModuleType::StickyRoles)
+        .await?;
+    let logging = data
+        .config_cache
+        .module_config(guild_id, ModuleType::Logging)
         .await?;
 
-    let should_store = configs.iter().any(|config| match config.module_type {
-        ModuleType::StickyRoles => true,
-        ModuleType::Logging => {
+    let should_store = sticky_roles.is_some_and(|c| c.enabled)
+        || logging.is_some_and(|c| {
             let log_config: LoggingModuleConfig =
-                serde_json::from_value(config.config.clone()).unwrap_or_default();
-            log_config.log_membership
-        }
-        _ => false,
-    });
+                serde_json::from_value(c.config).unwrap_or_default();
+            c.enabled && log_config.log_membership
+        });
 
     if !should_store {
         return Ok(());
@@ -121,6 +113,178 @@ pub async fn get_stored_member_roles(
     }
 }
 
+/// Delete all member role records for a guild.
+/// Used when neither membership logging nor sticky roles need them anymore.
+pub async fn delete_all_guild_member_roles(
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<u64, Error> {
+    use sea_orm::ColumnTrait;
+    use sea_orm::QueryFilter;
+
+    let result = member_old_roles::Entity::delete_many()
+        .filter(member_old_roles::Column::GuildId.eq(guild_id.get() as i64))
+        .exec(&data.db)
+        .await?;
+
+    // Also delete the logging guild entry
+    logging_guilds::Entity::delete_by_id(guild_id.get() as i64)
+        .exec(&data.db)
+        .await
+        .ok();
+
+    Ok(result.rows_affected)
+}
+
+/// Internal function to touch guild stats with db connection directly.
+async fn touch_guild_stats_with_db(
+    guild_id: serenity::GuildId,
+    db: &sea_orm::DatabaseConnection,
+) -> Result<(), Error> {
+    let now = Utc::now();
+    let model = logging_guilds::ActiveModel {
+        guild_id: Set(guild_id.get() as i64),
+        last_accessed_at: Set(now.into()),
+    };
+
+    logging_guilds::Entity::insert(model)
+        .on_conflict(
+            sea_orm::sea_query::OnConflict::column(logging_guilds::Column::GuildId)
+                .update_column(logging_guilds::Column::LastAccessedAt)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Internal function to store member roles with db connection directly.
+async fn store_member_roles_with_db(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    roles: &[serenity::RoleId],
+    db: &sea_orm::DatabaseConnection,
+) -> Result<(), Error> {
+    let role_ids: Vec<u64> = roles.iter().map(|r| r.get()).collect();
+    let now = Utc::now();
+
+    let model = member_old_roles::ActiveModel {
+        guild_id: Set(guild_id.get() as i64),
+        user_id: Set(user_id.get() as i64),
+        role_ids: Set(json!(role_ids)),
+        updated_at: Set(now.into()),
+    };
+
+    member_old_roles::Entity::insert(model)
+        .on_conflict(
+            sea_orm::sea_query::OnConflict::columns([
+                member_old_roles::Column::GuildId,
+                member_old_roles::Column::UserId,
+            ])
+            .update_columns([
+                member_old_roles::Column::RoleIds,
+                member_old_roles::Column::UpdatedAt,
+            ])
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Fetch all members from the guild and store their roles in the database.
+/// Used when membership logging or sticky roles is enabled.
+/// This is run in the background to not block the interaction.
+/// Takes DatabaseConnection directly so it can be used in spawned tasks.
+/// Filters out managed roles (bot/integration roles) as they can't be restored.
+pub async fn fetch_and_store_all_members(
+    http: std::sync::Arc<serenity::Http>,
+    guild_id: serenity::GuildId,
+    db: sea_orm::DatabaseConnection,
+) -> Result<usize, Error> {
+    use serenity::nonmax::NonMaxU16;
+    use std::collections::HashSet;
+    use tracing::info;
+
+    // Touch the guild stats first
+    touch_guild_stats_with_db(guild_id, &db).await?;
+
+    // Fetch guild roles to identify managed roles
+    let guild_roles = guild_id.roles(&http).await.unwrap_or_default();
+    let managed_role_ids: HashSet<serenity::RoleId> = guild_roles
+        .into_iter()
+        .filter(|role| role.managed())
+        .map(|role| role.id)
+        .collect();
+
+    let mut last_id: Option<serenity::UserId> = None;
+    let mut total_members = 0;
+
+    loop {
+        match guild_id
+            .members(&http, Some(NonMaxU16::new(1000).unwrap()), last_id)
+            .await
+        {
+            Ok(members) => {
+                let count = members.len();
+                total_members += count;
+
+                // Store each member's roles (excluding managed roles)
+                for member in &members {
+                    let roles: Vec<serenity::RoleId> = member
+                        .roles
+                        .iter()
+                        .filter(|r| !managed_role_ids.contains(r))
+                        .cloned()
+                        .collect();
+
+                    if let Err(e) =
+                        store_member_roles_with_db(guild_id, member.user.id, &roles, &db).await
+                    {
+                        error!(
+                            "Failed to store roles for member {} in guild {}: {:?}",
+                            member.user.id.get(), guild_id.get(), e
+                        );
+                    }
+                }
+
+                info!(
+                    "Stored roles for {} members (total: {}) in guild {}",
+                    count, total_members, guild_id.get()
+                );
+
+                if count < 1000 {
+                    break;
+                }
+
+                if let Some(last_member) = members.last() {
+                    last_id = Some(last_member.user.id);
+                }
+            }
+            Err(e) => {
+                error!(
+                    "Failed to fetch members for guild {} at offset {:?}: {:?}",
+                    guild_id, last_id, e
+                );
+                break;
+            }
+        }
+    }
+
+    info!(
+        "Finished storing roles for {} members in guild {}",
+        total_members, guild_id.get()
+    );
+
+    Ok(total_members)
+}
+
+#[cfg_attr(
+    not(any(feature = "sticky-roles", feature = "logging")),
+    allow(unused_variables)
+)]
 pub async fn handle_guild_member_add(
     ctx: &serenity::Context,
     guild_id: serenity::GuildId,
@@ -131,6 +295,7 @@ pub async fn handle_guild_member_add(
     touch_guild_stats(guild_id, data).await?;
 
     // 2. Handle Sticky Roles (Restore)
+    #[cfg(feature = "sticky-roles")]
     if let Err(e) = crate::modules::sticky_roles::tracking::handle_guild_member_add(
         ctx, guild_id, &member, data,
     )
@@ -140,6 +305,7 @@ pub async fn handle_guild_member_add(
     }
 
     // 3. Handle Logging (Join Message)
+    #[cfg(feature = "logging")]
     if let Err(e) = crate::modules::logging::events::membership::handle_guild_member_add(
         ctx,
         guild_id,
@@ -190,6 +356,7 @@ pub async fn handle_guild_member_update(
     Ok(())
 }
 
+#[cfg_attr(not(feature = "logging"), allow(unused_variables))]
 pub async fn handle_guild_member_remove(
     ctx: &serenity::Context,
     guild_id: serenity::GuildId,
@@ -203,6 +370,7 @@ pub async fn handle_guild_member_remove(
     }
 
     // Handle Logging (Leave Message)
+    #[cfg(feature = "logging")]
     if let Err(e) = crate::modules::logging::events::membership::handle_guild_member_remove(
         ctx,
         guild_id,
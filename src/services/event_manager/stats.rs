@@ -0,0 +1,59 @@
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Default)]
+struct ModuleLatency {
+    handled: AtomicU64,
+    total_micros: AtomicU64,
+    max_micros: AtomicU64,
+}
+
+/// Latency summary of one module's event handlers.
+pub struct ModuleLatencySnapshot {
+    pub module_id: &'static str,
+    pub handled: u64,
+    pub average: Duration,
+    pub max: Duration,
+}
+
+/// How long each module spends handling the events it subscribed to, since startup.
+#[derive(Default)]
+pub struct EventStats {
+    modules: DashMap<&'static str, ModuleLatency>,
+}
+
+impl EventStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, module_id: &'static str, elapsed: Duration) {
+        let micros = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        let entry = self.modules.entry(module_id).or_default();
+        entry.handled.fetch_add(1, Ordering::Relaxed);
+        entry.total_micros.fetch_add(micros, Ordering::Relaxed);
+        entry.max_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    /// Returns the modules that handled at least one event, slowest average first.
+    pub fn snapshot(&self) -> Vec<ModuleLatencySnapshot> {
+        let mut snapshot: Vec<_> = self
+            .modules
+            .iter()
+            .map(|entry| {
+                let handled = entry.handled.load(Ordering::Relaxed);
+                let total = entry.total_micros.load(Ordering::Relaxed);
+                ModuleLatencySnapshot {
+                    module_id: *entry.key(),
+                    handled,
+                    average: Duration::from_micros(total / handled.max(1)),
+                    max: Duration::from_micros(entry.max_micros.load(Ordering::Relaxed)),
+                }
+            })
+            .filter(|s| s.handled > 0)
+            .collect();
+        snapshot.sort_by(|a, b| b.average.cmp(&a.average));
+        snapshot
+    }
+}
@@ -1,5 +1,6 @@
+use crate::db::entities::jails;
 use crate::db::entities::module_configs::ModuleType;
-use crate::db::entities::{guild_configs, jails, module_configs};
+use crate::services::config_cache::ConfigCacheService;
 use crate::services::logger::{LogLevel, LoggerService};
 use chrono::{Duration, Utc};
 use fluent::FluentArgs;
@@ -9,6 +10,7 @@ use std::sync::Arc;
 
 pub struct JailService {
     pub(crate) db: DatabaseConnection,
+    pub(crate) config_cache: Arc<ConfigCacheService>,
     pub(crate) logger: Arc<LoggerService>,
     pub(crate) l10n: Arc<crate::services::localization::LocalizationManager>,
 }
@@ -16,10 +18,16 @@ pub struct JailService {
 impl JailService {
     pub fn new(
         db: DatabaseConnection,
+        config_cache: Arc<ConfigCacheService>,
         logger: Arc<LoggerService>,
         l10n: Arc<crate::services::localization::LocalizationManager>,
     ) -> Self {
-        Self { db, logger, l10n }
+        Self {
+            db,
+            config_cache,
+            logger,
+            l10n,
+        }
     }
 
     pub async fn jail_user(
@@ -34,8 +42,9 @@ impl JailService {
         let mut member = guild_id.member(http, user_id).await?;
 
         // Get guild config for jail role
-        let g_config = guild_configs::Entity::find_by_id(guild_id.get() as i64)
-            .one(&self.db)
+        let g_config = self
+            .config_cache
+            .guild_config(guild_id)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Guild configuration not found"))?;
 
@@ -112,17 +121,16 @@ impl JailService {
 
         self.logger
             .log_action(
-                http,
                 guild_id,
                 Some(ModuleType::ModerationProtection),
                 None,
                 LogLevel::Audit,
-                &l10n.t("log-mod-jail-title", None),
-                &l10n.t("log-mod-jail-desc", Some(&user_args)),
+                l10n.text("log-mod-jail-title", None),
+                l10n.text("log-mod-jail-desc", Some(&user_args)),
                 vec![
-                    (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                    (&l10n.t("log-field-duration", None), duration_str),
-                    (&l10n.t("log-field-reason", None), reason.to_string()),
+                    (l10n.text("log-field-user", None), format!("<@{}>", user_id.get())),
+                    (l10n.text("log-field-duration", None), duration_str),
+                    (l10n.text("log-field-reason", None), reason.to_string()),
                 ],
             )
             .await?;
@@ -154,8 +162,9 @@ impl JailService {
         let guild = guild_id.to_partial_guild(http).await?;
 
         // Get jail role id
-        let g_config = guild_configs::Entity::find_by_id(guild_id.get() as i64)
-            .one(&self.db)
+        let g_config = self
+            .config_cache
+            .guild_config(guild_id)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Guild configuration not found"))?;
 
@@ -213,12 +222,10 @@ impl JailService {
                 // User is not in the guild anymore.
                 // We should remove the jail role from their sticky roles if Sticky Roles is enabled.
                 let sticky_enabled = {
-                    let m_config = module_configs::Entity::find_by_id((
-                        guild_id.get() as i64,
-                        ModuleType::StickyRoles,
-                    ))
-                    .one(&self.db)
-                    .await?;
+                    let m_config = self
+                        .config_cache
+                        .module_config(guild_id, ModuleType::StickyRoles)
+                        .await?;
                     m_config.map(|m| m.enabled).unwrap_or(false)
                 };
 
@@ -274,14 +281,13 @@ impl JailService {
 
         self.logger
             .log_action(
-                http,
                 guild_id,
                 Some(ModuleType::ModerationProtection),
                 None,
                 LogLevel::Audit,
-                &l10n.t("log-mod-unjail-title", None),
-                &l10n.t("log-mod-unjail-desc", Some(&user_args)),
-                vec![(&l10n.t("log-field-user", None), format!("<@{}>", user_id.get()))],
+                l10n.text("log-mod-unjail-title", None),
+                l10n.text("log-mod-unjail-desc", Some(&user_args)),
+                vec![(l10n.text("log-field-user", None), format!("<@{}>", user_id.get()))],
             )
             .await?;
 
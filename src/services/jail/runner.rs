@@ -9,12 +9,14 @@ use tracing::{error, info};
 impl JailService {
     pub fn start_unjail_runner(&self, http: Arc<serenity::Http>) {
         let db = self.db.clone();
+        let config_cache = self.config_cache.clone();
         let logger = self.logger.clone();
         let l10n = self.l10n.clone();
 
         tokio::spawn(async move {
             info!("Jail unjail runner started.");
-            let jail_service = JailService::new(db.clone(), logger.clone(), l10n.clone());
+            let jail_service =
+                JailService::new(db.clone(), config_cache, logger.clone(), l10n.clone());
 
             loop {
                 sleep(Duration::from_secs(60)).await;
@@ -1,11 +1,11 @@
-use fluent::{FluentArgs, FluentResource};
+use fluent::{FluentArgs, FluentResource, FluentValue};
 use fluent_bundle::bundle::FluentBundle;
 use include_dir::{Dir, include_dir};
 use poise::serenity_prelude as serenity;
 use sea_orm::DatabaseConnection;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use tracing::{error, info};
 use unic_langid::LanguageIdentifier;
 
@@ -165,8 +165,94 @@ impl LocalizationManager {
         key.to_string()
     }
 
+    /// Names of the variables a message uses in the default (en-US) locale.
+    pub fn message_variables(&self, key: &str) -> Vec<String> {
+        let Some(dir) = LOCALES_DIR.get_dir("en-US") else {
+            return Vec::new();
+        };
+
+        let mut source = String::new();
+        for file in dir.files() {
+            let Some(content) = file.contents_utf8() else {
+                continue;
+            };
+
+            let mut lines = content.lines();
+            while let Some(line) = lines.next() {
+                let Some(value) = line
+                    .strip_prefix(key)
+                    .and_then(|rest| rest.trim_start().strip_prefix('='))
+                else {
+                    continue;
+                };
+
+                source.push_str(value);
+                // Continuation lines of a multiline message are indented
+                for next in lines.by_ref() {
+                    if !next.starts_with(' ') {
+                        break;
+                    }
+                    source.push_str(next);
+                }
+                break;
+            }
+        }
+
+        template_variables(&source)
+    }
+
+    /// Checks that a guild-provided template is valid Fluent and only uses the given variables.
+    pub fn validate_template(source: &str, allowed: &[String]) -> Result<(), TemplateError> {
+        if FluentResource::try_new(template_resource(source)).is_err() {
+            return Err(TemplateError::Syntax);
+        }
+
+        match template_variables(source)
+            .into_iter()
+            .find(|var| !allowed.contains(var))
+        {
+            Some(var) => Err(TemplateError::UnknownVariable(var)),
+            None => Ok(()),
+        }
+    }
+
+    /// Renders a guild-provided template with the arguments of the original message.
+    /// Returns `None` if the template cannot be rendered.
+    pub fn render_template(
+        &self,
+        locale: &str,
+        source: &str,
+        args: &[(String, String)],
+    ) -> Option<String> {
+        let lang_id = locale
+            .parse::<LanguageIdentifier>()
+            .unwrap_or_else(|_| "en-US".parse().unwrap());
+
+        let resource = FluentResource::try_new(template_resource(source)).ok()?;
+        let mut bundle = ConcurrentBundle::new_concurrent(vec![lang_id]);
+        bundle.set_use_isolating(false);
+        bundle.add_resource(resource).ok()?;
+
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(name.clone(), value.clone());
+        }
+
+        let pattern = bundle.get_message(TEMPLATE_MESSAGE_ID)?.value()?;
+        let mut errors = vec![];
+        Some(
+            bundle
+                .format_pattern(pattern, Some(&fluent_args), &mut errors)
+                .into_owned(),
+        )
+    }
+
     pub fn apply_translations<U, E>(&self, commands: &mut [poise::Command<U, E>]) {
-        for (lang_id, locales) in &self.command_locales {
+        // en-US replaces the base names the other locales are looked up by, so it goes last
+        let mut command_locales: Vec<_> = self.command_locales.iter().collect();
+        command_locales.sort_by_key(|(lang_id, _)| lang_id.to_string() == "en-US");
+
+        for (lang_id, locales) in command_locales {
             let locale_str = lang_id.to_string();
 
             for cmd in commands.iter_mut() {
@@ -256,6 +342,88 @@ impl L10nProxy {
     pub fn t(&self, key: &str, args: Option<&FluentArgs>) -> String {
         self.manager.translate(&self.locale, key, args)
     }
+
+    /// Like `t`, but keeps the key and arguments next to the rendered text.
+    pub fn text(&self, key: &str, args: Option<&FluentArgs>) -> LocalizedText {
+        LocalizedText {
+            key: key.to_string(),
+            text: self.t(key, args),
+            locale: self.locale.clone(),
+            args: args
+                .map(|args| {
+                    args.iter()
+                        .map(|(name, value)| (name.to_string(), fluent_value_to_string(value)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// A translated string that remembers the key and arguments it was rendered from,
+/// so log sinks can work with the key instead of the text.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LocalizedText {
+    /// Fluent message key; empty for text that was not translated
+    pub key: String,
+    pub text: String,
+    /// Locale the text was rendered in, so templates are rendered in the same one
+    #[serde(skip)]
+    pub locale: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl LocalizedText {
+    /// Wraps text that does not come from a Fluent message.
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Why a guild-provided template was rejected.
+#[derive(Debug)]
+pub enum TemplateError {
+    /// The template is not valid Fluent syntax
+    Syntax,
+    /// The template references a variable the message does not provide
+    UnknownVariable(String),
+}
+
+/// Message id under which guild templates are parsed.
+const TEMPLATE_MESSAGE_ID: &str = "template";
+
+/// Wraps a template in a Fluent message; continuation lines must be indented.
+fn template_resource(source: &str) -> String {
+    format!(
+        "{} = {}\n",
+        TEMPLATE_MESSAGE_ID,
+        source.trim().replace('\n', "\n    ")
+    )
+}
+
+/// Variable references (`{ $name }`) in Fluent source, without duplicates.
+fn template_variables(source: &str) -> Vec<String> {
+    static VARIABLE: LazyLock<regex::Regex> =
+        LazyLock::new(|| regex::Regex::new(r"\$([A-Za-z][A-Za-z0-9_-]*)").unwrap());
+
+    let mut variables: Vec<String> = Vec::new();
+    for cap in VARIABLE.captures_iter(source) {
+        if !variables.iter().any(|v| v == &cap[1]) {
+            variables.push(cap[1].to_string());
+        }
+    }
+    variables
+}
+
+fn fluent_value_to_string(value: &FluentValue) -> String {
+    match value {
+        FluentValue::String(s) => s.to_string(),
+        FluentValue::Number(n) => n.as_string().to_string(),
+        _ => String::new(),
+    }
 }
 
 /// Holds localization proxies for both the user and the guild
@@ -333,3 +501,31 @@ impl ContextL10nExt for crate::Context<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_variables() {
+        assert_eq!(
+            template_variables("<@{ $userId }> in {$channelId}, again { $userId }"),
+            vec!["userId".to_string(), "channelId".to_string()]
+        );
+        assert!(template_variables("no variables").is_empty());
+    }
+
+    #[test]
+    fn test_validate_template() {
+        let allowed = vec!["userId".to_string()];
+        assert!(LocalizationManager::validate_template("Hi <@{ $userId }>", &allowed).is_ok());
+        assert!(matches!(
+            LocalizationManager::validate_template("{ $channelId }", &allowed),
+            Err(TemplateError::UnknownVariable(var)) if var == "channelId"
+        ));
+        assert!(matches!(
+            LocalizationManager::validate_template("{ $userId", &allowed),
+            Err(TemplateError::Syntax)
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::Error;
+use crate::db::entities::guild_configs;
+use crate::db::entities::module_configs::{self, LoggingModuleConfig, ModuleType};
+use crate::services::config_cache::ConfigCacheService;
+use crate::services::localization::LocalizationManager;
+use crate::services::logger::LogLevel;
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    sea_query::Expr,
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Name of the channel created to replace a deleted log channel.
+const RECOVERY_CHANNEL_NAME: &str = "open-guard-logs";
+
+/// Recreates log channels that were deleted, typically as the first step of a nuke.
+///
+/// Recovery runs under a per-guild lock: the first failing log creates the replacement
+/// channel, repoints every config row at it and posts a notice; logs that failed in the
+/// meantime wait on the lock and are then flushed into the new channel.
+pub struct LogChannelHealer {
+    db: DatabaseConnection,
+    config_cache: Arc<ConfigCacheService>,
+    l10n: Arc<LocalizationManager>,
+    locks: DashMap<serenity::GuildId, Arc<Mutex<()>>>,
+    replacements: DashMap<serenity::ChannelId, serenity::ChannelId>,
+}
+
+impl LogChannelHealer {
+    pub fn new(
+        db: DatabaseConnection,
+        config_cache: Arc<ConfigCacheService>,
+        l10n: Arc<LocalizationManager>,
+    ) -> Self {
+        Self {
+            db,
+            config_cache,
+            l10n,
+            locks: DashMap::new(),
+            replacements: DashMap::new(),
+        }
+    }
+
+    /// Returns the channel that replaced `channel_id`, or `channel_id` itself.
+    /// Logs resolved before the config rows were updated still land in the new channel.
+    pub fn current(&self, channel_id: serenity::ChannelId) -> serenity::ChannelId {
+        let mut current = channel_id;
+        while let Some(next) = self.replacements.get(&current).map(|c| *c) {
+            current = next;
+        }
+        current
+    }
+
+    /// Replaces a deleted log channel and returns the new one.
+    /// If another task already replaced it, the existing replacement is returned.
+    pub async fn heal(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        lost_channel_id: serenity::ChannelId,
+    ) -> Result<serenity::ChannelId, Error> {
+        let lock = self
+            .locks
+            .entry(guild_id)
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+        let _guard = lock.lock().await;
+
+        if let Some(replacement) = self.replacements.get(&lost_channel_id).map(|c| *c) {
+            return Ok(self.current(replacement));
+        }
+
+        warn!(
+            "Log channel {} in guild {} is gone, recreating it",
+            lost_channel_id, guild_id
+        );
+
+        let bot_id = http.get_current_user().await?.id;
+
+        let overwrites = vec![
+            serenity::PermissionOverwrite {
+                allow: serenity::Permissions::empty(),
+                deny: serenity::Permissions::VIEW_CHANNEL,
+                kind: serenity::PermissionOverwriteType::Role(guild_id.everyone_role()),
+            },
+            serenity::PermissionOverwrite {
+                allow: serenity::Permissions::VIEW_CHANNEL
+                    | serenity::Permissions::SEND_MESSAGES
+                    | serenity::Permissions::EMBED_LINKS
+                    | serenity::Permissions::ATTACH_FILES
+                    | serenity::Permissions::MANAGE_WEBHOOKS,
+                deny: serenity::Permissions::empty(),
+                kind: serenity::PermissionOverwriteType::Member(bot_id),
+            },
+        ];
+
+        let channel = guild_id
+            .create_channel(
+                http,
+                serenity::CreateChannel::new(RECOVERY_CHANNEL_NAME)
+                    .kind(serenity::ChannelType::Text)
+                    .permissions(overwrites)
+                    .audit_log_reason("Log channel was deleted"),
+            )
+            .await?;
+        let new_channel_id = channel.id;
+
+        self.repoint_configs(guild_id, lost_channel_id, new_channel_id)
+            .await?;
+        self.replacements.insert(lost_channel_id, new_channel_id);
+
+        info!(
+            "Recreated log channel {} as {} in guild {}",
+            lost_channel_id, new_channel_id, guild_id
+        );
+
+        // Post the notice while still holding the lock so it precedes the flushed logs
+        if let Err(e) = self
+            .post_notice(http, guild_id, lost_channel_id, new_channel_id)
+            .await
+        {
+            warn!("Failed to post log channel recovery notice: {:?}", e);
+        }
+
+        Ok(new_channel_id)
+    }
+
+    /// Points every config row that referenced the lost channel at the new one.
+    async fn repoint_configs(
+        &self,
+        guild_id: serenity::GuildId,
+        lost: serenity::ChannelId,
+        new: serenity::ChannelId,
+    ) -> Result<(), Error> {
+        let lost = lost.get() as i64;
+        let new = new.get() as i64;
+
+        // Rows as they were, for the modules' config hooks
+        let old_modules = module_configs::Entity::find()
+            .filter(module_configs::Column::GuildId.eq(guild_id.get() as i64))
+            .all(&self.db)
+            .await?;
+
+        guild_configs::Entity::update_many()
+            .col_expr(guild_configs::Column::LogChannelId, Expr::value(new))
+            .filter(guild_configs::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(guild_configs::Column::LogChannelId.eq(lost))
+            .exec(&self.db)
+            .await?;
+
+        module_configs::Entity::update_many()
+            .col_expr(module_configs::Column::LogChannelId, Expr::value(new))
+            .filter(module_configs::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(module_configs::Column::LogChannelId.eq(lost))
+            .exec(&self.db)
+            .await?;
+
+        // Logging sub-channels live inside the module's JSON config
+        let logging = old_modules
+            .iter()
+            .find(|m| m.module_type == ModuleType::Logging);
+
+        if let Some(m) = logging {
+            let mut config: LoggingModuleConfig =
+                serde_json::from_value(m.config.clone()).unwrap_or_default();
+
+            let mut changed = false;
+            for channel in [
+                &mut config.message_log_channel_id,
+                &mut config.voice_log_channel_id,
+                &mut config.membership_log_channel_id,
+                &mut config.member_log_channel_id,
+                &mut config.server_log_channel_id,
+            ] {
+                if *channel == Some(lost) {
+                    *channel = Some(new);
+                    changed = true;
+                }
+            }
+
+            if changed {
+                let mut active: module_configs::ActiveModel = m.clone().into();
+                active.config = Set(serde_json::to_value(config)?);
+                active.update(&self.db).await?;
+            }
+        }
+
+        self.config_cache.invalidate_guild(guild_id);
+        for old in old_modules {
+            self.config_cache
+                .invalidate_module(guild_id, old.module_type, Some(old));
+        }
+
+        Ok(())
+    }
+
+    async fn post_notice(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        lost: serenity::ChannelId,
+        new: serenity::ChannelId,
+    ) -> Result<(), Error> {
+        let l10n = self.l10n.get_l10n_for_guild(guild_id, &self.db).await;
+
+        let mut args = fluent::FluentArgs::new();
+        args.set("oldChannelId", lost.get().to_string());
+
+        let level = LogLevel::Warn;
+        let message = serenity::CreateMessage::new()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(vec![
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!(
+                            "### {} {}",
+                            level.icon(),
+                            l10n.t("log-channel-recovered-title", None)
+                        )),
+                    ),
+                    serenity::CreateContainerComponent::Separator(serenity::CreateSeparator::new(
+                        true,
+                    )),
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(
+                            l10n.t("log-channel-recovered-desc", Some(&args)),
+                        ),
+                    ),
+                ])
+                .accent_color(level.color()),
+            )])
+            .allowed_mentions(serenity::CreateAllowedMentions::new());
+
+        http.send_message(new.into(), Vec::new(), &message).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use crate::Error;
+use crate::services::logger::LogRecord;
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// The file currently being appended to.
+struct OpenFile {
+    date: NaiveDate,
+    index: u32,
+    file: File,
+    size: u64,
+}
+
+/// Writes every log record as a JSON line, for a machine-readable audit trail.
+///
+/// Files are named `audit-YYYY-MM-DD.N.jsonl` and rotate daily, or earlier when a file
+/// reaches its size limit. The oldest files are removed once the directory exceeds
+/// its total size limit.
+///
+/// Configured through environment variables:
+/// - `LOG_EXPORT_DIR`: output directory; the sink is disabled if unset
+/// - `LOG_EXPORT_MAX_FILE_MB`: size at which a file is rotated (default 100)
+/// - `LOG_EXPORT_MAX_TOTAL_MB`: total size cap of the directory (default 10240)
+pub struct LogFileSink {
+    root: PathBuf,
+    max_file_bytes: u64,
+    max_total_bytes: u64,
+    current: Mutex<Option<OpenFile>>,
+}
+
+impl LogFileSink {
+    /// Returns the sink if `LOG_EXPORT_DIR` is set.
+    pub fn from_env() -> Option<Self> {
+        let root = std::env::var("LOG_EXPORT_DIR").ok().map(PathBuf::from)?;
+        let max_file_mb: u64 = std::env::var("LOG_EXPORT_MAX_FILE_MB")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(100);
+        let max_total_mb: u64 = std::env::var("LOG_EXPORT_MAX_TOTAL_MB")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10240);
+
+        info!("Exporting logs as JSON lines to {}", root.display());
+
+        Some(Self {
+            root,
+            max_file_bytes: max_file_mb * 1024 * 1024,
+            max_total_bytes: max_total_mb * 1024 * 1024,
+            current: Mutex::new(None),
+        })
+    }
+
+    /// Appends the record as one line, rotating the file first if needed.
+    pub async fn write(&self, record: &LogRecord) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut current = self.current.lock().await;
+        let today = record.timestamp.date_naive();
+
+        let needs_rotation = match current.as_ref() {
+            Some(open) => {
+                open.date != today || open.size + line.len() as u64 > self.max_file_bytes
+            }
+            None => true,
+        };
+
+        if needs_rotation {
+            let index = match current.as_ref() {
+                Some(open) if open.date == today => open.index + 1,
+                _ => self.next_index(today).await?,
+            };
+            *current = Some(self.open(today, index).await?);
+            self.enforce_total_limit().await;
+        }
+
+        let open = current.as_mut().expect("log file was just opened");
+        open.file.write_all(&line).await?;
+        open.file.flush().await?;
+        open.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    fn path_for(&self, date: NaiveDate, index: u32) -> PathBuf {
+        self.root.join(format!("audit-{}.{}.jsonl", date.format("%Y-%m-%d"), index))
+    }
+
+    /// Resumes the last file of the day; files are opened in append mode, so a restart
+    /// never overwrites anything.
+    async fn next_index(&self, date: NaiveDate) -> Result<u32, Error> {
+        let mut index = 0;
+        while fs::try_exists(self.path_for(date, index + 1)).await? {
+            index += 1;
+        }
+        Ok(index)
+    }
+
+    async fn open(&self, date: NaiveDate, index: u32) -> Result<OpenFile, Error> {
+        fs::create_dir_all(&self.root).await?;
+
+        let path = self.path_for(date, index);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        let size = file.metadata().await?.len();
+
+        Ok(OpenFile {
+            date,
+            index,
+            file,
+            size,
+        })
+    }
+
+    /// Deletes the oldest export files until the directory fits its size limit.
+    async fn enforce_total_limit(&self) {
+        let mut files = match list_exports(&self.root).await {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to list log export files: {:?}", e);
+                return;
+            }
+        };
+        files.sort_by_key(|(key, _, _)| *key);
+
+        let mut total: u64 = files.iter().map(|(_, _, size)| size).sum();
+        // Never delete the newest file, it is the one about to be written to
+        for (_, path, size) in files.iter().take(files.len().saturating_sub(1)) {
+            if total <= self.max_total_bytes {
+                break;
+            }
+            match fs::remove_file(path).await {
+                Ok(()) => {
+                    total -= size;
+                    info!("Removed old log export {}", path.display());
+                }
+                Err(e) => warn!("Failed to remove log export {}: {:?}", path.display(), e),
+            }
+        }
+    }
+}
+
+/// Lists export files with their date and index, so they can be ordered by age.
+async fn list_exports(root: &Path) -> Result<Vec<((NaiveDate, u32), PathBuf, u64)>, Error> {
+    let mut files = Vec::new();
+    let mut entries = fs::read_dir(root).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let Some(key) = parse_export_name(&name.to_string_lossy()) else {
+            continue;
+        };
+        files.push((key, entry.path(), entry.metadata().await?.len()));
+    }
+
+    Ok(files)
+}
+
+/// Parses `audit-YYYY-MM-DD.N.jsonl` into its date and index.
+fn parse_export_name(name: &str) -> Option<(NaiveDate, u32)> {
+    let stem = name.strip_prefix("audit-")?.strip_suffix(".jsonl")?;
+    let (date, index) = stem.split_once('.')?;
+    Some((
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+        index.parse().ok()?,
+    ))
+}
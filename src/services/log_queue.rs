@@ -0,0 +1,742 @@
+use crate::Error;
+use crate::db::entities::pending_logs;
+use crate::services::config_cache::ConfigCacheService;
+use crate::services::log_channel_healer::LogChannelHealer;
+use crate::services::log_webhooks::LogWebhookSink;
+use crate::services::logger::{LogLevel, LogRecord};
+use dashmap::{DashMap, DashSet};
+use poise::serenity_prelude as serenity;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    sea_query::Expr,
+};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, sleep, timeout_at};
+use tracing::{error, info, warn};
+
+/// Entries buffered in memory per log channel before enqueuing waits for the worker.
+const QUEUE_CAPACITY: usize = 256;
+/// How long a worker keeps collecting entries after the first one of a burst.
+const COALESCE_WINDOW: Duration = Duration::from_secs(2);
+/// Maximum entries collected into one batch.
+const MAX_BATCH: usize = 50;
+/// Maximum entries merged into one container.
+const MAX_COALESCED: usize = 10;
+/// Text budget per coalesced message; Discord allows 4000 characters per message.
+const MAX_TEXT_LEN: usize = 3500;
+/// Delivery attempts before an entry is dropped.
+const MAX_ATTEMPTS: i32 = 5;
+/// Upper bound for the retry backoff.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Background retries of a group whose delivery failed, before it waits for the next start.
+const MAX_RETRIES: u32 = 6;
+/// Delay before the first background retry; doubles with each further one.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Times a delivery may recreate a deleted log channel before giving up.
+const MAX_HEALS: u32 = 2;
+/// Undelivered entries older than this are discarded on startup.
+const MAX_PENDING_DAYS: i64 = 7;
+/// Discord's JSON error code for Unknown Channel.
+const UNKNOWN_CHANNEL: isize = 10003;
+
+/// A log entry waiting for delivery.
+pub struct LogEntry {
+    pub guild_id: serenity::GuildId,
+    pub channel_id: serenity::ChannelId,
+    pub level: LogLevel,
+    pub title: String,
+    pub description: String,
+    pub fields: Vec<(String, String)>,
+    pub accent_color: u32,
+    /// Files are only kept in memory; replayed entries are delivered without them.
+    pub files: Vec<serenity::CreateAttachment<'static>>,
+}
+
+impl LogEntry {
+    /// Prepares the Discord delivery of a record.
+    pub fn new(
+        channel_id: serenity::ChannelId,
+        record: &LogRecord,
+        files: Vec<serenity::CreateAttachment<'static>>,
+    ) -> Self {
+        Self {
+            guild_id: record.guild_id,
+            channel_id,
+            level: record.level,
+            title: record.title.text.clone(),
+            description: record.description.text.clone(),
+            fields: record
+                .fields
+                .iter()
+                .map(|(name, value)| (name.text.clone(), value.clone()))
+                .collect(),
+            accent_color: record.level.color(),
+            files,
+        }
+    }
+}
+
+/// A persisted entry, identified by its `pending_logs` row.
+struct QueuedEntry {
+    id: i64,
+    attempts: i32,
+    /// Background retries so far in this run
+    retries: u32,
+    entry: LogEntry,
+}
+
+/// The part of a group left to deliver after a failure, retried in the background.
+enum Retry {
+    /// Nothing was posted; the rows are still pending.
+    Group(Vec<QueuedEntry>),
+    /// The log message was posted and its rows removed; only the files are left.
+    Files {
+        guild_id: serenity::GuildId,
+        channel_id: serenity::ChannelId,
+        files: Vec<serenity::CreateAttachment<'static>>,
+        retries: u32,
+    },
+}
+
+/// Outcome of a failed delivery.
+enum Failure {
+    /// Rate limits, server errors and network issues; worth retrying.
+    Retryable(Error),
+    /// Anything else, such as missing permissions.
+    Permanent(Error),
+}
+
+/// Bounded per-channel log queue backed by the `pending_logs` table.
+///
+/// Each log channel gets its own worker, so a slow or rate limited channel does not hold
+/// back the others. Workers collect bursts for a short window and merge entries with the
+/// same level and title into one container. Failed deliveries are retried in the
+/// background with a growing delay. Rows are only deleted once delivered, so anything
+/// still pending after a crash, restart or the last retry is replayed on startup.
+pub struct LogQueue {
+    db: DatabaseConnection,
+    webhooks: LogWebhookSink,
+    healer: LogChannelHealer,
+    http: OnceLock<Arc<serenity::Http>>,
+    senders: DashMap<serenity::ChannelId, mpsc::Sender<QueuedEntry>>,
+    /// IDs of rows handed to a worker and not yet finished, so replays skip them
+    in_flight: DashSet<i64>,
+}
+
+impl LogQueue {
+    pub fn new(
+        db: DatabaseConnection,
+        config_cache: Arc<ConfigCacheService>,
+        healer: LogChannelHealer,
+    ) -> Self {
+        Self {
+            webhooks: LogWebhookSink::new(db.clone(), config_cache),
+            healer,
+            http: OnceLock::new(),
+            senders: DashMap::new(),
+            in_flight: DashSet::new(),
+            db,
+        }
+    }
+
+    /// Persists the entry and hands it to the channel's worker.
+    /// Waits if the channel's queue is full.
+    pub async fn enqueue(self: &Arc<Self>, entry: LogEntry) -> Result<(), Error> {
+        let fields = serde_json::to_value(&entry.fields)?;
+
+        let row = pending_logs::ActiveModel {
+            guild_id: Set(entry.guild_id.get() as i64),
+            channel_id: Set(entry.channel_id.get() as i64),
+            level: Set(entry.level.key().to_string()),
+            title: Set(entry.title.clone()),
+            description: Set(entry.description.clone()),
+            fields: Set(fields),
+            accent_color: Set(Some(entry.accent_color as i32)),
+            attempts: Set(0),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        // Before the runner starts the entry stays in the table and is replayed
+        if self.http.get().is_none() {
+            return Ok(());
+        }
+
+        self.push(QueuedEntry {
+            id: row.id,
+            attempts: 0,
+            retries: 0,
+            entry,
+        })
+        .await;
+
+        Ok(())
+    }
+
+    /// Replays entries left over from the previous run and enables delivery.
+    pub fn start_delivery_runner(self: Arc<Self>, http: Arc<serenity::Http>) {
+        tokio::spawn(async move {
+            // Entries enqueued from here on are pushed directly. Loading afterwards
+            // catches everything inserted before; rows pushed twice are skipped.
+            let _ = self.http.set(http);
+
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(MAX_PENDING_DAYS);
+            match pending_logs::Entity::delete_many()
+                .filter(pending_logs::Column::CreatedAt.lt(cutoff))
+                .exec(&self.db)
+                .await
+            {
+                Ok(res) if res.rows_affected > 0 => {
+                    warn!("Discarded {} expired pending log(s).", res.rows_affected)
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to discard expired pending logs: {:?}", e),
+            }
+
+            let pending = match pending_logs::Entity::find()
+                .order_by_asc(pending_logs::Column::Id)
+                .all(&self.db)
+                .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    error!("Failed to load pending logs: {:?}", e);
+                    Vec::new()
+                }
+            };
+
+            info!(
+                "Log delivery runner started, replaying {} pending log(s).",
+                pending.len()
+            );
+
+            for row in pending {
+                let Some(queued) = queued_from_row(row) else {
+                    continue;
+                };
+                self.push(queued).await;
+            }
+        });
+    }
+
+    async fn push(self: &Arc<Self>, queued: QueuedEntry) {
+        if !self.in_flight.insert(queued.id) {
+            return;
+        }
+
+        let channel_id = queued.entry.channel_id;
+        let sender = self
+            .senders
+            .entry(channel_id)
+            .or_insert_with(|| {
+                let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+                tokio::spawn(self.clone().run_worker(rx));
+                tx
+            })
+            .clone();
+
+        if let Err(mpsc::error::SendError(queued)) = sender.send(queued).await {
+            // Only happens if the worker panicked; the row is replayed on the next start
+            self.in_flight.remove(&queued.id);
+            self.senders.remove(&channel_id);
+            error!("Log worker for channel {} is gone", channel_id);
+        }
+    }
+
+    async fn run_worker(self: Arc<Self>, mut rx: mpsc::Receiver<QueuedEntry>) {
+        while let Some(first) = rx.recv().await {
+            let mut batch = vec![first];
+            let deadline = Instant::now() + COALESCE_WINDOW;
+
+            while batch.len() < MAX_BATCH {
+                match timeout_at(deadline, rx.recv()).await {
+                    Ok(Some(queued)) => batch.push(queued),
+                    _ => break,
+                }
+            }
+
+            for group in coalesce(batch) {
+                self.deliver_group(group).await;
+            }
+        }
+    }
+
+    /// Marks rows as no longer handled by a worker.
+    fn release(&self, group: &[QueuedEntry]) {
+        for queued in group {
+            self.in_flight.remove(&queued.id);
+        }
+    }
+
+    /// Delivers one group, retrying with backoff, and removes its rows once delivered.
+    /// Components V2 messages cannot show unreferenced attachments, so files are
+    /// sent as a plain follow-up message in the same channel. The rows are removed as
+    /// soon as the log message is out, so a failed file upload only retries the files.
+    async fn deliver_group(self: &Arc<Self>, mut group: Vec<QueuedEntry>) {
+        let Some(http) = self.http.get() else {
+            self.release(&group);
+            return;
+        };
+        let guild_id = group[0].entry.guild_id;
+        let mut channel_id = self.healer.current(group[0].entry.channel_id);
+        let mut attempts = group.iter().map(|q| q.attempts).max().unwrap_or(0);
+
+        // Entries with files are never coalesced, so the files belong to the first one
+        let files = std::mem::take(&mut group[0].entry.files);
+
+        let result = self
+            .deliver_with_retry(
+                http,
+                guild_id,
+                &mut channel_id,
+                &group,
+                &mut attempts,
+                render(&group),
+                Vec::new(),
+            )
+            .await;
+
+        if let Err(e) = result {
+            warn!(
+                "Failed to deliver {} log(s) to channel {}: {:?}",
+                group.len(),
+                channel_id,
+                e
+            );
+            group[0].entry.files = files;
+            self.retry_later(Retry::Group(group));
+            return;
+        }
+
+        let ids: Vec<i64> = group.iter().map(|q| q.id).collect();
+        if let Err(e) = pending_logs::Entity::delete_many()
+            .filter(pending_logs::Column::Id.is_in(ids))
+            .exec(&self.db)
+            .await
+        {
+            error!("Failed to remove delivered logs: {:?}", e);
+        }
+        self.release(&group);
+
+        if !files.is_empty() {
+            let retries = group[0].retries;
+            self.deliver_files(http, guild_id, channel_id, files, retries)
+                .await;
+        }
+    }
+
+    /// Sends the files of a log whose message was already posted.
+    async fn deliver_files(
+        self: &Arc<Self>,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        mut channel_id: serenity::ChannelId,
+        files: Vec<serenity::CreateAttachment<'static>>,
+        retries: u32,
+    ) {
+        let mut attempts = 0;
+        let result = self
+            .deliver_with_retry(
+                http,
+                guild_id,
+                &mut channel_id,
+                &[],
+                &mut attempts,
+                Vec::new(),
+                files.clone(),
+            )
+            .await;
+
+        if let Err(e) = result {
+            warn!(
+                "Failed to deliver log files to channel {}: {:?}",
+                channel_id, e
+            );
+            self.retry_later(Retry::Files {
+                guild_id,
+                channel_id,
+                files,
+                retries,
+            });
+        }
+    }
+
+    /// Tries the failed part again after a delay that doubles with every retry.
+    /// After `MAX_RETRIES`, pending rows are left for the next start and files dropped.
+    fn retry_later(self: &Arc<Self>, retry: Retry) {
+        let retries = match &retry {
+            Retry::Group(group) => group.iter().map(|q| q.retries).max().unwrap_or(0),
+            Retry::Files { retries, .. } => *retries,
+        };
+
+        if retries >= MAX_RETRIES {
+            match retry {
+                Retry::Group(group) => {
+                    warn!(
+                        "Giving up on {} log(s) for now, keeping them for the next start",
+                        group.len()
+                    );
+                    self.release(&group);
+                }
+                Retry::Files { channel_id, .. } => {
+                    warn!("Giving up on log files for channel {}", channel_id);
+                }
+            }
+            return;
+        }
+
+        let queue = self.clone();
+        tokio::spawn(async move {
+            sleep(RETRY_DELAY * 2u32.pow(retries)).await;
+
+            match retry {
+                Retry::Group(group) => {
+                    queue.release(&group);
+                    for mut queued in group {
+                        queued.retries = retries + 1;
+                        queue.push(queued).await;
+                    }
+                }
+                Retry::Files {
+                    guild_id,
+                    channel_id,
+                    files,
+                    ..
+                } => {
+                    let Some(http) = queue.http.get() else {
+                        return;
+                    };
+                    queue
+                        .deliver_files(http, guild_id, channel_id, files, retries + 1)
+                        .await;
+                }
+            }
+        });
+    }
+
+    /// Retries rate limited and transient failures with exponential backoff.
+    /// A deleted log channel is recreated and the message sent to the new one,
+    /// at most `MAX_HEALS` times.
+    async fn deliver_with_retry(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        channel_id: &mut serenity::ChannelId,
+        group: &[QueuedEntry],
+        attempts: &mut i32,
+        components: Vec<serenity::CreateComponent<'static>>,
+        files: Vec<serenity::CreateAttachment<'static>>,
+    ) -> Result<(), Error> {
+        let mut heals = 0;
+        loop {
+            let result = self
+                .deliver(http, guild_id, *channel_id, components.clone(), files.clone())
+                .await;
+
+            match result {
+                Ok(()) => return Ok(()),
+                Err(Failure::Permanent(e)) if is_unknown_channel(&e) => {
+                    heals += 1;
+                    if heals > MAX_HEALS {
+                        return Err(e);
+                    }
+                    *channel_id = self.healer.heal(http, guild_id, *channel_id).await?;
+                }
+                Err(Failure::Permanent(e)) => return Err(e),
+                Err(Failure::Retryable(e)) => {
+                    *attempts += 1;
+                    if *attempts >= MAX_ATTEMPTS {
+                        return Err(e);
+                    }
+
+                    let backoff = Duration::from_secs(1 << *attempts).min(MAX_BACKOFF);
+                    warn!(
+                        "Log delivery to channel {} failed (attempt {}), retrying in {:?}: {:?}",
+                        channel_id, attempts, backoff, e
+                    );
+                    self.record_attempt(group, *attempts).await;
+                    sleep(backoff).await;
+                }
+            }
+        }
+    }
+
+    async fn record_attempt(&self, group: &[QueuedEntry], attempts: i32) {
+        if group.is_empty() {
+            return;
+        }
+        let ids: Vec<i64> = group.iter().map(|q| q.id).collect();
+        if let Err(e) = pending_logs::Entity::update_many()
+            .col_expr(pending_logs::Column::Attempts, Expr::value(attempts))
+            .filter(pending_logs::Column::Id.is_in(ids))
+            .exec(&self.db)
+            .await
+        {
+            error!("Failed to record log delivery attempt: {:?}", e);
+        }
+    }
+
+    /// Sends a message to a log channel, through the channel's webhook if enabled.
+    /// Falls back to a regular bot message if the webhook cannot be used.
+    async fn deliver(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        channel_id: serenity::ChannelId,
+        components: Vec<serenity::CreateComponent<'static>>,
+        files: Vec<serenity::CreateAttachment<'static>>,
+    ) -> Result<(), Failure> {
+        let use_webhook = self
+            .webhooks
+            .is_enabled(guild_id)
+            .await
+            .map_err(Failure::Retryable)?;
+
+        if use_webhook {
+            let mut builder = serenity::ExecuteWebhook::new()
+                .allowed_mentions(serenity::CreateAllowedMentions::new());
+            if !components.is_empty() {
+                builder = builder
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .components(components.clone());
+            }
+            if !files.is_empty() {
+                builder = builder.files(files.clone());
+            }
+
+            match self.webhooks.send(http, guild_id, channel_id, builder).await {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!(
+                    "Webhook log delivery failed for channel {}, falling back to bot message: {:?}",
+                    channel_id, e
+                ),
+            }
+        }
+
+        let mut message =
+            serenity::CreateMessage::new().allowed_mentions(serenity::CreateAllowedMentions::new());
+        if !components.is_empty() {
+            message = message
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .components(components);
+        }
+
+        match http.send_message(channel_id.into(), files, &message).await {
+            Ok(_) => Ok(()),
+            Err(e) if is_retryable(&e) => Err(Failure::Retryable(e.into())),
+            Err(e) => Err(Failure::Permanent(e.into())),
+        }
+    }
+}
+
+fn queued_from_row(row: pending_logs::Model) -> Option<QueuedEntry> {
+    let Some(level) = LogLevel::from_key(&row.level) else {
+        warn!("Skipping pending log {} with unknown level", row.id);
+        return None;
+    };
+
+    Some(QueuedEntry {
+        id: row.id,
+        attempts: row.attempts,
+        retries: 0,
+        entry: LogEntry {
+            guild_id: serenity::GuildId::new(row.guild_id as u64),
+            channel_id: serenity::ChannelId::new(row.channel_id as u64),
+            level,
+            title: row.title,
+            description: row.description,
+            fields: serde_json::from_value(row.fields).unwrap_or_default(),
+            accent_color: row
+                .accent_color
+                .map(|c| c as u32)
+                .unwrap_or_else(|| level.color()),
+            files: Vec::new(),
+        },
+    })
+}
+
+/// Splits a batch into messages. Entries with the same level and title are merged,
+/// in order of first appearance; entries with files are always sent on their own.
+fn coalesce(batch: Vec<QueuedEntry>) -> Vec<Vec<QueuedEntry>> {
+    let mut groups: Vec<Vec<QueuedEntry>> = Vec::new();
+
+    for queued in batch {
+        let text_len = entry_text(&queued.entry).len();
+        let target = groups.iter_mut().find(|g| {
+            let head = &g[0].entry;
+            queued.entry.files.is_empty()
+                && head.files.is_empty()
+                && head.level == queued.entry.level
+                && head.accent_color == queued.entry.accent_color
+                && head.title == queued.entry.title
+                && g.len() < MAX_COALESCED
+                && g.iter().map(|q| entry_text(&q.entry).len()).sum::<usize>() + text_len
+                    <= MAX_TEXT_LEN
+        });
+
+        match target {
+            Some(group) => group.push(queued),
+            None => groups.push(vec![queued]),
+        }
+    }
+
+    groups
+}
+
+/// Builds the container for a group. A single entry keeps the regular log layout.
+fn render(group: &[QueuedEntry]) -> Vec<serenity::CreateComponent<'static>> {
+    let head = &group[0].entry;
+    let mut inner_components = vec![];
+
+    let title = if group.len() > 1 {
+        format!("### {} {} ×{}", head.level.icon(), head.title, group.len())
+    } else {
+        format!("### {} {}", head.level.icon(), head.title)
+    };
+    inner_components.push(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(title),
+    ));
+
+    inner_components.push(serenity::CreateContainerComponent::Separator(
+        serenity::CreateSeparator::new(true),
+    ));
+
+    if group.len() == 1 {
+        inner_components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(head.description.clone()),
+        ));
+
+        if !head.fields.is_empty() {
+            inner_components.push(serenity::CreateContainerComponent::Separator(
+                serenity::CreateSeparator::new(false),
+            ));
+
+            for (name, value) in &head.fields {
+                inner_components.push(serenity::CreateContainerComponent::TextDisplay(
+                    serenity::CreateTextDisplay::new(format!("> **{}**\n> {}", name, value)),
+                ));
+            }
+        }
+    } else {
+        for (idx, queued) in group.iter().enumerate() {
+            if idx > 0 {
+                inner_components.push(serenity::CreateContainerComponent::Separator(
+                    serenity::CreateSeparator::new(false),
+                ));
+            }
+            inner_components.push(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(entry_text(&queued.entry)),
+            ));
+        }
+    }
+
+    vec![serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(inner_components).accent_color(head.accent_color),
+    )]
+}
+
+/// Compact rendering of an entry inside a coalesced container.
+fn entry_text(entry: &LogEntry) -> String {
+    let mut text = entry.description.clone();
+    for (name, value) in &entry.fields {
+        text.push_str(&format!("\n> **{}**: {}", name, value));
+    }
+    text
+}
+
+fn is_retryable(err: &serenity::Error) -> bool {
+    match err {
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) => {
+            let status = response.status_code.as_u16();
+            status == 429 || status >= 500
+        }
+        serenity::Error::Http(serenity::HttpError::Request(_)) => true,
+        _ => false,
+    }
+}
+
+/// Returns true if the error is Discord reporting that the channel no longer exists.
+fn is_unknown_channel(err: &Error) -> bool {
+    match err.downcast_ref::<serenity::Error>() {
+        Some(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response))) => {
+            response.error.code == UNKNOWN_CHANNEL
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(id: i64, level: LogLevel, title: &str, description: &str) -> QueuedEntry {
+        QueuedEntry {
+            id,
+            attempts: 0,
+            retries: 0,
+            entry: LogEntry {
+                guild_id: serenity::GuildId::new(1),
+                channel_id: serenity::ChannelId::new(2),
+                level,
+                title: title.to_string(),
+                description: description.to_string(),
+                fields: vec![],
+                accent_color: level.color(),
+                files: vec![],
+            },
+        }
+    }
+
+    fn ids(groups: &[Vec<QueuedEntry>]) -> Vec<Vec<i64>> {
+        groups
+            .iter()
+            .map(|g| g.iter().map(|q| q.id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_coalesce_same_title() {
+        let batch = vec![
+            queued(1, LogLevel::Warn, "Deleted", "a"),
+            queued(2, LogLevel::Info, "Deleted", "b"),
+            queued(3, LogLevel::Warn, "Created", "c"),
+            queued(4, LogLevel::Warn, "Deleted", "d"),
+        ];
+        assert_eq!(ids(&coalesce(batch)), vec![vec![1, 4], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_coalesce_limits() {
+        let batch = (0..MAX_COALESCED as i64 + 1)
+            .map(|id| queued(id, LogLevel::Info, "Deleted", "x"))
+            .collect();
+        let groups = coalesce(batch);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), MAX_COALESCED);
+
+        let long = "x".repeat(MAX_TEXT_LEN / 2 + 1);
+        let batch = vec![
+            queued(1, LogLevel::Info, "Deleted", &long),
+            queued(2, LogLevel::Info, "Deleted", &long),
+        ];
+        assert_eq!(ids(&coalesce(batch)), vec![vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_coalesce_files_alone() {
+        let mut with_file = queued(2, LogLevel::Info, "Deleted", "b");
+        with_file.entry.files = vec![serenity::CreateAttachment::bytes(
+            vec![0u8],
+            "a.txt".to_string(),
+        )];
+        let batch = vec![
+            queued(1, LogLevel::Info, "Deleted", "a"),
+            with_file,
+            queued(3, LogLevel::Info, "Deleted", "c"),
+        ];
+        assert_eq!(ids(&coalesce(batch)), vec![vec![1, 3], vec![2]]);
+    }
+}
@@ -0,0 +1,835 @@
+use crate::Error;
+use crate::db::entities::{log_level_colors, log_templates};
+use crate::services::config_cache::spawn_guild_listener;
+use crate::services::localization::{LocalizationManager, TemplateError};
+use crate::services::log_queue::LogEntry;
+use crate::services::logger::{LogLevel, LogRecord};
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Postgres channel the template tables notify on; the payload is the guild id.
+/// The triggers are created by the `m000025_add_log_template_notify_triggers` migration.
+pub const LOG_TEMPLATES_NOTIFY_CHANNEL: &str = "log_templates_changed";
+
+/// How long a guild's templates are reused, in case a notification was missed.
+const CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Groups used to split the log types in the config UI (select menus hold 25 options).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogTypeGroup {
+    Protection,
+    Moderation,
+    Logging,
+}
+
+impl LogTypeGroup {
+    pub const ALL: [LogTypeGroup; 3] = [
+        LogTypeGroup::Protection,
+        LogTypeGroup::Moderation,
+        LogTypeGroup::Logging,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            LogTypeGroup::Protection => "protection",
+            LogTypeGroup::Moderation => "moderation",
+            LogTypeGroup::Logging => "logging",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|g| g.key() == key)
+    }
+
+    pub fn l10n_key(&self) -> &'static str {
+        match self {
+            LogTypeGroup::Protection => "config-logtpl-group-protection",
+            LogTypeGroup::Moderation => "config-logtpl-group-moderation",
+            LogTypeGroup::Logging => "config-logtpl-group-logging",
+        }
+    }
+}
+
+/// A kind of log entry whose texts and fields can be customized.
+pub struct LogType {
+    /// Description key, which identifies the log type
+    pub key: &'static str,
+    pub group: LogTypeGroup,
+    /// Title keys the log type is shown with
+    pub title_keys: &'static [&'static str],
+    /// Field name keys the log type may include
+    pub fields: &'static [&'static str],
+}
+
+impl LogType {
+    /// Every customizable key: the titles, then the description.
+    pub fn keys(&self) -> Vec<&'static str> {
+        let mut keys = self.title_keys.to_vec();
+        keys.push(self.key);
+        keys
+    }
+
+    /// Variables the default messages of this log type use; templates may use any of them.
+    pub fn variables(&self, l10n: &LocalizationManager) -> Vec<String> {
+        let mut variables: Vec<String> = Vec::new();
+        for key in self.keys() {
+            for var in l10n.message_variables(key) {
+                if !variables.contains(&var) {
+                    variables.push(var);
+                }
+            }
+        }
+        variables
+    }
+}
+
+const CHANNEL_TITLES: &[&str] = &[
+    "log-chan-title-whitelisted",
+    "log-chan-title-blocked",
+    "log-chan-title-logged",
+];
+const CHAN_PERM_TITLES: &[&str] = &[
+    "log-chan-perm-title-whitelisted",
+    "log-chan-perm-title-blocked",
+    "log-chan-perm-title-logged",
+];
+const ROLE_TITLES: &[&str] = &[
+    "log-role-title-whitelisted",
+    "log-role-title-blocked",
+    "log-role-title-logged",
+];
+const MOD_COMMAND_FIELDS: &[&str] = &[
+    "config-log-field-user",
+    "config-log-field-channel",
+    "log-field-user",
+    "log-field-duration",
+    "log-field-reason",
+];
+
+pub const LOG_TYPES: &[LogType] = &[
+    // Protection modules
+    LogType {
+        key: "log-chan-desc-create",
+        group: LogTypeGroup::Protection,
+        title_keys: CHANNEL_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-channel",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-chan-desc-delete",
+        group: LogTypeGroup::Protection,
+        title_keys: CHANNEL_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-channel-id",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-chan-desc-update",
+        group: LogTypeGroup::Protection,
+        title_keys: CHANNEL_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-channel",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-chan-perm-desc-create",
+        group: LogTypeGroup::Protection,
+        title_keys: CHAN_PERM_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-channel",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-chan-perm-desc-delete",
+        group: LogTypeGroup::Protection,
+        title_keys: CHAN_PERM_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-channel",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-chan-perm-desc-update",
+        group: LogTypeGroup::Protection,
+        title_keys: CHAN_PERM_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-channel",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-role-desc-create",
+        group: LogTypeGroup::Protection,
+        title_keys: ROLE_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-role-id",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-role-desc-delete",
+        group: LogTypeGroup::Protection,
+        title_keys: ROLE_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-role-id",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-role-desc-update",
+        group: LogTypeGroup::Protection,
+        title_keys: ROLE_TITLES,
+        fields: &[
+            "log-field-user",
+            "log-field-role",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-role-perm-desc-update",
+        group: LogTypeGroup::Protection,
+        title_keys: &[
+            "log-role-perm-title-whitelisted",
+            "log-role-perm-title-blocked",
+            "log-role-perm-title-logged",
+        ],
+        fields: &[
+            "log-field-user",
+            "log-field-role",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-member-perm-desc",
+        group: LogTypeGroup::Protection,
+        title_keys: &[
+            "log-member-perm-title-whitelisted",
+            "log-member-perm-title-blocked",
+        ],
+        fields: &[
+            "log-field-acting-user",
+            "log-field-target-member",
+            "log-field-added-perms",
+            "log-field-action-status",
+        ],
+    },
+    LogType {
+        key: "log-bot-add-desc",
+        group: LogTypeGroup::Protection,
+        title_keys: &["log-bot-add-title-whitelisted", "log-bot-add-title-blocked"],
+        fields: &["log-field-acting-user", "log-field-action-status"],
+    },
+    LogType {
+        key: "log-mod-audit-desc",
+        group: LogTypeGroup::Protection,
+        title_keys: &[
+            "log-mod-audit-title-whitelisted",
+            "log-mod-audit-title-limited",
+            "log-mod-audit-title-logged",
+        ],
+        fields: &[
+            "log-field-user",
+            "log-field-target-member",
+            "log-field-action-status",
+        ],
+    },
+    // Moderation commands and punishments
+    LogType {
+        key: "log-mod-ban-cmd-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-ban-cmd-title"],
+        fields: MOD_COMMAND_FIELDS,
+    },
+    LogType {
+        key: "log-mod-kick-cmd-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-kick-cmd-title"],
+        fields: &[
+            "config-log-field-user",
+            "config-log-field-channel",
+            "log-field-user",
+            "log-field-reason",
+        ],
+    },
+    LogType {
+        key: "log-mod-jail-cmd-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-jail-cmd-title"],
+        fields: MOD_COMMAND_FIELDS,
+    },
+    LogType {
+        key: "log-mod-timeout-cmd-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-timeout-cmd-title"],
+        fields: MOD_COMMAND_FIELDS,
+    },
+    LogType {
+        key: "log-mod-unjail-cmd-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-unjail-cmd-title"],
+        fields: &[
+            "config-log-field-user",
+            "config-log-field-channel",
+            "log-field-user",
+        ],
+    },
+    LogType {
+        key: "log-mod-punish-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-punish-title"],
+        fields: &["log-field-user", "log-field-type", "log-field-reason"],
+    },
+    LogType {
+        key: "log-mod-jail-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-jail-title"],
+        fields: &["log-field-user", "log-field-duration", "log-field-reason"],
+    },
+    LogType {
+        key: "log-mod-unjail-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-unjail-title"],
+        fields: &["log-field-user"],
+    },
+    LogType {
+        key: "log-mod-unban-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-mod-unban-title"],
+        fields: &["log-field-user", "log-field-reason"],
+    },
+    LogType {
+        key: "log-whitelist-expired-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-whitelist-expired-title"],
+        fields: &[],
+    },
+    LogType {
+        key: "log-whitelist-change-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &[
+            "log-whitelist-change-title-create",
+            "log-whitelist-change-title-update",
+            "log-whitelist-change-title-delete",
+        ],
+        fields: &["log-field-before", "log-field-after"],
+    },
+    LogType {
+        key: "log-approval-desc",
+        group: LogTypeGroup::Moderation,
+        title_keys: &["log-approval-title-approved", "log-approval-title-rejected"],
+        fields: &["log-field-action-status"],
+    },
+    // Logging module
+    LogType {
+        key: "log-msg-delete-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-msg-delete-title"],
+        fields: &["log-msg-delete-content", "log-msg-delete-attachments"],
+    },
+    LogType {
+        key: "log-msg-edit-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-msg-edit-title"],
+        fields: &["log-msg-edit-before", "log-msg-edit-after"],
+    },
+    LogType {
+        key: "log-member-join-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-join-title"],
+        fields: &[],
+    },
+    LogType {
+        key: "log-member-leave-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-leave-title"],
+        fields: &["log-member-leave-roles"],
+    },
+    LogType {
+        key: "log-member-nick-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-nick-title"],
+        fields: &[
+            "log-field-acting-user",
+            "log-field-before",
+            "log-field-after",
+        ],
+    },
+    LogType {
+        key: "log-member-avatar-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-avatar-title"],
+        fields: &["log-field-before", "log-field-after"],
+    },
+    LogType {
+        key: "log-member-roles-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-roles-title"],
+        fields: &[
+            "log-field-acting-user",
+            "log-member-roles-added",
+            "log-member-roles-removed",
+        ],
+    },
+    LogType {
+        key: "log-member-timeout-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-timeout-title"],
+        fields: &[
+            "log-field-acting-user",
+            "log-field-until",
+            "log-field-reason",
+        ],
+    },
+    LogType {
+        key: "log-member-timeout-removed-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-member-timeout-removed-title"],
+        fields: &["log-field-acting-user", "log-field-reason"],
+    },
+    LogType {
+        key: "log-voice-join-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-voice-join-title"],
+        fields: &[],
+    },
+    LogType {
+        key: "log-voice-leave-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-voice-leave-title"],
+        fields: &["log-field-duration"],
+    },
+    LogType {
+        key: "log-voice-move-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-voice-move-title"],
+        fields: &[],
+    },
+    LogType {
+        key: "log-voice-state-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-voice-state-title"],
+        fields: &[],
+    },
+    LogType {
+        key: "log-server-desc",
+        group: LogTypeGroup::Logging,
+        title_keys: &["log-server-title"],
+        fields: &[
+            "log-field-acting-user",
+            "log-server-changes",
+            "log-field-reason",
+        ],
+    },
+];
+
+pub fn find_log_type(key: &str) -> Option<&'static LogType> {
+    LOG_TYPES.iter().find(|t| t.key == key)
+}
+
+/// A guild's customization of one log type.
+#[derive(Default, Clone)]
+pub struct TypeTemplate {
+    /// Fluent key -> template
+    pub overrides: HashMap<String, String>,
+    /// Fluent keys of the field names to leave out
+    pub hidden_fields: Vec<String>,
+}
+
+/// Everything a guild customized, loaded at once and cached.
+#[derive(Default)]
+pub struct GuildTemplates {
+    pub types: HashMap<String, TypeTemplate>,
+    /// `LogLevel` key -> accent color
+    pub colors: HashMap<String, u32>,
+}
+
+impl GuildTemplates {
+    pub fn color(&self, level: LogLevel) -> u32 {
+        self.colors
+            .get(level.key())
+            .copied()
+            .unwrap_or_else(|| level.color())
+    }
+}
+
+/// Per-guild log templates, field selection and level colors.
+/// Applied to Discord deliveries only; other sinks keep the original record.
+pub struct LogTemplateService {
+    db: DatabaseConnection,
+    l10n: Arc<LocalizationManager>,
+    /// Map: GuildID -> (templates, Timestamp)
+    cache: Arc<DashMap<serenity::GuildId, (Arc<GuildTemplates>, Instant)>>,
+}
+
+impl LogTemplateService {
+    pub fn new(db: DatabaseConnection, l10n: Arc<LocalizationManager>) -> Self {
+        Self {
+            db,
+            l10n,
+            cache: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Starts listening for template changes made by other processes or by hand.
+    pub fn start_listener(&self) {
+        let notified = Arc::clone(&self.cache);
+        let lost = Arc::clone(&self.cache);
+        spawn_guild_listener(
+            &self.db,
+            LOG_TEMPLATES_NOTIFY_CHANNEL,
+            move |guild_id| {
+                notified.remove(&guild_id);
+            },
+            move || lost.clear(),
+        );
+    }
+
+    pub async fn get(&self, guild_id: serenity::GuildId) -> Result<Arc<GuildTemplates>, Error> {
+        if let Some(cached) = self.cache.get(&guild_id) {
+            let (templates, loaded_at) = cached.value();
+            if loaded_at.elapsed() < CACHE_TTL {
+                return Ok(templates.clone());
+            }
+        }
+
+        let rows = log_templates::Entity::find()
+            .filter(log_templates::Column::GuildId.eq(guild_id.get() as i64))
+            .all(&self.db)
+            .await?;
+        let colors = log_level_colors::Entity::find()
+            .filter(log_level_colors::Column::GuildId.eq(guild_id.get() as i64))
+            .all(&self.db)
+            .await?;
+
+        let templates = Arc::new(GuildTemplates {
+            types: rows
+                .into_iter()
+                .map(|row| {
+                    (
+                        row.log_type,
+                        TypeTemplate {
+                            overrides: serde_json::from_value(row.overrides).unwrap_or_default(),
+                            hidden_fields: serde_json::from_value(row.hidden_fields)
+                                .unwrap_or_default(),
+                        },
+                    )
+                })
+                .collect(),
+            colors: colors
+                .into_iter()
+                .map(|row| (row.level, row.color as u32))
+                .collect(),
+        });
+
+        self.cache
+            .insert(guild_id, (templates.clone(), Instant::now()));
+        Ok(templates)
+    }
+
+    /// Applies the guild's templates, hidden fields and colors to a Discord delivery.
+    pub async fn apply(&self, record: &LogRecord, entry: &mut LogEntry) -> Result<(), Error> {
+        let templates = self.get(record.guild_id).await?;
+        apply_templates(&self.l10n, &templates, record, entry);
+        Ok(())
+    }
+
+    /// Checks a template for one of the log type's keys.
+    pub fn validate(&self, log_type: &LogType, source: &str) -> Result<(), TemplateError> {
+        LocalizationManager::validate_template(source, &log_type.variables(&self.l10n))
+    }
+
+    /// Replaces the template overrides of a log type. Empty templates are dropped.
+    /// Callers validate every template first.
+    pub async fn save_overrides(
+        &self,
+        guild_id: serenity::GuildId,
+        log_type: &LogType,
+        overrides: HashMap<String, String>,
+    ) -> Result<(), Error> {
+        let overrides: HashMap<String, String> = overrides
+            .into_iter()
+            .filter(|(key, source)| {
+                log_type.keys().contains(&key.as_str()) && !source.trim().is_empty()
+            })
+            .collect();
+
+        log_templates::Entity::insert(log_templates::ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            log_type: Set(log_type.key.to_string()),
+            overrides: Set(serde_json::to_value(overrides)?),
+            hidden_fields: Set(serde_json::json!([])),
+        })
+        .on_conflict(
+            sea_orm::sea_query::OnConflict::columns([
+                log_templates::Column::GuildId,
+                log_templates::Column::LogType,
+            ])
+            .update_column(log_templates::Column::Overrides)
+            .to_owned(),
+        )
+        .exec(&self.db)
+        .await?;
+
+        self.cache.remove(&guild_id);
+        Ok(())
+    }
+
+    /// Stores which of the log type's fields are hidden.
+    pub async fn save_hidden_fields(
+        &self,
+        guild_id: serenity::GuildId,
+        log_type: &LogType,
+        hidden_fields: Vec<String>,
+    ) -> Result<(), Error> {
+        let hidden_fields: Vec<String> = hidden_fields
+            .into_iter()
+            .filter(|key| log_type.fields.contains(&key.as_str()))
+            .collect();
+
+        log_templates::Entity::insert(log_templates::ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            log_type: Set(log_type.key.to_string()),
+            overrides: Set(serde_json::json!({})),
+            hidden_fields: Set(serde_json::to_value(hidden_fields)?),
+        })
+        .on_conflict(
+            sea_orm::sea_query::OnConflict::columns([
+                log_templates::Column::GuildId,
+                log_templates::Column::LogType,
+            ])
+            .update_column(log_templates::Column::HiddenFields)
+            .to_owned(),
+        )
+        .exec(&self.db)
+        .await?;
+
+        self.cache.remove(&guild_id);
+        Ok(())
+    }
+
+    /// Restores the default texts and fields of a log type.
+    pub async fn reset(
+        &self,
+        guild_id: serenity::GuildId,
+        log_type: &LogType,
+    ) -> Result<(), Error> {
+        log_templates::Entity::delete_many()
+            .filter(log_templates::Column::GuildId.eq(guild_id.get() as i64))
+            .filter(log_templates::Column::LogType.eq(log_type.key))
+            .exec(&self.db)
+            .await?;
+
+        self.cache.remove(&guild_id);
+        Ok(())
+    }
+
+    /// Stores the accent color of each level; `None` restores the default.
+    pub async fn save_colors(
+        &self,
+        guild_id: serenity::GuildId,
+        colors: Vec<(LogLevel, Option<u32>)>,
+    ) -> Result<(), Error> {
+        for (level, color) in colors {
+            match color {
+                Some(color) => {
+                    log_level_colors::Entity::insert(log_level_colors::ActiveModel {
+                        guild_id: Set(guild_id.get() as i64),
+                        level: Set(level.key().to_string()),
+                        color: Set(color as i32),
+                    })
+                    .on_conflict(
+                        sea_orm::sea_query::OnConflict::columns([
+                            log_level_colors::Column::GuildId,
+                            log_level_colors::Column::Level,
+                        ])
+                        .update_column(log_level_colors::Column::Color)
+                        .to_owned(),
+                    )
+                    .exec(&self.db)
+                    .await?;
+                }
+                None => {
+                    log_level_colors::Entity::delete_many()
+                        .filter(log_level_colors::Column::GuildId.eq(guild_id.get() as i64))
+                        .filter(log_level_colors::Column::Level.eq(level.key()))
+                        .exec(&self.db)
+                        .await?;
+                }
+            }
+        }
+
+        self.cache.remove(&guild_id);
+        Ok(())
+    }
+}
+
+/// Applies loaded templates to the delivery of a record. Templates that fail to render
+/// leave the default text in place.
+fn apply_templates(
+    l10n: &LocalizationManager,
+    templates: &GuildTemplates,
+    record: &LogRecord,
+    entry: &mut LogEntry,
+) {
+    entry.accent_color = templates.color(record.level);
+
+    let Some(template) = templates.types.get(&record.description.key) else {
+        return;
+    };
+
+    if let Some(source) = template.overrides.get(&record.title.key) {
+        match l10n.render_template(&record.title.locale, source, &record.title.args) {
+            Some(text) => entry.title = text,
+            None => warn!("Failed to render log template {}", record.title.key),
+        }
+    }
+    if let Some(source) = template.overrides.get(&record.description.key) {
+        match l10n.render_template(&record.description.locale, source, &record.description.args) {
+            Some(text) => entry.description = text,
+            None => warn!("Failed to render log template {}", record.description.key),
+        }
+    }
+
+    if !template.hidden_fields.is_empty() {
+        entry.fields = record
+            .fields
+            .iter()
+            .filter(|(name, _)| !template.hidden_fields.contains(&name.key))
+            .map(|(name, value)| (name.text.clone(), value.clone()))
+            .collect();
+    }
+}
+
+/// Parses `#rrggbb` or `rrggbb`.
+pub fn parse_hex_color(input: &str) -> Option<u32> {
+    let hex = input.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::localization::LocalizedText;
+
+    fn record(l10n: &Arc<LocalizationManager>) -> LogRecord {
+        let proxy = l10n.get_proxy("en-US");
+        let mut args = fluent::FluentArgs::new();
+        args.set("userId", "42");
+        LogRecord {
+            guild_id: serenity::GuildId::new(1),
+            module: None,
+            level: LogLevel::Warn,
+            title: proxy.text("log-mod-punish-title", None),
+            description: proxy.text("log-mod-punish-desc", Some(&args)),
+            fields: vec![
+                (proxy.text("log-field-user", None), "<@42>".to_string()),
+                (proxy.text("log-field-reason", None), "spam".to_string()),
+                // Same name as a translated field, but not the field itself
+                (LocalizedText::plain("User"), "plain".to_string()),
+            ],
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn templates(overrides: &[(&str, &str)], hidden_fields: &[&str]) -> GuildTemplates {
+        let template = TypeTemplate {
+            overrides: overrides
+                .iter()
+                .map(|(key, source)| (key.to_string(), source.to_string()))
+                .collect(),
+            hidden_fields: hidden_fields.iter().map(|key| key.to_string()).collect(),
+        };
+        GuildTemplates {
+            types: HashMap::from([("log-mod-punish-desc".to_string(), template)]),
+            colors: HashMap::new(),
+        }
+    }
+
+    fn apply(templates: &GuildTemplates) -> LogEntry {
+        let l10n = Arc::new(LocalizationManager::new());
+        let record = record(&l10n);
+        let mut entry = LogEntry::new(serenity::ChannelId::new(2), &record, vec![]);
+        apply_templates(&l10n, templates, &record, &mut entry);
+        entry
+    }
+
+    #[test]
+    fn test_apply_without_template() {
+        let entry = apply(&GuildTemplates::default());
+        assert_eq!(entry.title, "Automated Punishment Applied");
+        assert_eq!(entry.accent_color, LogLevel::Warn.color());
+        assert_eq!(entry.fields.len(), 3);
+    }
+
+    #[test]
+    fn test_apply_overrides_title_and_description() {
+        let entry = apply(&templates(
+            &[
+                ("log-mod-punish-title", "Punished"),
+                ("log-mod-punish-desc", "<@{ $userId }> was punished"),
+            ],
+            &[],
+        ));
+        assert_eq!(entry.title, "Punished");
+        assert_eq!(entry.description, "<@42> was punished");
+    }
+
+    #[test]
+    fn test_apply_keeps_default_when_render_fails() {
+        let entry = apply(&templates(&[("log-mod-punish-desc", "{ $userId")], &[]));
+        assert_eq!(
+            entry.description,
+            "User <@42> has been automatically punished."
+        );
+    }
+
+    #[test]
+    fn test_apply_hides_fields_by_key() {
+        let entry = apply(&templates(&[], &["log-field-user"]));
+        assert_eq!(
+            entry.fields,
+            vec![
+                ("Reason".to_string(), "spam".to_string()),
+                ("User".to_string(), "plain".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_color() {
+        let mut templates = GuildTemplates::default();
+        templates
+            .colors
+            .insert(LogLevel::Warn.key().to_string(), 0x123456);
+        assert_eq!(apply(&templates).accent_color, 0x123456);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8800"), Some(0xff8800));
+        assert_eq!(parse_hex_color(" 00FF00 "), Some(0x00ff00));
+        assert_eq!(parse_hex_color("#fff"), None);
+        assert_eq!(parse_hex_color("gggggg"), None);
+    }
+}
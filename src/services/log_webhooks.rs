@@ -0,0 +1,191 @@
+use crate::Error;
+use crate::db::entities::log_webhooks;
+use crate::services::config_cache::ConfigCacheService;
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use sea_orm::{DatabaseConnection, EntityTrait, Set, sea_query::OnConflict};
+use std::sync::Arc;
+use tracing::info;
+
+/// Name of the webhooks the bot creates in log channels.
+const WEBHOOK_NAME: &str = "Open Guard Logs";
+
+#[derive(Clone)]
+struct WebhookCredentials {
+    id: serenity::WebhookId,
+    token: String,
+}
+
+/// Delivers log messages through a bot-managed webhook in each log channel,
+/// so logging does not compete with punishments for the bot's own rate limits.
+///
+/// Credentials are persisted per channel in `log_webhooks`, which also covers the
+/// logging module's per-category channels; every resolved webhook is kept in memory.
+/// Deleted webhooks are recreated on the next delivery.
+pub struct LogWebhookSink {
+    db: DatabaseConnection,
+    config_cache: Arc<ConfigCacheService>,
+    webhooks: DashMap<serenity::ChannelId, WebhookCredentials>,
+}
+
+impl LogWebhookSink {
+    pub fn new(db: DatabaseConnection, config_cache: Arc<ConfigCacheService>) -> Self {
+        Self {
+            db,
+            config_cache,
+            webhooks: DashMap::new(),
+        }
+    }
+
+    /// Returns true if the guild opted into webhook delivery.
+    pub async fn is_enabled(&self, guild_id: serenity::GuildId) -> Result<bool, Error> {
+        let g_config = self.config_cache.guild_config(guild_id).await?;
+        Ok(g_config.map(|c| c.log_webhooks_enabled).unwrap_or(false))
+    }
+
+    /// Sends the message through the channel's webhook.
+    /// If the webhook was deleted, a new one is created and the send is retried once.
+    pub async fn send(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        channel_id: serenity::ChannelId,
+        builder: serenity::ExecuteWebhook<'static>,
+    ) -> Result<(), Error> {
+        let credentials = self.resolve(http, guild_id, channel_id).await?;
+
+        match builder
+            .clone()
+            .execute(http, credentials.id, &credentials.token)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) if is_not_found(&e) => {
+                info!(
+                    "Log webhook in channel {} was deleted, recreating it",
+                    channel_id
+                );
+                self.forget(channel_id).await?;
+
+                let credentials = self.resolve(http, guild_id, channel_id).await?;
+                builder
+                    .execute(http, credentials.id, &credentials.token)
+                    .await?;
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Finds the webhook for a channel: memory, then the database, then an existing
+    /// bot-owned webhook in the channel, and finally creates a new one.
+    async fn resolve(
+        &self,
+        http: &serenity::Http,
+        guild_id: serenity::GuildId,
+        channel_id: serenity::ChannelId,
+    ) -> Result<WebhookCredentials, Error> {
+        if let Some(credentials) = self.webhooks.get(&channel_id) {
+            return Ok(credentials.clone());
+        }
+
+        if let Some(credentials) = self.load_stored(channel_id).await? {
+            self.webhooks.insert(channel_id, credentials.clone());
+            return Ok(credentials);
+        }
+
+        let existing = channel_id.webhooks(http).await?.into_iter().find(|w| {
+            w.application_id.is_some() && w.application_id == http.application_id()
+        });
+
+        let webhook = match existing {
+            Some(w) if w.token.is_some() => w,
+            _ => {
+                channel_id
+                    .create_webhook(http, serenity::CreateWebhook::new(WEBHOOK_NAME))
+                    .await?
+            }
+        };
+
+        let token = webhook
+            .token
+            .as_ref()
+            .map(|t| t.expose_secret().to_string())
+            .ok_or_else(|| anyhow::anyhow!("Created webhook has no token"))?;
+
+        let credentials = WebhookCredentials {
+            id: webhook.id,
+            token,
+        };
+
+        self.store(guild_id, channel_id, &credentials).await?;
+        self.webhooks.insert(channel_id, credentials.clone());
+
+        Ok(credentials)
+    }
+
+    async fn load_stored(
+        &self,
+        channel_id: serenity::ChannelId,
+    ) -> Result<Option<WebhookCredentials>, Error> {
+        let stored = log_webhooks::Entity::find_by_id(channel_id.get() as i64)
+            .one(&self.db)
+            .await?;
+
+        Ok(stored.map(|w| WebhookCredentials {
+            id: serenity::WebhookId::new(w.webhook_id as u64),
+            token: w.token,
+        }))
+    }
+
+    /// Persists the credentials of a channel's webhook.
+    async fn store(
+        &self,
+        guild_id: serenity::GuildId,
+        channel_id: serenity::ChannelId,
+        credentials: &WebhookCredentials,
+    ) -> Result<(), Error> {
+        let row = log_webhooks::ActiveModel {
+            channel_id: Set(channel_id.get() as i64),
+            guild_id: Set(guild_id.get() as i64),
+            webhook_id: Set(credentials.id.get() as i64),
+            token: Set(credentials.token.clone()),
+        };
+
+        log_webhooks::Entity::insert(row)
+            .on_conflict(
+                OnConflict::column(log_webhooks::Column::ChannelId)
+                    .update_columns([
+                        log_webhooks::Column::GuildId,
+                        log_webhooks::Column::WebhookId,
+                        log_webhooks::Column::Token,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drops the credentials of a channel whose webhook no longer exists.
+    async fn forget(&self, channel_id: serenity::ChannelId) -> Result<(), Error> {
+        self.webhooks.remove(&channel_id);
+
+        log_webhooks::Entity::delete_by_id(channel_id.get() as i64)
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Discord answers 404 for deleted webhooks and channels.
+pub fn is_not_found(err: &serenity::Error) -> bool {
+    match err {
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) => {
+            response.status_code.as_u16() == 404
+        }
+        _ => false,
+    }
+}
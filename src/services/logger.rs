@@ -1,13 +1,20 @@
 use crate::Error;
-use crate::db::entities::{
-    guild_configs,
-    module_configs::{self, ModuleType},
-};
-use crate::services::localization::ContextL10nExt;
+use crate::db::entities::module_configs::ModuleType;
+use crate::services::config_cache::ConfigCacheService;
+use crate::services::localization::{ContextL10nExt, LocalizationManager, LocalizedText};
+use crate::services::log_channel_healer::LogChannelHealer;
+use crate::services::log_file_sink::LogFileSink;
+use crate::services::log_queue::{LogEntry, LogQueue};
+use crate::services::log_templates::LogTemplateService;
+use chrono::{DateTime, Utc};
 use poise::serenity_prelude as serenity;
-use sea_orm::{DatabaseConnection, EntityTrait};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::warn;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     Info,
     Warn,
@@ -33,110 +40,183 @@ impl LogLevel {
             LogLevel::Audit => 0x95a5a6, // Gray
         }
     }
+
+    /// Stable key used when persisting queued logs.
+    pub fn key(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+            LogLevel::Audit => "audit",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "info" => Some(LogLevel::Info),
+            "warn" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            "audit" => Some(LogLevel::Audit),
+            _ => None,
+        }
+    }
+}
+
+/// Structured form of a log entry. The same record is handed to every sink.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub guild_id: serenity::GuildId,
+    pub module: Option<ModuleType>,
+    pub level: LogLevel,
+    pub title: LocalizedText,
+    pub description: LocalizedText,
+    /// Field names keep their Fluent key, so templates can hide fields by key
+    pub fields: Vec<(LocalizedText, String)>,
+    pub timestamp: DateTime<Utc>,
 }
 
 pub struct LoggerService {
-    db: DatabaseConnection,
+    config_cache: Arc<ConfigCacheService>,
+    queue: Arc<LogQueue>,
+    file_sink: Option<LogFileSink>,
+    templates: LogTemplateService,
 }
 
 impl LoggerService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(
+        db: DatabaseConnection,
+        config_cache: Arc<ConfigCacheService>,
+        l10n: Arc<LocalizationManager>,
+    ) -> Self {
+        let healer = LogChannelHealer::new(db.clone(), config_cache.clone(), l10n.clone());
+        Self {
+            queue: Arc::new(LogQueue::new(db.clone(), config_cache.clone(), healer)),
+            file_sink: LogFileSink::from_env(),
+            templates: LogTemplateService::new(db, l10n),
+            config_cache,
+        }
+    }
+
+    /// Starts delivering queued logs, including those left over from the previous run.
+    pub fn start_delivery_runner(&self, http: Arc<serenity::Http>) {
+        self.queue.clone().start_delivery_runner(http);
     }
 
-    /// Logs a structured message to the configured log channel for a guild or module.
+    /// Per-guild templates, field selection and level colors of Discord log deliveries.
+    pub fn templates(&self) -> &LogTemplateService {
+        &self.templates
+    }
+
+    /// Queues a structured message for the configured log channel of a guild or module.
     /// Priority: preferred_channel_id > module log channel > general guild log channel.
     /// If none are configured, it does nothing.
     pub async fn log_action(
         &self,
-        http: &serenity::Http,
         guild_id: serenity::GuildId,
         module: Option<ModuleType>,
         preferred_channel_id: Option<i64>,
         level: LogLevel,
-        title: &str,
-        desc: &str,
-        fields: Vec<(&str, String)>,
+        title: LocalizedText,
+        desc: LocalizedText,
+        fields: Vec<(LocalizedText, String)>,
     ) -> Result<(), Error> {
-        let mut target_channel_id = preferred_channel_id;
+        self.log_action_with_files(
+            guild_id,
+            module,
+            preferred_channel_id,
+            level,
+            title,
+            desc,
+            fields,
+            Vec::new(),
+        )
+        .await
+    }
 
-        // 1. Try module-specific channel if preferred not set
-        if target_channel_id.is_none() {
-            if let Some(module_type) = module {
-                let m_config =
-                    module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
-                        .one(&self.db)
-                        .await?;
+    /// Same as `log_action`, but re-uploads the given files right after the log entry.
+    pub async fn log_action_with_files(
+        &self,
+        guild_id: serenity::GuildId,
+        module: Option<ModuleType>,
+        preferred_channel_id: Option<i64>,
+        level: LogLevel,
+        title: LocalizedText,
+        desc: LocalizedText,
+        fields: Vec<(LocalizedText, String)>,
+        files: Vec<serenity::CreateAttachment<'static>>,
+    ) -> Result<(), Error> {
+        let record = LogRecord {
+            guild_id,
+            module,
+            level,
+            title,
+            description: desc,
+            fields,
+            timestamp: Utc::now(),
+        };
 
-                target_channel_id = m_config.and_then(|c| c.log_channel_id);
+        // The file sink records every entry, even without a log channel
+        if let Some(sink) = &self.file_sink {
+            if let Err(e) = sink.write(&record).await {
+                warn!("Failed to export log record: {:?}", e);
             }
         }
 
-        // 2. Fallback to general guild channel if not found
-        if target_channel_id.is_none() {
-            let g_config = guild_configs::Entity::find_by_id(guild_id.get() as i64)
-                .one(&self.db)
-                .await?;
-
-            target_channel_id = g_config.and_then(|c| c.log_channel_id);
-        }
-
-        let channel_id = match target_channel_id {
+        let channel_id = match preferred_channel_id {
             Some(id) => serenity::ChannelId::new(id as u64),
-            None => return Ok(()), // No log channel configured
+            None => match self.resolve_channel(guild_id, module).await? {
+                Some(id) => id,
+                None => return Ok(()), // No log channel configured
+            },
         };
 
-        let mut inner_components = vec![];
-
-        // Header
-        inner_components.push(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new(format!("### {} {}", level.icon(), title)),
-        ));
-
-        inner_components.push(serenity::CreateContainerComponent::Separator(
-            serenity::CreateSeparator::new(true),
-        ));
+        let mut entry = LogEntry::new(channel_id, &record, files);
+        if let Err(e) = self.templates.apply(&record, &mut entry).await {
+            warn!("Failed to apply log templates: {:?}", e);
+        }
 
-        // Description
-        inner_components.push(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new(desc),
-        ));
+        self.queue.enqueue(entry).await
+    }
 
-        // Optional fields
-        if !fields.is_empty() {
-            inner_components.push(serenity::CreateContainerComponent::Separator(
-                serenity::CreateSeparator::new(false),
-            ));
+    /// Returns the log channel for a module: its own channel if set, otherwise the
+    /// guild's general log channel.
+    pub async fn resolve_channel(
+        &self,
+        guild_id: serenity::GuildId,
+        module: Option<ModuleType>,
+    ) -> Result<Option<serenity::ChannelId>, Error> {
+        let mut target_channel_id = None;
+
+        // 1. Try module-specific channel
+        if let Some(module_type) = module {
+            let m_config = self
+                .config_cache
+                .module_config(guild_id, module_type)
+                .await?;
 
-            for (name, value) in fields {
-                inner_components.push(serenity::CreateContainerComponent::TextDisplay(
-                    serenity::CreateTextDisplay::new(format!("> **{}**\n> {}", name, value)),
-                ));
-            }
+            target_channel_id = m_config.and_then(|c| c.log_channel_id);
         }
 
-        let message = serenity::CreateMessage::new()
-            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![serenity::CreateComponent::Container(
-                serenity::CreateContainer::new(inner_components).accent_color(level.color()),
-            )])
-            .allowed_mentions(serenity::CreateAllowedMentions::new());
+        // 2. Fallback to general guild channel if not found
+        if target_channel_id.is_none() {
+            let g_config = self.config_cache.guild_config(guild_id).await?;
 
-        http.send_message(channel_id.into(), Vec::new(), &message)
-            .await?;
+            target_channel_id = g_config.and_then(|c| c.log_channel_id);
+        }
 
-        Ok(())
+        Ok(target_channel_id.map(|id| serenity::ChannelId::new(id as u64)))
     }
 
     /// Helper to log an event from a command context
+    #[cfg_attr(not(feature = "moderation-protection"), allow(dead_code))]
     pub async fn log_context(
         &self,
         ctx: &crate::Context<'_>,
         module: Option<ModuleType>,
         level: LogLevel,
-        title: &str,
-        desc: &str,
-        additional_fields: Vec<(&str, String)>,
+        title: LocalizedText,
+        desc: LocalizedText,
+        additional_fields: Vec<(LocalizedText, String)>,
     ) -> Result<(), Error> {
         let guild_id = ctx
             .guild_id()
@@ -145,27 +225,18 @@ impl LoggerService {
         let l10n = ctx.l10n_guild();
         let mut fields = vec![
             (
-                l10n.t("config-log-field-user", None).leak() as &str,
+                l10n.text("config-log-field-user", None),
                 format!("<@{}>", ctx.author().id.get()),
             ),
             (
-                l10n.t("config-log-field-channel", None).leak() as &str,
+                l10n.text("config-log-field-channel", None),
                 format!("<#{}>", ctx.channel_id().get()),
             ),
         ];
 
         fields.extend(additional_fields);
 
-        self.log_action(
-            ctx.serenity_context().http.as_ref(),
-            guild_id,
-            module,
-            None,
-            level,
-            title,
-            desc,
-            fields,
-        )
-        .await
+        self.log_action(guild_id, module, None, level, title, desc, fields)
+            .await
     }
 }
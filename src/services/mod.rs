@@ -1,9 +1,19 @@
+pub mod approval;
+#[cfg(feature = "logging")]
+pub mod attachment_archive;
 pub mod cache;
 pub mod config;
+pub mod config_cache;
+pub mod duration;
 pub mod event_manager;
 pub mod help;
 pub mod jail;
 pub mod localization;
+pub mod log_channel_healer;
+pub mod log_file_sink;
+pub mod log_queue;
+pub mod log_templates;
+pub mod log_webhooks;
 pub mod logger;
 pub mod logging_cleanup;
 pub mod punishment;
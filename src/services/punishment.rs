@@ -1,6 +1,7 @@
 use crate::Error;
 use crate::db::entities::{
-    module_configs::{self, ModuleType, PunishmentType},
+    bot_adders,
+    module_configs::{BotAddingProtectionModuleConfig, ModuleType, PunishmentType},
     violations,
 };
 use chrono::Utc;
@@ -10,9 +11,11 @@ use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, Qu
 
 pub struct PunishmentService {
     db: DatabaseConnection,
+    config_cache: std::sync::Arc<crate::services::config_cache::ConfigCacheService>,
     logger: std::sync::Arc<crate::services::logger::LoggerService>,
     l10n: std::sync::Arc<crate::services::localization::LocalizationManager>,
     jail: Option<std::sync::Arc<crate::services::jail::JailService>>,
+    whitelist: Option<std::sync::Arc<crate::services::whitelist::WhitelistService>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,14 +28,17 @@ pub enum ViolationResult {
 impl PunishmentService {
     pub fn new(
         db: DatabaseConnection,
+        config_cache: std::sync::Arc<crate::services::config_cache::ConfigCacheService>,
         logger: std::sync::Arc<crate::services::logger::LoggerService>,
         l10n: std::sync::Arc<crate::services::localization::LocalizationManager>,
     ) -> Self {
         Self {
             db,
+            config_cache,
             logger,
             l10n,
             jail: None,
+            whitelist: None,
         }
     }
 
@@ -40,8 +46,156 @@ impl PunishmentService {
         self.jail = Some(jail);
     }
 
+    pub fn set_whitelist_service(
+        &mut self,
+        whitelist: std::sync::Arc<crate::services::whitelist::WhitelistService>,
+    ) {
+        self.whitelist = Some(whitelist);
+    }
+
     /// Handles a violation by incrementing the counter and applying punishment if threshold is reached.
+    /// If the violator is a bot, the user who added it may be held responsible as well.
     pub async fn handle_violation(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        user_id: serenity::UserId,
+        module_type: ModuleType,
+        reason: &str,
+    ) -> Result<ViolationResult, Error> {
+        let result = self
+            .record_violation(&ctx.http, guild_id, user_id, module_type, reason)
+            .await?;
+
+        if let Err(e) = self
+            .handle_adder_violation(ctx, guild_id, user_id, module_type)
+            .await
+        {
+            tracing::error!(
+                "Failed to hold the adder of {} responsible in guild {}: {:?}",
+                user_id.get(),
+                guild_id.get(),
+                e
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Gives the user who added a violating bot a Bot Adding Protection violation, if the
+    /// guild enabled that. Violators known to be human are skipped without a lookup; other
+    /// users who were not added as a bot are not in the table and skipped too.
+    async fn handle_adder_violation(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        bot_id: serenity::UserId,
+        module_type: ModuleType,
+    ) -> Result<(), Error> {
+        // Bot additions are only ever made by users
+        if module_type == ModuleType::BotAddingProtection {
+            return Ok(());
+        }
+
+        // Only bots have adders; members missing from the cache are looked up
+        let is_bot = ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|g| g.members.get(&bot_id).map(|m| m.user.bot()));
+        if is_bot == Some(false) {
+            return Ok(());
+        }
+
+        let Some(config) = self
+            .config_cache
+            .module_config(guild_id, ModuleType::BotAddingProtection)
+            .await?
+        else {
+            return Ok(());
+        };
+        let bap_config: BotAddingProtectionModuleConfig =
+            serde_json::from_value(config.config.clone()).unwrap_or_default();
+        if !config.enabled || !bap_config.punish_adder {
+            return Ok(());
+        }
+
+        let Some(adder) =
+            bot_adders::Entity::find_by_id((guild_id.get() as i64, bot_id.get() as i64))
+                .one(&self.db)
+                .await?
+        else {
+            return Ok(());
+        };
+        let adder_id = serenity::UserId::new(adder.adder_id as u64);
+
+        // Trusted users are never punished, as in the protection modules themselves
+        if let Some(whitelist) = &self.whitelist {
+            let level = whitelist
+                .get_whitelist_level(
+                    ctx,
+                    guild_id,
+                    adder_id,
+                    ModuleType::BotAddingProtection,
+                    Some("add"),
+                )
+                .await?;
+            if level.is_some() {
+                return Ok(());
+            }
+        }
+
+        let l10n = self.l10n.get_l10n_for_guild(guild_id, &self.db).await;
+        let mut args = FluentArgs::new();
+        args.set("botId", bot_id.get().to_string());
+        args.set("userId", adder_id.get().to_string());
+        args.set("module", module_type.to_string());
+        let reason = l10n.t("log-bot-adder-reason", Some(&args));
+
+        let result = self
+            .record_violation(
+                &ctx.http,
+                guild_id,
+                adder_id,
+                ModuleType::BotAddingProtection,
+                &reason,
+            )
+            .await?;
+
+        let status = match result {
+            ViolationResult::Punished(p) => {
+                let mut args = FluentArgs::new();
+                args.set("type", format!("{:?}", p));
+                l10n.t("log-status-punished", Some(&args))
+            }
+            ViolationResult::ViolationRecorded { current, threshold } => {
+                let mut args = FluentArgs::new();
+                args.set("current", current.to_string());
+                args.set("threshold", threshold.to_string());
+                l10n.t("log-status-violation", Some(&args))
+            }
+            ViolationResult::None => l10n.t("log-status-blocked", None),
+        };
+
+        self.logger
+            .log_action(
+                guild_id,
+                Some(ModuleType::BotAddingProtection),
+                None,
+                crate::services::logger::LogLevel::Warn,
+                l10n.text("log-bot-adder-title", None),
+                l10n.text("log-bot-adder-desc", Some(&args)),
+                vec![
+                    (
+                        l10n.text("log-field-user", None),
+                        format!("<@{}>", adder_id.get()),
+                    ),
+                    (l10n.text("log-field-action-status", None), status),
+                ],
+            )
+            .await
+    }
+
+    async fn record_violation(
         &self,
         http: &serenity::Http,
         guild_id: serenity::GuildId,
@@ -49,8 +203,9 @@ impl PunishmentService {
         module_type: ModuleType,
         reason: &str,
     ) -> Result<ViolationResult, Error> {
-        let config = module_configs::Entity::find_by_id((guild_id.get() as i64, module_type))
-            .one(&self.db)
+        let config = self
+            .config_cache
+            .module_config(guild_id, module_type)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Module config not found"))?;
 
@@ -221,17 +376,22 @@ impl PunishmentService {
                 let _ = self
                     .logger
                     .log_action(
-                        http,
                         guild_id,
                         Some(ModuleType::ModerationProtection),
                         None,
                         crate::services::logger::LogLevel::Warn,
-                        &l10n.t("log-mod-punish-title", None),
-                        &l10n.t("log-mod-punish-desc", Some(&user_args)),
+                        l10n.text("log-mod-punish-title", None),
+                        l10n.text("log-mod-punish-desc", Some(&user_args)),
                         vec![
-                            (&l10n.t("log-field-user", None), format!("<@{}>", user_id.get())),
-                            (&l10n.t("log-field-type", None), format!("{:?}", punishment)),
-                            (&l10n.t("log-field-reason", None), reason.to_string()),
+                            (
+                                l10n.text("log-field-user", None),
+                                format!("<@{}>", user_id.get()),
+                            ),
+                            (
+                                l10n.text("log-field-type", None),
+                                format!("{:?}", punishment),
+                            ),
+                            (l10n.text("log-field-reason", None), reason.to_string()),
                         ],
                     )
                     .await;
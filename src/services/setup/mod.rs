@@ -7,7 +7,7 @@ use crate::db::entities::module_configs::ModuleType;
 use crate::services::localization::{ContextL10nExt, L10nProxy};
 use fluent::FluentArgs;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, Iterable, QueryFilter, Set};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use state::SetupStep;
 
 /// Start the fast setup process for the bot.
@@ -740,7 +740,8 @@ async fn handle_apply(
     };
 
     // 1. Update modules
-    for module_type in ModuleType::iter() {
+    let mut old_configs = vec![];
+    for module_type in ModuleType::available() {
         let is_enabled = state.enabled_modules.contains(&module_type);
         
         let existing = module_configs::Entity::find()
@@ -749,6 +750,8 @@ async fn handle_apply(
             .one(&data.db)
             .await?;
 
+        old_configs.push((module_type, existing.clone()));
+
         let mut am: module_configs::ActiveModel = existing
             .map(|m| m.into())
             .unwrap_or_else(|| module_configs::ActiveModel {
@@ -785,6 +788,10 @@ async fn handle_apply(
             .exec(&data.db)
             .await?;
     }
+    for (module_type, old_config) in old_configs {
+        data.config_cache
+            .invalidate_module(guild_id, module_type, old_config);
+    }
 
     // 2. Update whitelist - check if exists first, then insert or update
     for user_id in &state.whitelist_users {
@@ -799,6 +806,8 @@ async fn handle_apply(
             // Update existing entry
             let mut active: whitelist_user::ActiveModel = existing.into();
             active.level = Set(WhitelistLevel::Invulnerable);
+            active.expires_at = Set(None);
+            active.actions = Set(serde_json::json!([]));
             active.update(&data.db).await?;
         } else {
             // Insert new entry
@@ -825,6 +834,8 @@ async fn handle_apply(
             // Update existing entry
             let mut active: whitelist_role::ActiveModel = existing.into();
             active.level = Set(WhitelistLevel::Invulnerable);
+            active.expires_at = Set(None);
+            active.actions = Set(serde_json::json!([]));
             active.update(&data.db).await?;
         } else {
             // Insert new entry
@@ -1,14 +1,13 @@
 use crate::db::entities::module_configs::ModuleType;
 use crate::services::localization::L10nProxy;
 use poise::serenity_prelude as serenity;
-use sea_orm::Iterable;
 
 pub fn build_systems_step(
     setup_id: &str,
     l10n: &L10nProxy,
 ) -> Vec<serenity::CreateComponent<'static>> {
     let mut options = Vec::new();
-    for module in ModuleType::iter() {
+    for module in ModuleType::available() {
         let label = match module {
             ModuleType::ChannelProtection => l10n.t("config-channel-protection-label", None),
             ModuleType::ChannelPermissionProtection => {
@@ -1,7 +1,9 @@
 use crate::db::entities::module_configs;
+use crate::modules::ModuleHealth;
 use crate::services::localization::{ContextL10nExt, L10nProxy};
 use crate::{Context, Data, Error};
 use chrono::Utc;
+use fluent::FluentArgs;
 use poise::serenity_prelude as serenity;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use std::sync::atomic::Ordering;
@@ -20,6 +22,7 @@ pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
     let l10n = ctx.l10n_user();
 
     let components = get_status_components(
+        ctx.serenity_context(),
         &*ctx.data(),
         &l10n,
         ctx.guild_id().map(|id| id.get() as i64),
@@ -41,6 +44,7 @@ pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 pub async fn get_status_components(
+    ctx: &serenity::Context,
     data: &Data,
     l10n: &L10nProxy,
     guild_id: Option<i64>,
@@ -72,20 +76,31 @@ pub async fn get_status_components(
             .all(&data.db)
             .await?;
 
-        data.module_definitions
-            .iter()
-            .map(|def| {
-                let is_enabled = db_modules
-                    .iter()
-                    .find(|m| m.module_type.to_string() == def.id)
-                    .map(|m| m.enabled)
-                    .unwrap_or(false);
-                let status_emoji = if is_enabled { "✅" } else { "❌" };
-                let name = l10n.t(def.name_key, None);
-                format!("{} **{}**", status_emoji, name)
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
+        let mut lines = vec![];
+        for module in &data.modules {
+            let def = module.definition();
+            let is_enabled = db_modules
+                .iter()
+                .find(|m| m.module_type.to_string() == def.id)
+                .map(|m| m.enabled)
+                .unwrap_or(false);
+            let status_emoji = if is_enabled { "✅" } else { "❌" };
+            let name = l10n.t(def.name_key, None);
+            lines.push(format!("{} **{}**", status_emoji, name));
+
+            // Enabled modules that cannot do their job get a warning under them
+            if is_enabled {
+                let guild_id = serenity::GuildId::new(gid as u64);
+                if let ModuleHealth::MissingPermissions(missing) =
+                    module.health(ctx, guild_id, data).await
+                {
+                    let mut args = FluentArgs::new();
+                    args.set("permissions", missing.get_permission_names().join(", "));
+                    lines.push(l10n.t("status-module-missing-permissions", Some(&args)));
+                }
+            }
+        }
+        lines.join("\n")
     } else {
         l10n.t("status-no-guild", None)
     };
@@ -119,6 +134,33 @@ pub async fn get_status_components(
         serenity::CreateTextDisplay::new(metrics_text),
     ));
 
+    // Module Latency
+    let latencies = data.event_stats.snapshot();
+    if !latencies.is_empty() {
+        let mut latency_text = format!("**{}**", l10n.t("status-module-latency", None));
+        for latency in latencies {
+            let name = data
+                .modules
+                .iter()
+                .map(|module| module.definition())
+                .find(|def| def.id == latency.module_id)
+                .map(|def| l10n.t(def.name_key, None))
+                .unwrap_or_else(|| latency.module_id.to_string());
+
+            let mut args = FluentArgs::new();
+            args.set("module", name);
+            args.set("average", latency.average.as_millis() as u64);
+            args.set("max", latency.max.as_millis() as u64);
+            args.set("handled", latency.handled);
+            latency_text.push('\n');
+            latency_text.push_str(&l10n.t("status-module-latency-entry", Some(&args)));
+        }
+
+        inner_components.push(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(latency_text),
+        ));
+    }
+
     #[cfg(feature = "system-info")]
     {
         use sysinfo::System;
@@ -191,6 +233,7 @@ pub async fn handle_interaction(
     let end_time = Utc::now();
 
     let components = get_status_components(
+        ctx,
         data,
         &l10n,
         interaction.guild_id.map(|id| id.get() as i64),
@@ -67,20 +67,19 @@ impl TempBanService {
 
                                 let _ = logger
                                     .log_action(
-                                        &http,
                                         guild_id,
                                         Some(ModuleType::ModerationProtection),
                                         None,
                                         LogLevel::Audit,
-                                        &l10n.t("log-mod-unban-title", None),
-                                        &l10n.t("log-mod-unban-desc", Some(&user_args)),
+                                        l10n.text("log-mod-unban-title", None),
+                                        l10n.text("log-mod-unban-desc", Some(&user_args)),
                                         vec![
                                             (
-                                                &l10n.t("log-field-user", None),
+                                                l10n.text("log-field-user", None),
                                                 format!("<@{}>", user_id.get()),
                                             ),
                                             (
-                                                &l10n.t("log-field-reason", None),
+                                                l10n.text("log-field-reason", None),
                                                 l10n.t("log-val-temp-ban-expired", None),
                                             ),
                                         ],
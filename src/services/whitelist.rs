@@ -1,20 +1,560 @@
 use crate::db::entities::{
-    module_configs::ModuleType, whitelist_role, whitelist_user, whitelists::WhitelistLevel,
+    module_configs::ModuleType, whitelist_history, whitelist_role, whitelist_user,
+    whitelists::WhitelistLevel,
 };
+use crate::services::config_cache::ConfigCacheService;
+use crate::services::localization::{L10nProxy, LocalizationManager};
+use crate::services::logger::{LogLevel, LoggerService};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use fluent::FluentArgs;
 use poise::serenity_prelude as serenity;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tokio::time::{Duration, sleep};
+use tracing::{error, info, warn};
+
+/// Matches user entries that are permanent or have not expired yet.
+pub fn not_expired_user() -> Condition {
+    Condition::any()
+        .add(whitelist_user::Column::ExpiresAt.is_null())
+        .add(whitelist_user::Column::ExpiresAt.gt(chrono::Utc::now()))
+}
+
+/// Matches role entries that are permanent or have not expired yet.
+pub fn not_expired_role() -> Condition {
+    Condition::any()
+        .add(whitelist_role::Column::ExpiresAt.is_null())
+        .add(whitelist_role::Column::ExpiresAt.gt(chrono::Utc::now()))
+}
+
+/// Actions an entry for `module` can be limited to, using the same values as the
+/// module's `punish_when` setting; modules that check a single action have none.
+/// Global entries can use every action a module checks, where permission changes
+/// count as `update` and bot additions as `add`.
+pub fn scoped_actions(module: Option<ModuleType>) -> &'static [&'static str] {
+    match module {
+        Some(
+            ModuleType::ChannelProtection
+            | ModuleType::ChannelPermissionProtection
+            | ModuleType::RoleProtection,
+        ) => &["create", "update", "delete"],
+        Some(ModuleType::ModerationProtection) => &["ban", "kick", "prune", "timeout", "unban"],
+        Some(_) => &[],
+        None => &[
+            "create", "update", "delete", "add", "ban", "kick", "prune", "timeout", "unban",
+        ],
+    }
+}
+
+/// The action checked by modules that only check one, which have no `scoped_actions`.
+pub fn single_action(module: ModuleType) -> Option<&'static str> {
+    match module {
+        ModuleType::RolePermissionProtection | ModuleType::MemberPermissionProtection => {
+            Some("update")
+        }
+        ModuleType::BotAddingProtection => Some("add"),
+        _ => None,
+    }
+}
+
+/// Reads the action scope stored on an entry.
+pub fn entry_actions(actions: &serde_json::Value) -> Vec<String> {
+    serde_json::from_value(actions.clone()).unwrap_or_default()
+}
+
+/// Whether an entry with the given scope applies to `action`.
+/// Unscoped entries apply everywhere; scoped ones only to the actions they list.
+pub fn covers_action(actions: &serde_json::Value, action: Option<&str>) -> bool {
+    let actions = entry_actions(actions);
+    if actions.is_empty() {
+        return true;
+    }
+    action.is_some_and(|a| actions.iter().any(|x| x == a))
+}
+
+/// How long a Head-level grant waits for confirmation before it is dropped.
+const GRANT_CONFIRM_TIMEOUT_SECS: i64 = 300;
+
+/// How long members and guilds fetched over HTTP are reused for whitelist checks.
+const LOOKUP_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Owner and role layout of a guild, as far as whitelist checks need them.
+struct GuildInfo {
+    owner_id: serenity::UserId,
+    /// Position and permissions of each role
+    roles: HashMap<serenity::RoleId, (i16, serenity::Permissions)>,
+}
+
+impl GuildInfo {
+    fn new<'a>(
+        owner_id: serenity::UserId,
+        roles: impl Iterator<Item = &'a serenity::Role>,
+    ) -> Self {
+        Self {
+            owner_id,
+            roles: roles
+                .map(|role| (role.id, (role.position, role.permissions)))
+                .collect(),
+        }
+    }
+
+    fn highest_position(&self, member_roles: &[serenity::RoleId]) -> i16 {
+        member_roles
+            .iter()
+            .filter_map(|r| self.roles.get(r).map(|(position, _)| *position))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn has_administrator(&self, member_roles: &[serenity::RoleId]) -> bool {
+        member_roles.iter().any(|r| {
+            self.roles.get(r).is_some_and(|(_, permissions)| {
+                permissions.contains(serenity::Permissions::ADMINISTRATOR)
+            })
+        })
+    }
+}
+
+/// The user or role a whitelist entry applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhitelistTarget {
+    User(u64),
+    Role(u64),
+}
+
+impl WhitelistTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WhitelistTarget::User(_) => "user",
+            WhitelistTarget::Role(_) => "role",
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        match self {
+            WhitelistTarget::User(id) | WhitelistTarget::Role(id) => *id,
+        }
+    }
+
+    pub fn mention(&self) -> String {
+        match self {
+            WhitelistTarget::User(id) => format!("<@{}>", id),
+            WhitelistTarget::Role(id) => format!("<@&{}>", id),
+        }
+    }
+}
+
+/// The state of a whitelist entry, as recorded in the change history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntrySnapshot {
+    pub target: WhitelistTarget,
+    pub level: WhitelistLevel,
+    pub module_type: Option<ModuleType>,
+    pub actions: Vec<String>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+}
+
+impl From<&whitelist_user::Model> for EntrySnapshot {
+    fn from(entry: &whitelist_user::Model) -> Self {
+        Self {
+            target: WhitelistTarget::User(entry.user_id as u64),
+            level: entry.level,
+            module_type: entry.module_type,
+            actions: entry_actions(&entry.actions),
+            expires_at: entry.expires_at,
+        }
+    }
+}
+
+impl From<&whitelist_role::Model> for EntrySnapshot {
+    fn from(entry: &whitelist_role::Model) -> Self {
+        Self {
+            target: WhitelistTarget::Role(entry.role_id as u64),
+            level: entry.level,
+            module_type: entry.module_type,
+            actions: entry_actions(&entry.actions),
+            expires_at: entry.expires_at,
+        }
+    }
+}
+
+/// A Head-level grant held until the member who submitted it confirms.
+#[derive(Debug, Clone)]
+pub struct PendingGrant {
+    pub guild_id: serenity::GuildId,
+    pub actor_id: serenity::UserId,
+    /// Entry being edited, or `None` for a new entry
+    pub entry_id: Option<i32>,
+    pub entry: EntrySnapshot,
+    pub created_at: DateTime<Utc>,
+}
 
 pub struct WhitelistService {
     db: DatabaseConnection,
+    config_cache: Arc<ConfigCacheService>,
+    logger: Arc<LoggerService>,
+    l10n: Arc<LocalizationManager>,
+    pending_grants: DashMap<u64, PendingGrant>,
+    /// ID of the next held grant
+    next_grant_id: AtomicU64,
+    /// Map: (GuildID, UserID) -> (Role IDs, or None if not a member, Timestamp)
+    members: Arc<DashMap<(u64, u64), (Option<Arc<[serenity::RoleId]>>, Instant)>>,
+    /// Map: GuildID -> (GuildInfo, Timestamp)
+    guilds: Arc<DashMap<u64, (Arc<GuildInfo>, Instant)>>,
 }
 
 impl WhitelistService {
-    pub fn new(db: DatabaseConnection) -> self::WhitelistService {
-        Self { db }
+    pub fn new(
+        db: DatabaseConnection,
+        config_cache: Arc<ConfigCacheService>,
+        logger: Arc<LoggerService>,
+        l10n: Arc<LocalizationManager>,
+    ) -> self::WhitelistService {
+        let members: Arc<DashMap<_, (_, Instant)>> = Arc::new(DashMap::new());
+        let guilds: Arc<DashMap<_, (_, Instant)>> = Arc::new(DashMap::new());
+        let cleaner_members = Arc::clone(&members);
+        let cleaner_guilds = Arc::clone(&guilds);
+
+        // Spawn cleanup task
+        tokio::spawn(async move {
+            loop {
+                sleep(Duration::from_secs(60)).await;
+                cleaner_members.retain(|_, (_, time)| time.elapsed() < LOOKUP_CACHE_TTL);
+                cleaner_guilds.retain(|_, (_, time)| time.elapsed() < LOOKUP_CACHE_TTL);
+            }
+        });
+
+        Self {
+            db,
+            config_cache,
+            logger,
+            l10n,
+            pending_grants: DashMap::new(),
+            next_grant_id: AtomicU64::new(1),
+            members,
+            guilds,
+        }
+    }
+
+    /// Drops the cached roles of a member, after the member changed, joined or left.
+    pub fn invalidate_member(&self, guild_id: serenity::GuildId, user_id: serenity::UserId) {
+        self.members.remove(&(guild_id.get(), user_id.get()));
+    }
+
+    /// Drops the cached owner and roles of a guild, after the guild or one of its roles changed.
+    pub fn invalidate_guild(&self, guild_id: serenity::GuildId) {
+        self.guilds.remove(&guild_id.get());
+    }
+
+    /// Role IDs of a member, or `None` if the user is not in the guild.
+    /// Uses the serenity cache, then the short-lived lookup cache, and only then HTTP.
+    async fn member_roles(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        user_id: serenity::UserId,
+    ) -> Option<Arc<[serenity::RoleId]>> {
+        let cached: Option<Arc<[serenity::RoleId]>> = ctx.cache.guild(guild_id).and_then(|g| {
+            g.members
+                .get(&user_id)
+                .map(|m| m.roles.iter().copied().collect())
+        });
+        if cached.is_some() {
+            return cached;
+        }
+
+        let key = (guild_id.get(), user_id.get());
+        if let Some(entry) = self.members.get(&key) {
+            if entry.1.elapsed() < LOOKUP_CACHE_TTL {
+                return entry.0.clone();
+            }
+        }
+
+        let roles: Option<Arc<[serenity::RoleId]>> = guild_id
+            .member(&ctx.http, user_id)
+            .await
+            .ok()
+            .map(|m| m.roles.iter().copied().collect());
+        self.members.insert(key, (roles.clone(), Instant::now()));
+        roles
+    }
+
+    /// Owner and roles of a guild, from the serenity cache, the lookup cache or HTTP.
+    async fn guild_info(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+    ) -> Option<Arc<GuildInfo>> {
+        let cached = ctx
+            .cache
+            .guild(guild_id)
+            .map(|g| Arc::new(GuildInfo::new(g.owner_id, g.roles.iter())));
+        if cached.is_some() {
+            return cached;
+        }
+
+        if let Some(entry) = self.guilds.get(&guild_id.get()) {
+            if entry.1.elapsed() < LOOKUP_CACHE_TTL {
+                return Some(entry.0.clone());
+            }
+        }
+
+        let guild = ctx.http.get_guild(guild_id).await.ok()?;
+        let info = Arc::new(GuildInfo::new(guild.owner_id, guild.roles.iter()));
+        self.guilds
+            .insert(guild_id.get(), (info.clone(), Instant::now()));
+        Some(info)
+    }
+
+    /// Holds a Head-level grant until it is confirmed, dropping any stale ones.
+    /// Returns the ID its confirmation buttons refer to.
+    pub fn hold_grant(&self, grant: PendingGrant) -> u64 {
+        let now = Utc::now();
+        self.pending_grants
+            .retain(|_, g| (now - g.created_at).num_seconds() < GRANT_CONFIRM_TIMEOUT_SECS);
+        let id = self.next_grant_id.fetch_add(1, Ordering::Relaxed);
+        self.pending_grants.insert(id, grant);
+        id
+    }
+
+    /// Removes a held grant of the given guild and actor, returning it if it has not
+    /// timed out. Grants held by anyone else are left in place.
+    pub fn take_grant(
+        &self,
+        id: u64,
+        guild_id: serenity::GuildId,
+        actor_id: serenity::UserId,
+    ) -> Option<PendingGrant> {
+        self.pending_grants
+            .remove_if(&id, |_, g| g.guild_id == guild_id && g.actor_id == actor_id)
+            .map(|(_, g)| g)
+            .filter(|g| (Utc::now() - g.created_at).num_seconds() < GRANT_CONFIRM_TIMEOUT_SECS)
+    }
+
+    /// Records a whitelist change in the history table and announces it in the guild's
+    /// log, and by DM to the server owner if the guild opted in.
+    /// `before` is `None` for new entries and `after` is `None` for removed ones.
+    pub async fn record_change(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        actor_id: serenity::UserId,
+        before: Option<EntrySnapshot>,
+        after: Option<EntrySnapshot>,
+    ) -> Result<(), crate::Error> {
+        let (action, target) = match (&before, &after) {
+            (None, Some(after)) => ("create", after.target),
+            (Some(before), None) => ("delete", before.target),
+            (Some(before), Some(after)) if before != after => ("update", after.target),
+            _ => return Ok(()),
+        };
+
+        whitelist_history::ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            actor_id: Set(actor_id.get() as i64),
+            action: Set(action.to_string()),
+            target_type: Set(target.kind().to_string()),
+            target_id: Set(target.id() as i64),
+            before: Set(before.as_ref().map(serde_json::to_value).transpose()?),
+            after: Set(after.as_ref().map(serde_json::to_value).transpose()?),
+            created_at: Set(Utc::now().into()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        info!(
+            "Whitelist entry {} {}d by {} in guild {}",
+            target.mention(),
+            action,
+            actor_id.get(),
+            guild_id.get()
+        );
+
+        let l10n = self.l10n.get_l10n_for_guild(guild_id, &self.db).await;
+        let before_text = before
+            .as_ref()
+            .map(|e| describe_entry(e, &l10n))
+            .unwrap_or_else(|| l10n.t("log-val-none", None));
+        let after_text = after
+            .as_ref()
+            .map(|e| describe_entry(e, &l10n))
+            .unwrap_or_else(|| l10n.t("log-val-none", None));
+
+        let mut args = FluentArgs::new();
+        args.set("actor", format!("<@{}>", actor_id.get()));
+        args.set("target", target.mention());
+        let title_key = format!("log-whitelist-change-title-{}", action);
+
+        let module = after
+            .as_ref()
+            .or(before.as_ref())
+            .and_then(|e| e.module_type);
+        if let Err(e) = self
+            .logger
+            .log_action(
+                guild_id,
+                module,
+                None,
+                LogLevel::Warn,
+                l10n.text(&title_key, None),
+                l10n.text("log-whitelist-change-desc", Some(&args)),
+                vec![
+                    (l10n.text("log-field-before", None), before_text.clone()),
+                    (l10n.text("log-field-after", None), after_text.clone()),
+                ],
+            )
+            .await
+        {
+            error!("Failed to log whitelist change: {:?}", e);
+        }
+
+        let owner_dm = self
+            .config_cache
+            .guild_config(guild_id)
+            .await?
+            .is_some_and(|c| c.whitelist_owner_dm);
+        if !owner_dm {
+            return Ok(());
+        }
+
+        let owner_id = match ctx.cache.guild(guild_id).map(|g| g.owner_id) {
+            Some(id) => id,
+            None => ctx.http.get_guild(guild_id).await?.owner_id,
+        };
+        if owner_id == actor_id {
+            return Ok(());
+        }
+
+        let guild_name = ctx
+            .cache
+            .guild(guild_id)
+            .map(|g| g.name.to_string())
+            .unwrap_or_else(|| guild_id.get().to_string());
+        args.set("guild", guild_name);
+        let text = format!(
+            "## {}\n{}\n\n**{}**: {}\n**{}**: {}",
+            l10n.t(&title_key, None),
+            l10n.t("whitelist-change-dm", Some(&args)),
+            l10n.t("log-field-before", None),
+            before_text,
+            l10n.t("log-field-after", None),
+            after_text,
+        );
+        if let Err(e) = owner_id
+            .direct_message(
+                &ctx.http,
+                serenity::CreateMessage::new()
+                    .components(vec![serenity::CreateComponent::Container(
+                        serenity::CreateContainer::new(vec![
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(text),
+                            ),
+                        ]),
+                    )])
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2),
+            )
+            .await
+        {
+            warn!("Failed to DM owner about whitelist change: {:?}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Starts the background task that removes expired temporary whitelist entries.
+    /// Expired entries are already ignored by `get_whitelist_level`; this keeps the
+    /// tables clean and records the expiry in the guild's log.
+    pub fn start_expiry_runner(&self) {
+        let db = self.db.clone();
+        let logger = self.logger.clone();
+        let l10n = self.l10n.clone();
+        tokio::spawn(async move {
+            info!("Whitelist expiry runner started.");
+            loop {
+                sleep(Duration::from_secs(60)).await;
+                let now = chrono::Utc::now();
+
+                match whitelist_user::Entity::find()
+                    .filter(whitelist_user::Column::ExpiresAt.lte(now))
+                    .all(&db)
+                    .await
+                {
+                    Ok(entries) => {
+                        for entry in entries {
+                            if let Err(e) = whitelist_user::Entity::delete_by_id(entry.id)
+                                .exec(&db)
+                                .await
+                            {
+                                error!(
+                                    "Failed to delete expired whitelist user {}: {:?}",
+                                    entry.id, e
+                                );
+                                continue;
+                            }
+                            log_expiry(
+                                &db,
+                                &logger,
+                                &l10n,
+                                serenity::GuildId::new(entry.guild_id as u64),
+                                entry.module_type,
+                                format!("<@{}>", entry.user_id),
+                                entry.level,
+                            )
+                            .await;
+                        }
+                    }
+                    Err(e) => error!("Failed to fetch expired whitelist users: {:?}", e),
+                }
+
+                match whitelist_role::Entity::find()
+                    .filter(whitelist_role::Column::ExpiresAt.lte(now))
+                    .all(&db)
+                    .await
+                {
+                    Ok(entries) => {
+                        for entry in entries {
+                            if let Err(e) = whitelist_role::Entity::delete_by_id(entry.id)
+                                .exec(&db)
+                                .await
+                            {
+                                error!(
+                                    "Failed to delete expired whitelist role {}: {:?}",
+                                    entry.id, e
+                                );
+                                continue;
+                            }
+                            log_expiry(
+                                &db,
+                                &logger,
+                                &l10n,
+                                serenity::GuildId::new(entry.guild_id as u64),
+                                entry.module_type,
+                                format!("<@&{}>", entry.role_id),
+                                entry.level,
+                            )
+                            .await;
+                        }
+                    }
+                    Err(e) => error!("Failed to fetch expired whitelist roles: {:?}", e),
+                }
+            }
+        });
     }
 
     /// Determines the whitelist level for a user in a guild context.
     /// Checks both implicit (role hierarchy) and explicit (database) whitelists.
+    /// `action` is the `punish_when` value of the action being checked; entries scoped
+    /// to specific actions are skipped when it is `None` or not in their scope.
     /// Returns the highest level found, or None if not whitelisted.
     pub async fn get_whitelist_level(
         &self,
@@ -22,121 +562,365 @@ impl WhitelistService {
         guild_id: serenity::GuildId,
         user_id: serenity::UserId,
         module: ModuleType,
+        action: Option<&str>,
     ) -> Result<Option<WhitelistLevel>, crate::Error> {
-        let mut level = None;
+        Ok(self
+            .load_grants(ctx, guild_id, user_id)
+            .await?
+            .resolve(module, action)
+            .map(|(level, _)| level))
+    }
 
-        // 0. Server Owner Check
-        let cached_owner = ctx.cache.guild(guild_id).map(|g| g.owner_id);
-        let is_owner = if let Some(owner_id) = cached_owner {
-            owner_id == user_id
-        } else {
-            match ctx.http.get_guild(guild_id).await {
-                Ok(guild) => guild.owner_id == user_id,
-                Err(_) => false,
-            }
-        };
+    /// Loads everything that can whitelist a user, so the level can be resolved for
+    /// any module and action without further requests.
+    pub async fn load_grants(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        user_id: serenity::UserId,
+    ) -> Result<WhitelistGrants, crate::Error> {
+        let guild = self.guild_info(ctx, guild_id).await;
 
-        if is_owner {
-            return Ok(Some(WhitelistLevel::Head));
+        // 0. Server Owner Check
+        if guild.as_ref().is_some_and(|g| g.owner_id == user_id) {
+            return Ok(WhitelistGrants {
+                owner: true,
+                implicit: None,
+                users: vec![],
+                roles: vec![],
+            });
         }
 
         // 1. Implicit Checks (Hierarchy)
-        if let Ok(member) = guild_id.member(&ctx.http, user_id).await {
+        let member_roles = self.member_roles(ctx, guild_id, user_id).await;
+        let mut implicit = None;
+        if let (Some(guild), Some(member_roles)) = (&guild, &member_roles) {
             let bot_id = ctx.cache.current_user().id;
-            if let Ok(bot_member) = guild_id.member(&ctx.http, bot_id).await {
-                // We need to resolve role positions.
-                // Assuming we can get roles from cache or we need to fetch guild roles.
-                // For simplicity, let's try cache first, then http.
-                let roles = if let Some(g) = ctx.cache.guild(guild_id) {
-                    g.roles.clone()
-                } else {
-                    guild_id.roles(&ctx.http).await.unwrap_or_default()
-                };
-
-                let get_position = |member: &serenity::Member| -> i16 {
-                    member
-                        .roles
-                        .iter()
-                        .filter_map(|r| roles.get(r).map(|role| role.position))
-                        .max()
-                        .unwrap_or(0)
-                    // If cached member has no roles but is owner? Owner check requires Guild object.
-                };
-
-                let user_position = get_position(&member);
-                let bot_position = get_position(&bot_member);
-
-                if user_position > bot_position {
+            if let Some(bot_roles) = self.member_roles(ctx, guild_id, bot_id).await {
+                if guild.highest_position(member_roles) > guild.highest_position(&bot_roles) {
                     // User is above bot
-                    let has_admin = member
-                        .permissions
-                        .map_or(false, |p| p.contains(serenity::Permissions::ADMINISTRATOR));
-
-                    let implicit_level = if has_admin {
-                        WhitelistLevel::Admin
+                    implicit = Some(if guild.has_administrator(member_roles) {
+                        (WhitelistLevel::Admin, WhitelistSource::Administrator)
                     } else {
-                        WhitelistLevel::Invulnerable
-                    };
-
-                    level = self.merge_levels(level, Some(implicit_level));
+                        (WhitelistLevel::Invulnerable, WhitelistSource::Hierarchy)
+                    });
                 }
             }
         }
 
         // 2. Explicit User Whitelist
-        // Check for specific module OR global (null)
-        let user_whitelists = whitelist_user::Entity::find()
+        let users = whitelist_user::Entity::find()
             .filter(whitelist_user::Column::GuildId.eq(guild_id.get() as i64))
             .filter(whitelist_user::Column::UserId.eq(user_id.get() as i64))
+            .filter(not_expired_user())
             .all(&self.db)
             .await?;
 
-        for w in user_whitelists {
-            if w.module_type.is_none() || w.module_type == Some(module) {
-                level = self.merge_levels(level, Some(w.level));
-            }
-        }
-
         // 3. Explicit Role Whitelist
-        if let Ok(member) = guild_id.member(&ctx.http, user_id).await {
-            let role_ids: Vec<i64> = member.roles.iter().map(|r| r.get() as i64).collect();
+        let mut roles = vec![];
+        if let Some(member_roles) = &member_roles {
+            let role_ids: Vec<i64> = member_roles.iter().map(|r| r.get() as i64).collect();
 
             if !role_ids.is_empty() {
-                let role_whitelists = whitelist_role::Entity::find()
+                roles = whitelist_role::Entity::find()
                     .filter(whitelist_role::Column::GuildId.eq(guild_id.get() as i64))
                     .filter(whitelist_role::Column::RoleId.is_in(role_ids))
+                    .filter(not_expired_role())
                     .all(&self.db)
                     .await?;
-
-                for w in role_whitelists {
-                    if w.module_type.is_none() || w.module_type == Some(module) {
-                        level = self.merge_levels(level, Some(w.level));
-                    }
-                }
             }
         }
 
-        Ok(level)
+        Ok(WhitelistGrants {
+            owner: false,
+            implicit,
+            users,
+            roles,
+        })
     }
+}
 
-    /// Helper to merge levels, keeping the highest privilege.
-    /// Head > Admin > Invulnerable
-    fn merge_levels(
+/// Where a whitelist level comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitelistSource {
+    /// The user owns the server
+    Owner,
+    /// The user's highest role is above the bot's
+    Hierarchy,
+    /// The user is above the bot and has the Administrator permission
+    Administrator,
+    /// An explicit entry for the user
+    User(Option<ModuleType>),
+    /// An explicit entry for one of the user's roles
+    Role(u64, Option<ModuleType>),
+}
+
+/// Everything that whitelists a user in a guild, as loaded by
+/// [`WhitelistService::load_grants`].
+#[derive(Debug, Clone)]
+pub struct WhitelistGrants {
+    pub owner: bool,
+    pub implicit: Option<(WhitelistLevel, WhitelistSource)>,
+    pub users: Vec<whitelist_user::Model>,
+    pub roles: Vec<whitelist_role::Model>,
+}
+
+impl WhitelistGrants {
+    /// Resolves the highest level for a module and action, and where it comes from.
+    /// When several grants give the same level, the first one checked wins:
+    /// owner, hierarchy, user entries, then role entries.
+    pub fn resolve(
         &self,
-        current: Option<WhitelistLevel>,
-        new: Option<WhitelistLevel>,
-    ) -> Option<WhitelistLevel> {
-        match (current, new) {
-            (Some(c), Some(n)) => Some(if c == WhitelistLevel::Head || n == WhitelistLevel::Head {
-                WhitelistLevel::Head
-            } else if c == WhitelistLevel::Admin || n == WhitelistLevel::Admin {
-                WhitelistLevel::Admin
-            } else {
-                WhitelistLevel::Invulnerable
-            }),
-            (Some(c), None) => Some(c),
-            (None, Some(n)) => Some(n),
-            (None, None) => None,
+        module: ModuleType,
+        action: Option<&str>,
+    ) -> Option<(WhitelistLevel, WhitelistSource)> {
+        if self.owner {
+            return Some((WhitelistLevel::Head, WhitelistSource::Owner));
         }
+
+        let users = self
+            .users
+            .iter()
+            .filter(|w| w.module_type.is_none() || w.module_type == Some(module))
+            .filter(|w| covers_action(&w.actions, action))
+            .map(|w| (w.level, WhitelistSource::User(w.module_type)));
+        let roles = self
+            .roles
+            .iter()
+            .filter(|w| w.module_type.is_none() || w.module_type == Some(module))
+            .filter(|w| covers_action(&w.actions, action))
+            .map(|w| {
+                (
+                    w.level,
+                    WhitelistSource::Role(w.role_id as u64, w.module_type),
+                )
+            });
+
+        self.implicit
+            .into_iter()
+            .chain(users)
+            .chain(roles)
+            .fold(None, |best, (level, source)| match best {
+                Some((best_level, _)) if level_rank(best_level) >= level_rank(level) => best,
+                _ => Some((level, source)),
+            })
+    }
+}
+
+/// Head > Admin > Invulnerable
+fn level_rank(level: WhitelistLevel) -> u8 {
+    match level {
+        WhitelistLevel::Head => 3,
+        WhitelistLevel::Admin => 2,
+        WhitelistLevel::Invulnerable => 1,
+    }
+}
+
+/// One-line summary of an entry's level, module, action scope and expiry.
+pub fn describe_entry(entry: &EntrySnapshot, l10n: &L10nProxy) -> String {
+    let mut parts = vec![match entry.level {
+        WhitelistLevel::Head => l10n.t("config-level-head", None),
+        WhitelistLevel::Admin => l10n.t("config-level-admin", None),
+        WhitelistLevel::Invulnerable => l10n.t("config-level-invulnerable", None),
+    }];
+
+    parts.push(match entry.module_type {
+        Some(module) => format!("`{}`", module),
+        None => l10n.t("config-whitelist-global-btn", None),
+    });
+
+    if !entry.actions.is_empty() {
+        let mut args = FluentArgs::new();
+        args.set(
+            "actions",
+            entry
+                .actions
+                .iter()
+                .map(|a| l10n.t(&format!("config-whitelist-action-{}", a), None))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        parts.push(l10n.t("config-whitelist-actions", Some(&args)));
+    }
+
+    if let Some(expires_at) = entry.expires_at {
+        let mut args = FluentArgs::new();
+        args.set("time", format!("<t:{}:R>", expires_at.timestamp()));
+        parts.push(l10n.t("config-whitelist-expires", Some(&args)));
+    }
+
+    parts.join(" · ")
+}
+
+/// Records the removal of an expired entry in the guild's log.
+async fn log_expiry(
+    db: &DatabaseConnection,
+    logger: &LoggerService,
+    l10n: &Arc<LocalizationManager>,
+    guild_id: serenity::GuildId,
+    module: Option<ModuleType>,
+    target: String,
+    level: WhitelistLevel,
+) {
+    info!(
+        "Whitelist entry {} expired in guild {}",
+        target,
+        guild_id.get()
+    );
+
+    let l10n = l10n.get_l10n_for_guild(guild_id, db).await;
+    let level_label = match level {
+        WhitelistLevel::Head => l10n.t("config-level-head", None),
+        WhitelistLevel::Admin => l10n.t("config-level-admin", None),
+        WhitelistLevel::Invulnerable => l10n.t("config-level-invulnerable", None),
+    };
+
+    let mut args = FluentArgs::new();
+    args.set("target", target.clone());
+    args.set("level", level_label);
+
+    if let Err(e) = logger
+        .log_action(
+            guild_id,
+            module,
+            None,
+            LogLevel::Audit,
+            l10n.text("log-whitelist-expired-title", None),
+            l10n.text("log-whitelist-expired-desc", Some(&args)),
+            vec![],
+        )
+        .await
+    {
+        error!("Failed to log whitelist expiry: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user_entry(
+        level: WhitelistLevel,
+        module_type: Option<ModuleType>,
+        actions: serde_json::Value,
+    ) -> whitelist_user::Model {
+        whitelist_user::Model {
+            id: 1,
+            guild_id: 1,
+            user_id: 2,
+            level,
+            module_type,
+            expires_at: None,
+            actions,
+        }
+    }
+
+    fn role_entry(
+        level: WhitelistLevel,
+        module_type: Option<ModuleType>,
+        actions: serde_json::Value,
+    ) -> whitelist_role::Model {
+        whitelist_role::Model {
+            id: 1,
+            guild_id: 1,
+            role_id: 3,
+            level,
+            module_type,
+            expires_at: None,
+            actions,
+        }
+    }
+
+    fn grants(
+        users: Vec<whitelist_user::Model>,
+        roles: Vec<whitelist_role::Model>,
+    ) -> WhitelistGrants {
+        WhitelistGrants {
+            owner: false,
+            implicit: None,
+            users,
+            roles,
+        }
+    }
+
+    #[test]
+    fn test_covers_action() {
+        assert!(covers_action(&json!([]), None));
+        assert!(covers_action(&json!([]), Some("ban")));
+        assert!(covers_action(&json!(null), Some("ban")));
+        assert!(covers_action(&json!(["ban", "kick"]), Some("kick")));
+        assert!(!covers_action(&json!(["ban", "kick"]), Some("unban")));
+        assert!(!covers_action(&json!(["ban"]), None));
+    }
+
+    #[test]
+    fn test_resolve_owner() {
+        let grants = WhitelistGrants {
+            owner: true,
+            ..grants(vec![], vec![])
+        };
+        assert_eq!(
+            grants.resolve(ModuleType::RoleProtection, Some("delete")),
+            Some((WhitelistLevel::Head, WhitelistSource::Owner))
+        );
+    }
+
+    #[test]
+    fn test_resolve_highest_level() {
+        let grants = grants(
+            vec![user_entry(WhitelistLevel::Invulnerable, None, json!([]))],
+            vec![role_entry(
+                WhitelistLevel::Admin,
+                Some(ModuleType::ChannelProtection),
+                json!([]),
+            )],
+        );
+        assert_eq!(
+            grants.resolve(ModuleType::ChannelProtection, None),
+            Some((
+                WhitelistLevel::Admin,
+                WhitelistSource::Role(3, Some(ModuleType::ChannelProtection))
+            ))
+        );
+        assert_eq!(
+            grants.resolve(ModuleType::RoleProtection, None),
+            Some((WhitelistLevel::Invulnerable, WhitelistSource::User(None)))
+        );
+    }
+
+    #[test]
+    fn test_resolve_scoped_actions() {
+        let grants = grants(
+            vec![user_entry(
+                WhitelistLevel::Admin,
+                Some(ModuleType::ModerationProtection),
+                json!(["ban"]),
+            )],
+            vec![],
+        );
+        assert_eq!(
+            grants
+                .resolve(ModuleType::ModerationProtection, Some("ban"))
+                .map(|(level, _)| level),
+            Some(WhitelistLevel::Admin)
+        );
+        assert_eq!(
+            grants.resolve(ModuleType::ModerationProtection, Some("prune")),
+            None
+        );
+        assert_eq!(grants.resolve(ModuleType::ModerationProtection, None), None);
+    }
+
+    #[test]
+    fn test_resolve_same_level_prefers_users() {
+        let grants = grants(
+            vec![user_entry(WhitelistLevel::Head, None, json!([]))],
+            vec![role_entry(WhitelistLevel::Head, None, json!([]))],
+        );
+        assert_eq!(
+            grants.resolve(ModuleType::RoleProtection, None),
+            Some((WhitelistLevel::Head, WhitelistSource::User(None)))
+        );
     }
 }